@@ -27,7 +27,12 @@ use crate::{
     errors,
     experiment::{EventLoopAction, ExperimentManager, Monitor, WindowOptions},
     input::Event,
-    visual::window::{PhysicalScreen, Window, WindowState},
+    visual::{
+        calibration::MonitorCalibration,
+        color2::ColorFormat,
+        rig,
+        window::{Window, WindowState},
+    },
     EventTryFrom,
 };
 
@@ -134,7 +139,12 @@ impl App {
     }
 
     /// Create a new window with the given options.
-    pub fn create_window(&self, window_options: &WindowOptions, event_loop: &ActiveEventLoop) -> Window {
+    pub fn create_window(
+        &self,
+        window_options: &WindowOptions,
+        color_format: ColorFormat,
+        event_loop: &ActiveEventLoop,
+    ) -> Window {
         let window_attributes = WinitWindow::default_attributes()
             .with_title("Winit window")
             .with_transparent(false);
@@ -172,8 +182,22 @@ impl App {
         let _swapchain_formats = adapter.get_texture_format_features(TextureFormat::Bgra8Unorm);
 
         let swapchain_capabilities = surface.get_capabilities(adapter);
-        let swapchain_format = TextureFormat::Bgra8Unorm;
-        let swapchain_view_format = vec![TextureFormat::Bgra8Unorm];
+        // fall back to 8 bit if the requested format is not supported
+        let color_format = if swapchain_capabilities
+            .formats
+            .contains(&color_format.to_wgpu_surface_texture_format())
+        {
+            color_format
+        } else {
+            log::warn!(
+                "Color format {:?} is not supported by the surface, falling back to {:?}",
+                color_format,
+                ColorFormat::default()
+            );
+            ColorFormat::default()
+        };
+        let swapchain_format = color_format.to_wgpu_surface_texture_format();
+        let swapchain_view_format = vec![swapchain_format];
 
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
@@ -196,14 +220,28 @@ impl App {
 
         winit_window.set_fullscreen(Some(winit::window::Fullscreen::Borderless(Some(mon_handle.clone()))));
 
-        let wgpu_renderer = pollster::block_on(renderer::wgpu_renderer::WgpuRenderer::new(
+        let mut wgpu_renderer = pollster::block_on(renderer::wgpu_renderer::WgpuRenderer::new(
             winit_window.clone(),
             instance,
             device,
             queue,
             swapchain_format,
+            color_format.output_color_space(),
         ));
 
+        // load the calibration of the monitor, if there is one
+        let calibration = MonitorCalibration::find(&mon_name);
+        if let Some(calibration) = &calibration {
+            let applied = calibration.gamma_correction().and_then(|gamma_correction| {
+                wgpu_renderer
+                    .set_gamma_correction(queue, gamma_correction)
+                    .map_err(errors::psydkError::CalibrationError)
+            });
+            if let Err(e) = applied {
+                log::warn!("Failed to apply calibration for monitor {}: {}", mon_name, e);
+            }
+        }
+
         // create the renderer
         let mut renderer =
             self.renderer_factory
@@ -211,9 +249,10 @@ impl App {
 
         let winit_id = winit_window.id();
 
-        // set width of the screen to 30 cm
-        let width_mm = 300.0;
-        let viewing_distance = 1000.0;
+        // the physical size of the screen from the rig profile or the operating system
+        let mon_size = mon_handle.size();
+        let physical_screen = rig::physical_screen(&mon_handle, &mon_name, (mon_size.width, mon_size.height));
+        log::debug!("Physical screen of monitor {}: {:?}", mon_name, physical_screen);
 
         // create a pwindow
         let window_state = WindowState {
@@ -225,7 +264,9 @@ impl App {
             mouse_cursor_visible: true,
             mouse_position: None,
             size: size.into(),
-            physical_screen: PhysicalScreen::new(size.width, width_mm, viewing_distance),
+            physical_screen,
+            monitor_name: mon_name,
+            calibration,
             event_handlers: HashMap::new(), // TODO this should be a weak reference
         };
 
@@ -322,8 +363,8 @@ impl ApplicationHandler<()> for App {
         println!("received user event");
         // check if we need to create a new window
         self.action_receiver.try_recv().map(|action| match action {
-            EventLoopAction::CreateNewWindow(options, sender) => {
-                let window = self.create_window(&options, event_loop);
+            EventLoopAction::CreateNewWindow(options, color_format, sender) => {
+                let window = self.create_window(&options, color_format, event_loop);
                 self.windows.push(window.clone());
                 sender.send(window).unwrap();
             }
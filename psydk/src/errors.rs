@@ -60,6 +60,14 @@ pub enum psydkError {
     // #[error("The index {0} is out of bounds for an array or vector of length {1}.")]
     // IndexOutOfBoundsError(usize, usize),
 
+    // calibration errors
+    #[error("{0}")]
+    CalibrationError(String),
+
+    // color errors
+    #[error("Colors specified in luminance or a device-independent color space need to be resolved with eval(window) first.")]
+    UnresolvedColorError,
+
     // single image error
     #[error("Only one image was provided. This is currently not supported.")]
     SingleImageError,
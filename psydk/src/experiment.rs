@@ -12,11 +12,15 @@ use pyo3::{
 use renderer::{cosmic_text, renderer::RendererFactory};
 use winit::event_loop::EventLoopProxy;
 
-use crate::{app::App, errors, visual::window::Window};
+use crate::{
+    app::App,
+    errors,
+    visual::{color2::ColorFormat, window::Window},
+};
 
 #[derive(Dbg)]
 pub enum EventLoopAction {
-    CreateNewWindow(WindowOptions, Sender<Window>),
+    CreateNewWindow(WindowOptions, ColorFormat, Sender<Window>),
     GetAvailableMonitors(Sender<Vec<Monitor>>),
 }
 
@@ -158,10 +162,10 @@ impl ExperimentManager {
     /// a new UserEvent to the event loop and wait until the winit window
     /// has been created. Then it will setup the wgpu device and surface and
     /// return a new Window object.
-    pub fn create_window(&self, window_options: &WindowOptions) -> Window {
+    pub fn create_window(&self, window_options: &WindowOptions, color_format: ColorFormat) -> Window {
         // set up window by dispatching a new CreateNewWindow action
         let (sender, receiver) = channel();
-        let action = EventLoopAction::CreateNewWindow(window_options.clone(), sender);
+        let action = EventLoopAction::CreateNewWindow(window_options.clone(), color_format, sender);
 
         // send action
         println!("Sending action");
@@ -177,7 +181,7 @@ impl ExperimentManager {
 
     /// Create a new window. This is a convenience function that creates a
     /// window with the default options.
-    pub fn create_default_window(&self, fullscreen: bool, monitor: Option<u32>) -> Window {
+    pub fn create_default_window(&self, fullscreen: bool, monitor: Option<u32>, color_format: ColorFormat) -> Window {
         // select monitor 1 if available
         // find all monitors available
 
@@ -190,10 +194,13 @@ impl ExperimentManager {
             .unwrap_or(monitors.first().expect("No monitor found - this should not happen"));
 
         println!("Creating default window on monitor {:?}", monitor);
-        self.create_window(&WindowOptions::FullscreenHighestResolution {
-            monitor: Some(monitor.clone()),
-            refresh_rate: None,
-        })
+        self.create_window(
+            &WindowOptions::FullscreenHighestResolution {
+                monitor: Some(monitor.clone()),
+                refresh_rate: None,
+            },
+            color_format,
+        )
     }
 
     /// Retrive available monitors.
@@ -214,7 +221,7 @@ impl ExperimentManager {
 #[pymethods]
 impl ExperimentManager {
     #[pyo3(name = "create_default_window")]
-    #[pyo3(signature = (fullscreen = false, monitor = None))]
+    #[pyo3(signature = (fullscreen = false, monitor = None, color_format = None))]
     /// Create a new window. This is a convenience function that creates a
     /// window with the default options.
     ///
@@ -230,13 +237,45 @@ impl ExperimentManager {
     ///   Whether to create a fullscreen window. Defaults to `false`.
     /// monitor : int, optional
     ///   The index of the monitor to use. Defaults to 0.
+    /// color_format : str, optional
+    ///   The pixel format of the window. One of "rgba8" (default),
+    ///   "display_p3" for wide-gamut output, "rgb10a2" for 10-bit output or
+    ///   "rgba16_float" for extended-range output. Falls back to "rgba8" if the
+    ///   format is not supported.
+    ///
+    /// Returns
+    /// -------
+    /// Window
+    ///  The new window.
+    fn py_create_default_window(
+        &self,
+        fullscreen: bool,
+        monitor: Option<u32>,
+        color_format: Option<ColorFormat>,
+    ) -> Window {
+        self.create_default_window(fullscreen, monitor, color_format.unwrap_or_default())
+    }
+
+    #[pyo3(name = "create_window")]
+    #[pyo3(signature = (window_options, color_format = None))]
+    /// Create a new window with the given options.
+    ///
+    /// Parameters
+    /// ----------
+    /// window_options : WindowOptions
+    ///   The options of the window.
+    /// color_format : str, optional
+    ///   The pixel format of the window. One of "rgba8" (default),
+    ///   "display_p3" for wide-gamut output, "rgb10a2" for 10-bit output or
+    ///   "rgba16_float" for extended-range output. Falls back to "rgba8" if the
+    ///   format is not supported.
     ///
     /// Returns
     /// -------
     /// Window
     ///  The new window.
-    fn py_create_default_window(&self, fullscreen: bool, monitor: Option<u32>) -> Window {
-        self.create_default_window(fullscreen, monitor)
+    fn py_create_window(&self, window_options: WindowOptions, color_format: Option<ColorFormat>) -> Window {
+        self.create_window(&window_options, color_format.unwrap_or_default())
     }
 
     #[pyo3(name = "get_available_monitors")]
@@ -8,7 +8,7 @@ use std::{
 
 use async_channel::{bounded, Receiver, Sender};
 use derive_debug::Dbg;
-use experiment::{py_run_experiment, ExperimentManager};
+use experiment::{py_run_experiment, ExperimentManager, Monitor, WindowOptions};
 use futures_lite::{future::block_on, Future};
 use pyo3::{prelude::*, py_run};
 use renderer::wgpu_renderer;
@@ -64,6 +64,8 @@ macro_rules! new_submodule {
 fn psydk(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(py_run_experiment, m)?);
     m.add_class::<ExperimentManager>()?;
+    m.add_class::<Monitor>()?;
+    m.add_class::<WindowOptions>()?;
 
     let m_visual = {
         let m = new_submodule!(m, "psydk", "visual");
@@ -72,6 +74,7 @@ fn psydk(m: &Bound<'_, PyModule>) -> PyResult<()> {
             let m = new_submodule!(m, "psydk.visual", "stimuli");
             m.add_class::<visual::stimuli::PyStimulus>()?;
             m.add_class::<visual::stimuli::gabor::PyGaborStimulus>()?;
+            m.add_class::<visual::stimuli::group::PyGroupStimulus>()?;
             m.add_class::<visual::stimuli::image::PyImageStimulus>()?;
             m.add_class::<visual::stimuli::shape::PyShapeStimulus>()?;
             m.add_class::<visual::stimuli::pattern::PyPatternStimulus>()?;
@@ -85,7 +88,10 @@ fn psydk(m: &Bound<'_, PyModule>) -> PyResult<()> {
             let m = new_submodule!(m, "psydk.visual", "geometry");
             m.add_class::<visual::geometry::Transformation2D>()?;
             m.add_class::<visual::geometry::Shape>()?;
+            m.add_class::<visual::geometry::PathCommand>()?;
             m.add_class::<visual::geometry::Size>()?;
+            m.add_class::<visual::geometry::Rect>()?;
+            m.add_class::<visual::geometry::OrientedRect>()?;
             m
         };
 
@@ -98,16 +104,63 @@ fn psydk(m: &Bound<'_, PyModule>) -> PyResult<()> {
             m.add_function(wrap_pyfunction!(visual::color::py_rgba, &m)?)?;
             m.add_function(wrap_pyfunction!(visual::color::py_linrgb, &m)?)?;
             m.add_function(wrap_pyfunction!(visual::color::py_linrgba, &m)?)?;
+            m.add_function(wrap_pyfunction!(visual::color::py_lum, &m)?)?;
+            m.add_function(wrap_pyfunction!(visual::color::py_lum_rgb, &m)?)?;
+            m.add_function(wrap_pyfunction!(visual::color::py_xyz, &m)?)?;
+            m.add_function(wrap_pyfunction!(visual::color::py_xyy, &m)?)?;
+            m.add_function(wrap_pyfunction!(visual::color::py_lab, &m)?)?;
+            m.add_function(wrap_pyfunction!(visual::color::py_lch, &m)?)?;
+            m.add_function(wrap_pyfunction!(visual::color::py_dkl, &m)?)?;
             m
         };
 
         m.add_submodule(&m_color)?;
 
+        let m_fill = {
+            let m = new_submodule!(m, "psydk.visual", "fill");
+            m.add_class::<visual::fill::Gradient>()?;
+            m
+        };
+
+        m.add_submodule(&m_fill)?;
+
+        let m_layout = {
+            let m = new_submodule!(m, "psydk.visual", "layout");
+            m.add_function(wrap_pyfunction!(visual::layout::py_grid, &m)?)?;
+            m.add_function(wrap_pyfunction!(visual::layout::py_ring, &m)?)?;
+            m.add_function(wrap_pyfunction!(visual::layout::py_random, &m)?)?;
+            m
+        };
+
+        m.add_submodule(&m_layout)?;
+
+        m.add_function(wrap_pyfunction!(visual::calibration::py_fit_calibration, &m)?)?;
+
         m
     };
 
     m.add_submodule(&m_visual)?;
 
+    let m_size = {
+        let m = new_submodule!(m, "psydk", "size");
+        m.add_class::<visual::geometry::Size>()?;
+        m.add_class::<visual::geometry::Unit>()?;
+        m.add("px", visual::geometry::Unit::Pixels)?;
+        m.add("pt", visual::geometry::Unit::Points)?;
+        m.add("deg", visual::geometry::Unit::Degrees)?;
+        m.add("sw", visual::geometry::Unit::ViewportWidth)?;
+        m.add("sh", visual::geometry::Unit::ViewportHeight)?;
+        m.add("vw", visual::geometry::Unit::ViewportWidth)?;
+        m.add("vh", visual::geometry::Unit::ViewportHeight)?;
+        m.add("mm", visual::geometry::Unit::Millimeters)?;
+        m.add("cm", visual::geometry::Unit::Centimeters)?;
+        m.add("m", visual::geometry::Unit::Meters)?;
+        m.add("inch", visual::geometry::Unit::Inches)?;
+        m
+    };
+
+    m.add_submodule(&m_size)?;
+
     Ok(())
 }
 
@@ -0,0 +1,488 @@
+use std::path::{Path, PathBuf};
+
+use nalgebra::{DMatrix, DVector};
+use pyo3::{pyfunction, PyResult};
+use renderer::wgpu_renderer::{GammaCorrection, MAX_LUT_SIZE};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::psydkError;
+
+/// The name of the environment variable that can be used to override the
+/// directory in which calibration files are searched.
+pub const CALIBRATION_DIR_ENV: &str = "PSYDK_CALIBRATION_DIR";
+
+/// Luminance measurements of a single channel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelMeasurements {
+    /// The normalized values (0 to 1) that were displayed.
+    pub values: Vec<f32>,
+    /// The measured luminance (in cd/m²) for each value.
+    pub luminances: Vec<f32>,
+}
+
+/// Luminance measurements of the red, green and blue channels.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measurements {
+    pub r: ChannelMeasurements,
+    pub g: ChannelMeasurements,
+    pub b: ChannelMeasurements,
+}
+
+/// The CIE 1931 xy chromaticities of the monitor's primaries.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Primaries {
+    pub red: [f32; 2],
+    pub green: [f32; 2],
+    pub blue: [f32; 2],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub white: Option<[f32; 2]>,
+}
+
+/// The fitted coefficients of the red, green and blue channels.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Coefficients {
+    pub r: Vec<f32>,
+    pub g: Vec<f32>,
+    pub b: Vec<f32>,
+}
+
+/// The calibration of a single monitor. Calibration files can be stored as
+/// JSON or TOML and are matched to monitors by their device name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorCalibration {
+    /// The name of the monitor, as reported by the operating system.
+    pub device_name: String,
+    /// The date of the measurement (ideally in ISO 8601 format).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// The maximum luminance of the monitor (white) in cd/m².
+    pub max_luminance: f32,
+    /// The minimum luminance of the monitor (black) in cd/m².
+    #[serde(default)]
+    pub min_luminance: f32,
+    /// The chromaticities of the primaries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primaries: Option<Primaries>,
+    /// The correction model (see `GammaCorrection::from_coefficients`).
+    pub model: String,
+    /// The coefficients of the correction model.
+    pub coefficients: Coefficients,
+    /// The raw measurements the model was fitted to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub measurements: Option<Measurements>,
+}
+
+impl MonitorCalibration {
+    /// Fits the given model to the measurements and creates a new calibration.
+    /// The black level is estimated as the mean of the lowest luminance of each
+    /// channel, and the maximum luminance by adding up the luminance of the
+    /// three channels on top of the black level.
+    pub fn from_measurements(
+        device_name: impl Into<String>,
+        model: &str,
+        measurements: Measurements,
+        primaries: Option<Primaries>,
+        date: Option<String>,
+    ) -> Result<Self, psydkError> {
+        let channels = [&measurements.r, &measurements.g, &measurements.b];
+        let min_luminance = channels
+            .iter()
+            .map(|c| c.luminances.iter().cloned().fold(f32::INFINITY, f32::min))
+            .sum::<f32>()
+            / 3.0;
+        let max_luminance = min_luminance
+            + channels
+                .iter()
+                .map(|c| c.luminances.iter().cloned().fold(0.0, f32::max) - min_luminance)
+                .sum::<f32>();
+
+        let coefficients = Coefficients {
+            r: fit_model(model, &measurements.r)?,
+            g: fit_model(model, &measurements.g)?,
+            b: fit_model(model, &measurements.b)?,
+        };
+
+        Ok(Self {
+            device_name: device_name.into(),
+            date,
+            max_luminance,
+            min_luminance,
+            primaries,
+            model: model.to_lowercase(),
+            coefficients,
+            measurements: Some(measurements),
+        })
+    }
+
+    /// Loads a calibration from a JSON or TOML file. The format is inferred
+    /// from the file extension (JSON is used if the extension is not `.toml`).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, psydkError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+
+        let calibration = if is_toml(path) {
+            toml::from_str(&contents).map_err(|e| psydkError::CalibrationError(e.to_string()))?
+        } else {
+            serde_json::from_str(&contents).map_err(|e| psydkError::CalibrationError(e.to_string()))?
+        };
+
+        Ok(calibration)
+    }
+
+    /// Saves the calibration to a JSON or TOML file. The format is inferred
+    /// from the file extension (JSON is used if the extension is not `.toml`).
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), psydkError> {
+        let path = path.as_ref();
+
+        let contents = if is_toml(path) {
+            toml::to_string_pretty(self).map_err(|e| psydkError::CalibrationError(e.to_string()))?
+        } else {
+            serde_json::to_string_pretty(self).map_err(|e| psydkError::CalibrationError(e.to_string()))?
+        };
+
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Searches the calibration directory for a calibration of the monitor
+    /// with the given name. Files that can not be parsed are skipped.
+    pub fn find(monitor_name: &str) -> Option<Self> {
+        let dir = calibration_dir()?;
+        let entries = std::fs::read_dir(&dir).ok()?;
+
+        entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| matches!(path.extension().and_then(|e| e.to_str()), Some("json" | "toml")))
+            .filter_map(|path| match Self::load(&path) {
+                Ok(calibration) => Some(calibration),
+                Err(e) => {
+                    log::warn!("Skipping calibration file {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .find(|calibration| calibration.device_name == monitor_name)
+    }
+
+    /// Returns the luminance (in cd/m²) each channel adds on top of the black
+    /// level at its maximum. Without measurements, the luminance range is
+    /// split according to the Rec. 709 luminance coefficients.
+    pub fn channel_luminances(&self) -> [f32; 3] {
+        match &self.measurements {
+            Some(m) => {
+                [&m.r, &m.g, &m.b].map(|c| c.luminances.iter().cloned().fold(0.0, f32::max) - self.min_luminance)
+            }
+            None => [0.2126, 0.7152, 0.0722].map(|w| w * (self.max_luminance - self.min_luminance)),
+        }
+    }
+
+    /// Returns the gamma correction described by this calibration.
+    pub fn gamma_correction(&self) -> Result<GammaCorrection, psydkError> {
+        GammaCorrection::from_coefficients(
+            &self.model.to_lowercase(),
+            &self.coefficients.r,
+            &self.coefficients.g,
+            &self.coefficients.b,
+        )
+        .map_err(psydkError::CalibrationError)
+    }
+}
+
+/// Returns the directory in which calibration files are searched. This is the
+/// value of `PSYDK_CALIBRATION_DIR` if set, and `~/.psydk/calibrations`
+/// otherwise.
+pub fn calibration_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(CALIBRATION_DIR_ENV) {
+        return Some(PathBuf::from(dir));
+    }
+
+    psydk_dir().map(|dir| dir.join("calibrations"))
+}
+
+/// Returns the directory in which psydk stores its settings (`~/.psydk`).
+pub(crate) fn psydk_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".psydk"))
+}
+
+pub(crate) fn is_toml(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("toml"))
+}
+
+/// Fits a correction model to the measurements of a single channel and returns
+/// the coefficients in the order the output shader expects them. Supported
+/// models are "gamma", "polylog4" to "polylog6" and "lut".
+pub fn fit_model(model: &str, measurements: &ChannelMeasurements) -> Result<Vec<f32>, psydkError> {
+    let (values, luminances) = normalized_measurements(measurements)?;
+
+    match model.to_lowercase().as_str() {
+        "gamma" => fit_gamma(&values, &luminances),
+        "polylog4" => fit_polylog(&values, &luminances, 4),
+        "polylog5" => fit_polylog(&values, &luminances, 5),
+        "polylog6" => fit_polylog(&values, &luminances, 6),
+        "lut" => Ok(fit_lut(&values, &luminances, MAX_LUT_SIZE)),
+        _ => Err(psydkError::CalibrationError(format!(
+            "Can not fit model {}. Expected one of gamma, polylog4, polylog5, polylog6 or lut.",
+            model
+        ))),
+    }
+}
+
+/// Sorts the measurements by value and rescales the luminances to the range
+/// 0 to 1 (the luminance at the lowest value becomes 0, at the highest value 1).
+fn normalized_measurements(measurements: &ChannelMeasurements) -> Result<(Vec<f64>, Vec<f64>), psydkError> {
+    if measurements.values.len() != measurements.luminances.len() {
+        return Err(psydkError::CalibrationError(format!(
+            "Got {} values but {} luminances.",
+            measurements.values.len(),
+            measurements.luminances.len()
+        )));
+    }
+
+    let mut points: Vec<(f64, f64)> = measurements
+        .values
+        .iter()
+        .zip(&measurements.luminances)
+        .map(|(&v, &l)| (v as f64, l as f64))
+        .collect();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let (min, max) = match (points.first(), points.last()) {
+        (Some(first), Some(last)) if last.1 > first.1 => (first.1, last.1),
+        _ => {
+            return Err(psydkError::CalibrationError(
+                "The measured luminance does not increase with the displayed value.".to_string(),
+            ))
+        }
+    };
+
+    Ok(points.into_iter().map(|(v, l)| (v, (l - min) / (max - min))).unzip())
+}
+
+/// Fits `L = v^gamma` by least squares in log-log space.
+fn fit_gamma(values: &[f64], luminances: &[f64]) -> Result<Vec<f32>, psydkError> {
+    let (num, den) = values
+        .iter()
+        .zip(luminances)
+        .filter(|(&v, &l)| v > 0.0 && v < 1.0 && l > 0.0 && l < 1.0)
+        .fold((0.0, 0.0), |(num, den), (v, l)| {
+            (num + v.ln() * l.ln(), den + v.ln() * v.ln())
+        });
+
+    if den == 0.0 {
+        return Err(psydkError::CalibrationError(
+            "At least one measurement between the lowest and highest value is required.".to_string(),
+        ));
+    }
+
+    Ok(vec![(num / den) as f32])
+}
+
+/// Fits `v = c0 + c1 ln(L) + ... + cn ln(L)^n` by least squares, i.e., the
+/// inverse of the monitor's transfer function as evaluated by the shader.
+fn fit_polylog(values: &[f64], luminances: &[f64], degree: usize) -> Result<Vec<f32>, psydkError> {
+    let (xs, ys): (Vec<f64>, Vec<f64>) = values
+        .iter()
+        .zip(luminances)
+        .filter(|(_, &l)| l > 0.0)
+        .map(|(&v, &l)| (l.ln(), v))
+        .unzip();
+
+    if xs.len() <= degree {
+        return Err(psydkError::CalibrationError(format!(
+            "At least {} measurements above the lowest luminance are required to fit polylog{}, got {}.",
+            degree + 1,
+            degree,
+            xs.len()
+        )));
+    }
+
+    let a = DMatrix::from_fn(xs.len(), degree + 1, |i, j| xs[i].powi(j as i32));
+    let b = DVector::from_vec(ys);
+
+    let solution = a
+        .svd(true, true)
+        .solve(&b, 1e-12)
+        .map_err(|e| psydkError::CalibrationError(e.to_string()))?;
+
+    Ok(solution.iter().map(|&c| c as f32).collect())
+}
+
+/// Builds a lookup table with `size` entries by inverting the measured transfer
+/// function with linear interpolation. Measurements where the luminance does
+/// not increase are ignored.
+fn fit_lut(values: &[f64], luminances: &[f64], size: usize) -> Vec<f32> {
+    let mut points: Vec<(f64, f64)> = Vec::with_capacity(values.len());
+    for (&v, &l) in values.iter().zip(luminances) {
+        if points.last().map_or(true, |&(_, last)| l > last) {
+            points.push((v, l));
+        }
+    }
+
+    (0..size)
+        .map(|i| {
+            let target = i as f64 / (size - 1) as f64;
+            let upper = points.partition_point(|&(_, l)| l < target).clamp(1, points.len() - 1);
+            let (v0, l0) = points[upper - 1];
+            let (v1, l1) = points[upper];
+            let t = ((target - l0) / (l1 - l0)).clamp(0.0, 1.0);
+            (v0 + t * (v1 - v0)) as f32
+        })
+        .collect()
+}
+
+/// Fit a gamma correction model to photometer readings and save the result as
+/// a calibration file. The file can then be loaded with
+/// `Window.load_calibration()`, or is loaded automatically when it is placed
+/// in the calibration directory (`~/.psydk/calibrations` or
+/// `$PSYDK_CALIBRATION_DIR`).
+///
+/// Parameters
+/// ----------
+/// path : str
+///   The path of the calibration file. Use a `.toml` extension to save as TOML,
+///   otherwise JSON is used.
+/// device_name : str
+///   The name of the monitor (see `Monitor.name`).
+/// values : list of float
+///   The normalized values (0 to 1) that were displayed.
+/// r : list of float
+///   The luminance (in cd/m²) measured for each value on the red channel.
+/// g : list of float
+///   The luminance (in cd/m²) measured for each value on the green channel.
+/// b : list of float
+///   The luminance (in cd/m²) measured for each value on the blue channel.
+/// model : str, optional
+///   The model to fit. One of "gamma", "polylog4", "polylog5", "polylog6" or
+///   "lut". Defaults to "polylog5".
+/// primaries : tuple of (float, float), optional
+///   The xy chromaticities of the red, green and blue primaries.
+/// date : str, optional
+///   The date of the measurement.
+#[pyfunction]
+#[pyo3(name = "fit_calibration", signature = (path, device_name, values, r, g, b, model = "polylog5", primaries = None, date = None))]
+pub fn py_fit_calibration(
+    path: PathBuf,
+    device_name: String,
+    values: Vec<f32>,
+    r: Vec<f32>,
+    g: Vec<f32>,
+    b: Vec<f32>,
+    model: &str,
+    primaries: Option<([f32; 2], [f32; 2], [f32; 2])>,
+    date: Option<String>,
+) -> PyResult<()> {
+    let channel = |luminances: Vec<f32>| ChannelMeasurements {
+        values: values.clone(),
+        luminances,
+    };
+
+    let measurements = Measurements {
+        r: channel(r),
+        g: channel(g),
+        b: channel(b),
+    };
+
+    let primaries = primaries.map(|(red, green, blue)| Primaries {
+        red,
+        green,
+        blue,
+        white: None,
+    });
+
+    let calibration = MonitorCalibration::from_measurements(device_name, model, measurements, primaries, date)?;
+    calibration.save(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Measurements of a monitor with a pure power-law transfer function, an
+    /// offset of 0.5 cd/m² and a maximum luminance of 100.5 cd/m².
+    fn gamma_measurements(gamma: f32) -> ChannelMeasurements {
+        let values: Vec<f32> = (0..=10).map(|i| i as f32 / 10.0).collect();
+        let luminances = values.iter().map(|v| 0.5 + 100.0 * v.powf(gamma)).collect();
+        ChannelMeasurements { values, luminances }
+    }
+
+    #[test]
+    fn gamma_fit_recovers_exponent() {
+        let coefficients = fit_model("gamma", &gamma_measurements(2.2)).unwrap();
+        assert_eq!(coefficients.len(), 1);
+        assert!((coefficients[0] - 2.2).abs() < 1e-3, "{:?}", coefficients);
+    }
+
+    #[test]
+    fn polylog_fit_reproduces_values() {
+        // the values are a quadratic polynomial of ln(L), so polylog4 fits exactly
+        let polynomial = |x: f64| 1.0 + 0.3 * x + 0.03 * x * x;
+        let log_luminances: Vec<f64> = (0..=20).map(|i| -5.0 + i as f64 / 4.0).collect();
+
+        let mut measurements = ChannelMeasurements {
+            values: vec![0.0],
+            luminances: vec![0.0],
+        };
+        for &x in &log_luminances {
+            measurements.values.push(polynomial(x) as f32);
+            measurements.luminances.push(x.exp() as f32);
+        }
+
+        let coefficients = fit_model("polylog4", &measurements).unwrap();
+        assert_eq!(coefficients.len(), 5);
+
+        for &x in &log_luminances {
+            let fitted: f64 = coefficients
+                .iter()
+                .enumerate()
+                .map(|(i, &c)| c as f64 * x.powi(i as i32))
+                .sum();
+            assert!((fitted - polynomial(x)).abs() < 1e-3, "{} != {}", fitted, polynomial(x));
+        }
+    }
+
+    #[test]
+    fn lut_is_monotonic_from_zero_to_one() {
+        let lut = fit_model("lut", &gamma_measurements(2.2)).unwrap();
+
+        assert_eq!(lut.len(), MAX_LUT_SIZE);
+        assert_eq!(lut[0], 0.0);
+        assert_eq!(lut[MAX_LUT_SIZE - 1], 1.0);
+        assert!(lut.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn rejects_mismatched_lengths() {
+        let measurements = ChannelMeasurements {
+            values: vec![0.0, 0.5, 1.0],
+            luminances: vec![0.0, 1.0],
+        };
+        assert!(matches!(
+            fit_model("gamma", &measurements),
+            Err(psydkError::CalibrationError(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_non_increasing_luminance() {
+        let measurements = ChannelMeasurements {
+            values: vec![0.0, 0.5, 1.0],
+            luminances: vec![10.0, 5.0, 1.0],
+        };
+        assert!(matches!(
+            fit_model("polylog5", &measurements),
+            Err(psydkError::CalibrationError(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_model() {
+        assert!(matches!(
+            fit_model("cubic", &gamma_measurements(2.2)),
+            Err(psydkError::CalibrationError(_))
+        ));
+    }
+}
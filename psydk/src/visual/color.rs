@@ -1,7 +1,72 @@
+use std::ops::{Add, Div, Mul, Sub};
+
 use csscolorparser;
+use palette::{white_point::D65, FromColor, IntoColor, Lab, Lch, LinSrgb, Mix, Oklab, Srgb};
+use psydk_proc::FromPyStr;
 use pyo3::prelude::*;
+use strum_macros::EnumString;
+
+use super::{calibration::MonitorCalibration, color_space::ColorSpec, window::Window};
+use crate::{errors::psydkError, visual::geometry::IntoSize};
+
+/// A color specified in physical luminance (in cd/m²). Like a `Size`, it is
+/// only resolved to linear values when it is drawn, using the calibration of
+/// the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Luminance {
+    /// A grey with the given luminance.
+    Grey(f32),
+    /// The luminance contributed by the red, green and blue channels (on top
+    /// of the monitor's black level).
+    Rgb(f32, f32, f32),
+}
+
+impl Luminance {
+    /// Converts the luminance to linear RGB values using the given calibration.
+    /// Returns an error if the luminance is out of gamut.
+    pub fn to_linear(&self, calibration: &MonitorCalibration) -> Result<(f32, f32, f32), psydkError> {
+        let (r, g, b) = match *self {
+            Luminance::Grey(luminance) => {
+                let range = calibration.max_luminance - calibration.min_luminance;
+                let value = (luminance - calibration.min_luminance) / range;
+                (value, value, value)
+            }
+            Luminance::Rgb(r, g, b) => {
+                let [r_max, g_max, b_max] = calibration.channel_luminances();
+                (r / r_max, g / g_max, b / b_max)
+            }
+        };
+
+        if [r, g, b].iter().any(|v| !(0.0..=1.0).contains(v)) {
+            return Err(psydkError::CalibrationError(format!(
+                "The luminance {:?} is out of gamut for monitor {} (linear values: {}, {}, {}).",
+                self, calibration.device_name, r, g, b
+            )));
+        }
+
+        Ok((r, g, b))
+    }
+}
 
-use crate::visual::geometry::IntoSize;
+/// The luminance contribution of the red, green and blue channel (Rec. 709).
+const LUMINANCE_WEIGHTS: [f32; 3] = [0.2126, 0.7152, 0.0722];
+
+/// The color space in which two colors are interpolated.
+#[derive(EnumString, Debug, Clone, Copy, PartialEq, Default, FromPyStr)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum InterpolationSpace {
+    /// Linear light, i.e., the mixture of the two colors on screen.
+    #[default]
+    Linear,
+    /// Gamma encoded sRGB values, as used by most image editors.
+    Srgb,
+    /// CIE L*a*b*, which is approximately perceptually uniform.
+    Lab,
+    /// CIE L*C*h°, which interpolates the hue along the shorter arc.
+    Lch,
+    /// Oklab, a perceptually uniform space with better hue linearity.
+    Oklab,
+}
 
 #[pyclass(name = "LinRgba")]
 #[derive(Debug, Clone, Copy)]
@@ -33,6 +98,12 @@ pub struct LinRgba {
     pub g: f32,
     pub b: f32,
     pub a: f32,
+    /// If set, the color is specified in physical luminance and `r`, `g` and
+    /// `b` are only known once the color is resolved for a window.
+    pub luminance: Option<Luminance>,
+    /// If set, the color is specified in a device-independent color space and
+    /// `r`, `g` and `b` are only known once the color is resolved for a window.
+    pub spec: Option<ColorSpec>,
 }
 
 impl Default for LinRgba {
@@ -43,7 +114,53 @@ impl Default for LinRgba {
 
 impl LinRgba {
     pub fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
-        Self { r, g, b, a }
+        Self {
+            r,
+            g,
+            b,
+            a,
+            luminance: None,
+            spec: None,
+        }
+    }
+
+    /// Creates a color from a luminance (in cd/m²) that is resolved when the
+    /// color is drawn.
+    pub fn from_luminance(luminance: Luminance, a: f32) -> Self {
+        Self {
+            luminance: Some(luminance),
+            ..Self::new(0.0, 0.0, 0.0, a)
+        }
+    }
+
+    /// Creates a color from a device-independent specification that is
+    /// resolved when the color is drawn.
+    pub fn from_spec(spec: ColorSpec, a: f32) -> Self {
+        Self {
+            spec: Some(spec),
+            ..Self::new(0.0, 0.0, 0.0, a)
+        }
+    }
+
+    /// Resolves the color to linear values. Colors that are specified in
+    /// luminance require a calibration and must be in gamut; colors in a
+    /// device-independent color space use the primaries of the monitor (see
+    /// `ColorSpec::to_linear`).
+    pub fn resolve(&self, calibration: Option<&MonitorCalibration>) -> Result<LinRgba, psydkError> {
+        let (r, g, b) = match (self.luminance, self.spec) {
+            (Some(luminance), _) => {
+                let calibration = calibration.ok_or_else(|| {
+                    psydkError::CalibrationError(
+                        "Colors specified in luminance require a window with a monitor calibration.".to_string(),
+                    )
+                })?;
+                luminance.to_linear(calibration)?
+            }
+            (None, Some(spec)) => spec.to_linear(calibration)?,
+            (None, None) => return Ok(*self),
+        };
+
+        Ok(LinRgba::new(r, g, b, self.a))
     }
 
     #[inline]
@@ -57,22 +174,22 @@ impl LinRgba {
 
     pub fn from_srgba(r: f32, g: f32, b: f32, a: f32) -> Self {
         // Decode sRGB to linear RGB
-        Self {
-            r: Self::srgb_to_lin_rgb(r),
-            g: Self::srgb_to_lin_rgb(g),
-            b: Self::srgb_to_lin_rgb(b),
+        Self::new(
+            Self::srgb_to_lin_rgb(r),
+            Self::srgb_to_lin_rgb(g),
+            Self::srgb_to_lin_rgb(b),
             a,
-        }
+        )
     }
 
     pub fn from_str(css_color_str: &str) -> Self {
         let color = csscolorparser::parse(css_color_str).expect("Failed to parse color");
-        Self {
-            r: Self::srgb_to_lin_rgb(color.r),
-            g: Self::srgb_to_lin_rgb(color.g),
-            b: Self::srgb_to_lin_rgb(color.b),
-            a: color.a,
-        }
+        Self::new(
+            Self::srgb_to_lin_rgb(color.r),
+            Self::srgb_to_lin_rgb(color.g),
+            Self::srgb_to_lin_rgb(color.b),
+            color.a,
+        )
     }
 
     pub fn r(&self) -> f32 {
@@ -106,6 +223,165 @@ impl LinRgba {
     pub fn a_u8(&self) -> u8 {
         (self.a * 255.0).round() as u8
     }
+
+    /// Returns false if the color is specified in luminance or a
+    /// device-independent color space and needs to be resolved before its
+    /// channels can be used.
+    pub fn is_resolved(&self) -> bool {
+        self.luminance.is_none() && self.spec.is_none()
+    }
+
+    /// Returns an error if the color is not resolved, i.e., if its channels
+    /// are not known yet.
+    pub fn require_resolved(&self) -> Result<(), psydkError> {
+        if self.is_resolved() {
+            Ok(())
+        } else {
+            Err(psydkError::UnresolvedColorError)
+        }
+    }
+
+    /// Interpolates between this color (`t = 0`) and `other` (`t = 1`) in the
+    /// given color space. The alpha channel is interpolated linearly. Returns
+    /// an error if either color is not resolved; the perceptual spaces assume
+    /// sRGB primaries.
+    pub fn mix(&self, other: &LinRgba, t: f32, space: InterpolationSpace) -> Result<LinRgba, psydkError> {
+        self.require_resolved()?;
+        other.require_resolved()?;
+
+        let (from, to) = (
+            LinSrgb::new(self.r, self.g, self.b),
+            LinSrgb::new(other.r, other.g, other.b),
+        );
+
+        let mixed: LinSrgb<f32> = match space {
+            InterpolationSpace::Linear => from.mix(to, t),
+            InterpolationSpace::Srgb => Srgb::from_linear(from).mix(Srgb::from_linear(to), t).into_linear(),
+            InterpolationSpace::Lab => {
+                let (from, to): (Lab<D65, f32>, Lab<D65, f32>) = (from.into_color(), to.into_color());
+                from.mix(to, t).into_color()
+            }
+            InterpolationSpace::Lch => {
+                let (from, to): (Lch<D65, f32>, Lch<D65, f32>) = (from.into_color(), to.into_color());
+                from.mix(to, t).into_color()
+            }
+            InterpolationSpace::Oklab => LinSrgb::from_color(Oklab::from_color(from).mix(Oklab::from_color(to), t)),
+        };
+
+        Ok(LinRgba::new(
+            mixed.red,
+            mixed.green,
+            mixed.blue,
+            self.a + (other.a - self.a) * t,
+        ))
+    }
+
+    /// Scales the difference between this color and the background by
+    /// `contrast`: 1 returns the color, 0 the background and -1 the color
+    /// mirrored around the background. The alpha channel of this color is
+    /// kept. Returns an error if either color is not resolved.
+    pub fn with_contrast(&self, background: &LinRgba, contrast: f32) -> Result<LinRgba, psydkError> {
+        self.require_resolved()?;
+        background.require_resolved()?;
+        let color = *background + (*self - *background) * contrast;
+        Ok(LinRgba::new(color.r, color.g, color.b, self.a))
+    }
+
+    /// Adds the channels of `other` to this color. Returns an error if either
+    /// color is not resolved.
+    pub fn checked_add(&self, other: &LinRgba) -> Result<LinRgba, psydkError> {
+        self.require_resolved()?;
+        other.require_resolved()?;
+        Ok(*self + *other)
+    }
+
+    /// Subtracts the channels of `other` from this color. Returns an error if
+    /// either color is not resolved.
+    pub fn checked_sub(&self, other: &LinRgba) -> Result<LinRgba, psydkError> {
+        self.require_resolved()?;
+        other.require_resolved()?;
+        Ok(*self - *other)
+    }
+
+    /// Multiplies the channels of the color by `factor`. Returns an error if
+    /// the color is not resolved.
+    pub fn scaled(&self, factor: f32) -> Result<LinRgba, psydkError> {
+        self.require_resolved()?;
+        Ok(*self * factor)
+    }
+
+    /// The relative luminance of the color (Rec. 709 weights), from 0 for
+    /// black to 1 for white.
+    pub fn relative_luminance(&self) -> f32 {
+        LUMINANCE_WEIGHTS[0] * self.r + LUMINANCE_WEIGHTS[1] * self.g + LUMINANCE_WEIGHTS[2] * self.b
+    }
+
+    /// The Michelson contrast `(L_max - L_min) / (L_max + L_min)` between two
+    /// colors, based on their relative luminance. Returns 0 if both colors
+    /// are black.
+    pub fn michelson_contrast(a: &LinRgba, b: &LinRgba) -> f32 {
+        let (a, b) = (a.relative_luminance(), b.relative_luminance());
+        let (max, min) = (a.max(b), a.min(b));
+        if max + min == 0.0 {
+            return 0.0;
+        }
+        (max - min) / (max + min)
+    }
+
+    /// The RMS contrast of the colors, i.e., the standard deviation of their
+    /// relative luminance divided by the mean. Returns 0 if there are no
+    /// colors or the mean luminance is 0.
+    pub fn rms_contrast(colors: &[LinRgba]) -> f32 {
+        let n = colors.len() as f32;
+        let mean = colors.iter().map(|c| c.relative_luminance()).sum::<f32>() / n;
+        if colors.is_empty() || mean == 0.0 {
+            return 0.0;
+        }
+
+        let variance = colors
+            .iter()
+            .map(|c| (c.relative_luminance() - mean).powi(2))
+            .sum::<f32>()
+            / n;
+        variance.sqrt() / mean
+    }
+}
+
+// Arithmetic on colors applies to the red, green and blue channels and keeps
+// the alpha channel of the left operand. The channels of colors that are not
+// resolved are meaningless, so fallible code uses `checked_add`,
+// `checked_sub` and `scaled` instead.
+
+impl Add for LinRgba {
+    type Output = LinRgba;
+
+    fn add(self, other: LinRgba) -> LinRgba {
+        LinRgba::new(self.r + other.r, self.g + other.g, self.b + other.b, self.a)
+    }
+}
+
+impl Sub for LinRgba {
+    type Output = LinRgba;
+
+    fn sub(self, other: LinRgba) -> LinRgba {
+        LinRgba::new(self.r - other.r, self.g - other.g, self.b - other.b, self.a)
+    }
+}
+
+impl Mul<f32> for LinRgba {
+    type Output = LinRgba;
+
+    fn mul(self, factor: f32) -> LinRgba {
+        LinRgba::new(self.r * factor, self.g * factor, self.b * factor, self.a)
+    }
+}
+
+impl Div<f32> for LinRgba {
+    type Output = LinRgba;
+
+    fn div(self, divisor: f32) -> LinRgba {
+        LinRgba::new(self.r / divisor, self.g / divisor, self.b / divisor, self.a)
+    }
 }
 
 impl From<LinRgba> for renderer::colors::RGBA {
@@ -177,6 +453,199 @@ impl LinRgba {
     fn py_a(&self) -> f32 {
         self.a
     }
+
+    /// Resolve the color to linear values for the given window. This is only
+    /// needed for colors specified in luminance or a device-independent color
+    /// space (e.g., `lum()` or `lab()`), which are otherwise resolved when
+    /// they are drawn.
+    ///
+    /// Parameters
+    /// ----------
+    /// window : Window
+    ///   The window to resolve the color for.
+    ///
+    /// Returns
+    /// -------
+    /// LinRgba
+    ///   The linear RGBA color.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the color is out of gamut, or is specified in luminance and the
+    ///   window has no calibration.
+    #[pyo3(name = "eval")]
+    fn py_eval(&self, window: &Window) -> PyResult<LinRgba> {
+        let window_state = window.state.lock().unwrap();
+        self.resolve(window_state.calibration.as_ref())
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Interpolate between this color and another color.
+    ///
+    /// Parameters
+    /// ----------
+    /// other : LinRgba
+    ///   The color to interpolate to.
+    /// t : float
+    ///   The position between the colors (0.0 returns this color, 1.0 the other
+    ///   color).
+    /// space : str, optional
+    ///   The color space to interpolate in: "linear" (linear light, the
+    ///   default), "srgb", "lab", "lch" or "oklab".
+    ///
+    /// Returns
+    /// -------
+    /// LinRgba
+    ///   The interpolated color.
+    ///
+    /// Examples
+    /// --------
+    /// >>> orange = LinRgba.from_str("red").mix("yellow", 0.5, space="oklab")
+    #[pyo3(name = "mix", signature = (other, t, space = InterpolationSpace::Linear))]
+    fn py_mix(&self, other: IntoLinRgba, t: f32, space: InterpolationSpace) -> PyResult<LinRgba> {
+        self.mix(&LinRgba::from(other), t, space).map_err(value_error)
+    }
+
+    /// Scale the difference between this color and a background.
+    ///
+    /// Parameters
+    /// ----------
+    /// background : LinRgba
+    ///   The background color.
+    /// contrast : float
+    ///   The scale factor (1.0 returns this color, 0.0 the background and -1.0
+    ///   the color mirrored around the background).
+    ///
+    /// Returns
+    /// -------
+    /// LinRgba
+    ///   The new color.
+    #[pyo3(name = "with_contrast")]
+    fn py_with_contrast(&self, background: IntoLinRgba, contrast: f32) -> PyResult<LinRgba> {
+        self.with_contrast(&LinRgba::from(background), contrast)
+            .map_err(value_error)
+    }
+
+    /// The relative luminance of the color (Rec. 709 weights).
+    ///
+    /// Returns
+    /// -------
+    /// float
+    ///   The luminance, from 0.0 for black to 1.0 for white.
+    #[pyo3(name = "luminance")]
+    fn py_luminance(&self) -> PyResult<f32> {
+        require_resolved(&[self])?;
+        Ok(self.relative_luminance())
+    }
+
+    /// The Michelson contrast between two colors, based on their relative
+    /// luminance.
+    ///
+    /// Parameters
+    /// ----------
+    /// a : LinRgba
+    ///   The first color.
+    /// b : LinRgba
+    ///   The second color.
+    ///
+    /// Returns
+    /// -------
+    /// float
+    ///   The contrast `(L_max - L_min) / (L_max + L_min)`.
+    #[staticmethod]
+    #[pyo3(name = "michelson_contrast")]
+    fn py_michelson_contrast(a: IntoLinRgba, b: IntoLinRgba) -> PyResult<f32> {
+        let (a, b) = (LinRgba::from(a), LinRgba::from(b));
+        require_resolved(&[&a, &b])?;
+        Ok(LinRgba::michelson_contrast(&a, &b))
+    }
+
+    /// The RMS contrast of a set of colors, i.e., the standard deviation of
+    /// their relative luminance divided by the mean.
+    ///
+    /// Parameters
+    /// ----------
+    /// colors : list[LinRgba]
+    ///   The colors.
+    ///
+    /// Returns
+    /// -------
+    /// float
+    ///   The contrast.
+    #[staticmethod]
+    #[pyo3(name = "rms_contrast")]
+    fn py_rms_contrast(colors: Vec<IntoLinRgba>) -> PyResult<f32> {
+        let colors = colors.into_iter().map(LinRgba::from).collect::<Vec<_>>();
+        require_resolved(&colors.iter().collect::<Vec<_>>())?;
+        Ok(LinRgba::rms_contrast(&colors))
+    }
+
+    fn __add__(&self, other: IntoLinRgba) -> PyResult<LinRgba> {
+        self.checked_add(&LinRgba::from(other)).map_err(value_error)
+    }
+
+    fn __sub__(&self, other: IntoLinRgba) -> PyResult<LinRgba> {
+        self.checked_sub(&LinRgba::from(other)).map_err(value_error)
+    }
+
+    fn __mul__(&self, factor: f32) -> PyResult<LinRgba> {
+        self.scaled(factor).map_err(value_error)
+    }
+
+    fn __rmul__(&self, factor: f32) -> PyResult<LinRgba> {
+        self.__mul__(factor)
+    }
+
+    fn __truediv__(&self, divisor: f32) -> PyResult<LinRgba> {
+        require_resolved(&[self])?;
+        if divisor == 0.0 {
+            return Err(pyo3::exceptions::PyZeroDivisionError::new_err(
+                "division of a color by zero",
+            ));
+        }
+        Ok(*self / divisor)
+    }
+
+    fn __repr__(&self) -> String {
+        if let Some(luminance) = self.luminance {
+            return match luminance {
+                Luminance::Grey(l) => format!("lum({}, a={})", l, self.a),
+                Luminance::Rgb(r, g, b) => format!("lum_rgb({}, {}, {}, a={})", r, g, b, self.a),
+            };
+        }
+
+        match self.spec {
+            Some(ColorSpec::Xyz(x, y, z)) => format!("xyz({}, {}, {}, a={})", x, y, z, self.a),
+            Some(ColorSpec::Xyy(x, y, luma)) => format!("xyy({}, {}, {}, a={})", x, y, luma, self.a),
+            Some(ColorSpec::Lab(l, a, b)) => format!("lab({}, {}, {}, a={})", l, a, b, self.a),
+            Some(ColorSpec::Lch(l, c, h)) => format!("lch({}, {}, {}, a={})", l, c, h, self.a),
+            Some(ColorSpec::Dkl {
+                elevation,
+                azimuth,
+                contrast,
+                background,
+            }) => format!(
+                "dkl({}, {}, {}, background={}, a={})",
+                elevation, azimuth, contrast, background, self.a
+            ),
+            None => format!("LinRgba({}, {}, {}, {})", self.r, self.g, self.b, self.a),
+        }
+    }
+}
+
+/// Arithmetic needs the channels of the colors, which are not known for colors
+/// specified in a device-independent color space until they are resolved.
+fn require_resolved(colors: &[&LinRgba]) -> PyResult<()> {
+    colors
+        .iter()
+        .try_for_each(|c| c.require_resolved())
+        .map_err(value_error)
+}
+
+/// Unresolved colors are a ValueError in Python.
+fn value_error(err: psydkError) -> PyErr {
+    pyo3::exceptions::PyValueError::new_err(err.to_string())
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -249,3 +718,206 @@ pub fn py_linrgb(r: f32, g: f32, b: f32, a: f32) -> LinRgba {
 pub fn py_linrgba(r: f32, g: f32, b: f32, a: f32) -> LinRgba {
     LinRgba::new(r, g, b, a)
 }
+
+#[pyfunction]
+#[pyo3(name = "lum")]
+#[pyo3(signature = (luminance, a = 1.0))]
+/// Create a grey with the given luminance. The color is resolved to linear
+/// values when it is drawn, using the calibration of the window.
+///
+/// Parameters
+/// ----------
+/// luminance : float
+///   The luminance in cd/m².
+/// a : float, optional
+///   The alpha channel (0.0 to 1.0).
+///
+/// Returns
+/// -------
+/// LinRgba
+///   The color.
+///
+/// Examples
+/// --------
+/// >>> grey = lum(45.0)
+pub fn py_lum(luminance: f32, a: f32) -> PyResult<LinRgba> {
+    if luminance < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "Luminance must not be negative.",
+        ));
+    }
+    Ok(LinRgba::from_luminance(Luminance::Grey(luminance), a))
+}
+
+#[pyfunction]
+#[pyo3(name = "lum_rgb")]
+#[pyo3(signature = (r, g, b, a = 1.0))]
+/// Create a color from the luminance contributed by each channel. The color is
+/// resolved to linear values when it is drawn, using the calibration of the
+/// window.
+///
+/// Parameters
+/// ----------
+/// r : float
+///   The luminance of the red channel in cd/m².
+/// g : float
+///   The luminance of the green channel in cd/m².
+/// b : float
+///   The luminance of the blue channel in cd/m².
+/// a : float, optional
+///   The alpha channel (0.0 to 1.0).
+///
+/// Returns
+/// -------
+/// LinRgba
+///   The color.
+pub fn py_lum_rgb(r: f32, g: f32, b: f32, a: f32) -> PyResult<LinRgba> {
+    if r < 0.0 || g < 0.0 || b < 0.0 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "Luminance must not be negative.",
+        ));
+    }
+    Ok(LinRgba::from_luminance(Luminance::Rgb(r, g, b), a))
+}
+
+#[pyfunction]
+#[pyo3(name = "xyz")]
+#[pyo3(signature = (x, y, z, a = 1.0))]
+/// Create a color from CIE 1931 XYZ tristimulus values. The values are relative
+/// to the white of the monitor (Y = 1). The color is resolved using the
+/// primaries of the window's calibration (or sRGB primaries) when it is drawn.
+///
+/// Parameters
+/// ----------
+/// x : float
+///   The X tristimulus value.
+/// y : float
+///   The Y tristimulus value (relative luminance).
+/// z : float
+///   The Z tristimulus value.
+/// a : float, optional
+///   The alpha channel (0.0 to 1.0).
+///
+/// Returns
+/// -------
+/// LinRgba
+///   The color.
+pub fn py_xyz(x: f32, y: f32, z: f32, a: f32) -> LinRgba {
+    LinRgba::from_spec(ColorSpec::Xyz(x, y, z), a)
+}
+
+#[pyfunction]
+#[pyo3(name = "xyy")]
+#[pyo3(signature = (x, y, luminance, a = 1.0))]
+/// Create a color from CIE 1931 xy chromaticity coordinates and a relative
+/// luminance (1 is the white of the monitor).
+///
+/// Parameters
+/// ----------
+/// x : float
+///   The x chromaticity coordinate.
+/// y : float
+///   The y chromaticity coordinate.
+/// luminance : float
+///   The relative luminance Y.
+/// a : float, optional
+///   The alpha channel (0.0 to 1.0).
+///
+/// Returns
+/// -------
+/// LinRgba
+///   The color.
+pub fn py_xyy(x: f32, y: f32, luminance: f32, a: f32) -> LinRgba {
+    LinRgba::from_spec(ColorSpec::Xyy(x, y, luminance), a)
+}
+
+#[pyfunction]
+#[pyo3(name = "lab")]
+#[pyo3(signature = (l, a_star, b_star, a = 1.0))]
+/// Create a color from CIE L*a*b* coordinates relative to the white point of
+/// the monitor (D65 for monitors without calibrated primaries).
+///
+/// Parameters
+/// ----------
+/// l : float
+///   The lightness L* (0 to 100).
+/// a_star : float
+///   The green-red coordinate a*.
+/// b_star : float
+///   The blue-yellow coordinate b*.
+/// a : float, optional
+///   The alpha channel (0.0 to 1.0).
+///
+/// Returns
+/// -------
+/// LinRgba
+///   The color.
+pub fn py_lab(l: f32, a_star: f32, b_star: f32, a: f32) -> LinRgba {
+    LinRgba::from_spec(ColorSpec::Lab(l, a_star, b_star), a)
+}
+
+#[pyfunction]
+#[pyo3(name = "lch")]
+#[pyo3(signature = (l, c, h, a = 1.0))]
+/// Create a color from CIE L*C*h° coordinates relative to the white point of
+/// the monitor (D65 for monitors without calibrated primaries).
+///
+/// Parameters
+/// ----------
+/// l : float
+///   The lightness L* (0 to 100).
+/// c : float
+///   The chroma C*.
+/// h : float
+///   The hue angle in degrees.
+/// a : float, optional
+///   The alpha channel (0.0 to 1.0).
+///
+/// Returns
+/// -------
+/// LinRgba
+///   The color.
+pub fn py_lch(l: f32, c: f32, h: f32, a: f32) -> LinRgba {
+    LinRgba::from_spec(ColorSpec::Lch(l, c, h), a)
+}
+
+#[pyfunction]
+#[pyo3(name = "dkl")]
+#[pyo3(signature = (elevation, azimuth, contrast, background = 0.5, a = 1.0))]
+/// Create a color in the DKL cone-opponent space. The color is given in polar
+/// coordinates around a grey background: an elevation of 0° is isoluminant,
+/// with an azimuth of 0° along the L-M axis and 90° along the S axis. The
+/// contrast is expressed in cone contrast.
+///
+/// Parameters
+/// ----------
+/// elevation : float
+///   The elevation in degrees (-90 to 90).
+/// azimuth : float
+///   The azimuth in degrees.
+/// contrast : float
+///   The distance from the background.
+/// background : float, optional
+///   The linear grey level of the background (0.0 to 1.0). Defaults to 0.5.
+/// a : float, optional
+///   The alpha channel (0.0 to 1.0).
+///
+/// Returns
+/// -------
+/// LinRgba
+///   The color.
+///
+/// Examples
+/// --------
+/// >>> isoluminant_red = dkl(0.0, 0.0, 0.1)
+pub fn py_dkl(elevation: f32, azimuth: f32, contrast: f32, background: f32, a: f32) -> LinRgba {
+    LinRgba::from_spec(
+        ColorSpec::Dkl {
+            elevation,
+            azimuth,
+            contrast,
+            background,
+        },
+        a,
+    )
+}
@@ -123,7 +123,11 @@
 // necessarily give you the same color.
 
 use bytemuck::{Pod, Zeroable};
-use palette::{IntoColor, Srgba, Xyza};
+use palette::{IntoColor, LinSrgba, Srgba, Xyza};
+use psydk_proc::FromPyStr;
+use pyo3::prelude::*;
+use renderer::wgpu_renderer::OutputColorSpace;
+use strum_macros::EnumString;
 use wgpu::TextureFormat;
 
 /// Macro that creates an sRGB color from a given hex value.
@@ -137,7 +141,7 @@ use wgpu::TextureFormat;
 #[macro_export]
 macro_rules! srgb_hex {
     ($hex:expr) => {{
-        use $crate::visual::color::SRGBA;
+        use $crate::visual::color2::SRGBA;
         SRGBA::new(
             (($hex >> 16) & 0xff) as f32 / 255.0,
             (($hex >> 8) & 0xff) as f32 / 255.0,
@@ -344,15 +348,20 @@ impl IntoRawRgba for Rgba {
 /// The ColorFormat defines how color is handled internally in the rendering
 /// pipeline. It is used to convert colors to the appropriate color space
 /// before rendering.
-#[derive(Copy, Clone, Debug)]
+///
+/// The format of a window's surface is selected with the same enum. If the
+/// requested format is not supported by the surface, the window falls back to
+/// `SRGBA8`.
+#[derive(EnumString, Copy, Clone, Debug, PartialEq, Default, FromPyStr)]
+#[strum(ascii_case_insensitive)]
 pub enum ColorFormat {
     /// Indicates that the rendering pipeline should use the sRGB color space
     /// with 8 bits per channel (32 bits per pixel). This is the default color
     /// format and is supported on virtually all hardware.
+    #[default]
+    #[strum(serialize = "rgba8", serialize = "srgba8")]
     SRGBA8,
 
-    // /// The sRGB color space with 8 bits per channel (24 bits per pixel).
-    // SRGBA10,
     /// Indicates that the rendering pipeline should use the Display P3 color
     /// space with 8 bits per channel (32 bits per pixel). This color format
     /// is supported on macOS and iOS devices and is one of the color spaces
@@ -360,10 +369,48 @@ pub enum ColorFormat {
     /// color space has a wider gamut than sRGB, it can represent more colors
     /// (about 25% more). However, as this format still uses the same bit depth
     /// as the `SRGBA8` format, color banding may be more apparent.
+    ///
+    /// Colors are rendered in linear sRGB and converted to Display P3 when the
+    /// frame is presented, so the display has to be set to Display P3.
+    #[strum(serialize = "display_p3")]
     DisplayP3U8,
+
+    /// Indicates that the rendering pipeline should use the sRGB color space
+    /// with 10 bits per color channel and 2 bits of alpha (32 bits per
+    /// pixel).
+    #[strum(serialize = "rgb10a2")]
+    RGB10A2,
+
+    /// Indicates that the rendering pipeline should use 16-bit floating point
+    /// values per channel. The surface expects linear values that may exceed
+    /// 1.0 (extended range), so gamma correction is not available.
+    #[strum(serialize = "rgba16_float", serialize = "rgb16f")]
     RGB16f,
 }
 
+/// Converts a color from linear sRGB to (encoded) Display P3. Both color spaces
+/// have a D65 white point, so only the primaries change.
+fn srgb_to_display_p3(col: LinSrgba<f32>) -> Rgba {
+    #[rustfmt::skip]
+    const SRGB_TO_DISPLAY_P3: [[f32; 3]; 3] = [
+        [0.822_462_1, 0.177_538_0, 0.0],
+        [0.033_194_1, 0.966_805_8, 0.0],
+        [0.017_082_7, 0.072_397_4, 0.910_519_9],
+    ];
+
+    // Display P3 uses the transfer function of sRGB
+    let encode = |c: f32| {
+        if c <= 0.003_130_8 {
+            c * 12.92
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        }
+    };
+
+    let [r, g, b] = SRGB_TO_DISPLAY_P3.map(|row| encode(row[0] * col.red + row[1] * col.green + row[2] * col.blue));
+    Rgba { r, g, b, a: col.alpha }
+}
+
 pub trait IntoRawRgba {
     fn convert_to_raw_rgba(&self, color_format: ColorFormat) -> Rgba;
 }
@@ -391,9 +438,10 @@ impl ColorFormat {
                 }
             }
             ColorFormat::DisplayP3U8 => {
-                todo!()
+                let col: Xyza<palette::white_point::D65, f32> = col.into_color();
+                srgb_to_display_p3(col.into_color())
             }
-            ColorFormat::RGB16f => {
+            ColorFormat::RGB10A2 | ColorFormat::RGB16f => {
                 let col: Xyza<palette::white_point::D65, f32> = col.into_color();
                 let col: Srgba<f32> = col.into_color();
                 Rgba {
@@ -411,7 +459,28 @@ impl ColorFormat {
         match self {
             ColorFormat::SRGBA8 => TextureFormat::Bgra8UnormSrgb,
             ColorFormat::DisplayP3U8 => TextureFormat::Bgra8UnormSrgb,
-            ColorFormat::RGB16f => TextureFormat::Bgra8Unorm,
+            ColorFormat::RGB10A2 => TextureFormat::Rgb10a2Unorm,
+            ColorFormat::RGB16f => TextureFormat::Rgba16Float,
+        }
+    }
+
+    /// Returns the wgpu::TextureFormat of a window's surface. Gamma
+    /// correction and the conversion to the color space of the surface are
+    /// applied by the output pass, so integer formats are not sRGB-encoded.
+    pub fn to_wgpu_surface_texture_format(&self) -> TextureFormat {
+        match self {
+            ColorFormat::SRGBA8 => TextureFormat::Bgra8Unorm,
+            ColorFormat::DisplayP3U8 => TextureFormat::Bgra8Unorm,
+            ColorFormat::RGB10A2 => TextureFormat::Rgb10a2Unorm,
+            ColorFormat::RGB16f => TextureFormat::Rgba16Float,
+        }
+    }
+
+    /// Returns the color space the output pass converts the rendered frame to.
+    pub fn output_color_space(&self) -> OutputColorSpace {
+        match self {
+            ColorFormat::DisplayP3U8 => OutputColorSpace::DisplayP3,
+            _ => OutputColorSpace::Srgb,
         }
     }
 
@@ -424,7 +493,8 @@ impl ColorFormat {
         match self {
             ColorFormat::SRGBA8 => (TextureFormat::Bgra8Unorm, TextureFormat::Bgra8UnormSrgb),
             ColorFormat::DisplayP3U8 => (TextureFormat::Bgra8Unorm, TextureFormat::Bgra8UnormSrgb),
-            ColorFormat::RGB16f => (TextureFormat::Bgra8Unorm, TextureFormat::Bgra8Unorm),
+            ColorFormat::RGB10A2 => (TextureFormat::Rgb10a2Unorm, TextureFormat::Rgb10a2Unorm),
+            ColorFormat::RGB16f => (TextureFormat::Rgba16Float, TextureFormat::Rgba16Float),
         }
     }
 
@@ -473,10 +543,8 @@ impl IntoRawRgba for SRGBA {
                     a: col.alpha as f32,
                 }
             }
-            ColorFormat::DisplayP3U8 => {
-                todo!()
-            }
-            ColorFormat::RGB16f => {
+            ColorFormat::DisplayP3U8 => srgb_to_display_p3((*self).into_color()),
+            ColorFormat::RGB10A2 | ColorFormat::RGB16f => {
                 let col = self.clone();
                 let col: Xyza<palette::white_point::D65, f32> = col.into_color();
                 let col: Srgba<f32> = col.into_color();
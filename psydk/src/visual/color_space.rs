@@ -0,0 +1,173 @@
+//! Colors that are specified in device-independent color spaces. These are
+//! resolved to linear RGB values using the primaries of the monitor they are
+//! drawn on, so they can only be converted once the window is known.
+
+use nalgebra::{Matrix3, Vector3};
+
+use super::calibration::{MonitorCalibration, Primaries};
+use crate::errors::psydkError;
+
+/// The CIE 1931 xy chromaticity of the D65 white point.
+pub const D65_WHITE: [f32; 2] = [0.3127, 0.3290];
+
+/// The primaries of sRGB (and Rec. 709). These are used for monitors without
+/// calibrated primaries.
+pub const SRGB_PRIMARIES: Primaries = Primaries {
+    red: [0.64, 0.33],
+    green: [0.30, 0.60],
+    blue: [0.15, 0.06],
+    white: Some(D65_WHITE),
+};
+
+/// Converts XYZ to LMS cone excitations using the Smith & Pokorny cone
+/// fundamentals, scaled such that L + M equals the luminance Y.
+#[rustfmt::skip]
+const XYZ_TO_LMS: Matrix3<f64> = Matrix3::new(
+     0.15514, 0.54312, -0.03286,
+    -0.15514, 0.45684,  0.03286,
+     0.0,     0.0,      0.00801,
+);
+
+/// A color that is specified in a device-independent color space. XYZ values
+/// are relative, i.e., Y = 1 is the luminance of the monitor's white.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorSpec {
+    /// CIE 1931 XYZ.
+    Xyz(f32, f32, f32),
+    /// CIE 1931 xy chromaticity and luminance Y.
+    Xyy(f32, f32, f32),
+    /// CIE L*a*b* relative to the monitor's white point (L* from 0 to 100).
+    Lab(f32, f32, f32),
+    /// CIE L*C*h° relative to the monitor's white point (hue in degrees).
+    Lch(f32, f32, f32),
+    /// The DKL cone-opponent space in polar coordinates around a grey
+    /// background. Elevation and azimuth are in degrees; the contrast is in
+    /// units of cone contrast.
+    Dkl {
+        elevation: f32,
+        azimuth: f32,
+        contrast: f32,
+        background: f32,
+    },
+}
+
+impl ColorSpec {
+    /// Converts the color to linear RGB values for a monitor with the given
+    /// calibration. Without calibrated primaries, sRGB primaries and a D65
+    /// white point are assumed. Returns an error if the color is out of gamut.
+    pub fn to_linear(&self, calibration: Option<&MonitorCalibration>) -> Result<(f32, f32, f32), psydkError> {
+        let primaries = calibration.and_then(|c| c.primaries).unwrap_or(SRGB_PRIMARIES);
+        let white = chromaticity_to_xyz(primaries.white.unwrap_or(D65_WHITE));
+
+        let (r, g, b) = match *self {
+            ColorSpec::Xyz(x, y, z) => xyz_to_linear(&primaries, Vector3::new(x as f64, y as f64, z as f64))?,
+            ColorSpec::Xyy(x, y, luma) => xyz_to_linear(&primaries, chromaticity_to_xyz([x, y]) * luma as f64)?,
+            ColorSpec::Lab(l, a, b) => xyz_to_linear(&primaries, lab_to_xyz(l as f64, a as f64, b as f64, &white))?,
+            ColorSpec::Lch(l, c, h) => {
+                let (c, h) = (c as f64, (h as f64).to_radians());
+                xyz_to_linear(&primaries, lab_to_xyz(l as f64, c * h.cos(), c * h.sin(), &white))?
+            }
+            ColorSpec::Dkl {
+                elevation,
+                azimuth,
+                contrast,
+                background,
+            } => dkl_to_linear(&primaries, elevation, azimuth, contrast, background)?,
+        };
+
+        if [r, g, b].iter().any(|v| !(0.0..=1.0).contains(v)) {
+            let device_name = calibration.map_or("without calibration", |c| c.device_name.as_str());
+            return Err(psydkError::CalibrationError(format!(
+                "The color {:?} is out of gamut for monitor {} (linear values: {}, {}, {}).",
+                self, device_name, r, g, b
+            )));
+        }
+
+        Ok((r, g, b))
+    }
+}
+
+fn degenerate_primaries() -> psydkError {
+    psydkError::CalibrationError("The primaries of the monitor are degenerate.".to_string())
+}
+
+/// Returns the XYZ coordinates of a chromaticity with Y = 1.
+fn chromaticity_to_xyz([x, y]: [f32; 2]) -> Vector3<f64> {
+    let (x, y) = (x as f64, y as f64);
+    Vector3::new(x / y, 1.0, (1.0 - x - y) / y)
+}
+
+/// Returns the matrix that converts linear RGB values to XYZ for a monitor
+/// with the given primaries (the white of the monitor has Y = 1).
+pub fn rgb_to_xyz_matrix(primaries: &Primaries) -> Result<Matrix3<f64>, psydkError> {
+    let m = Matrix3::from_columns(&[
+        chromaticity_to_xyz(primaries.red),
+        chromaticity_to_xyz(primaries.green),
+        chromaticity_to_xyz(primaries.blue),
+    ]);
+    let white = chromaticity_to_xyz(primaries.white.unwrap_or(D65_WHITE));
+
+    let scale = m.try_inverse().ok_or_else(degenerate_primaries)? * white;
+
+    Ok(m * Matrix3::from_diagonal(&scale))
+}
+
+/// Converts CIE L*a*b* to XYZ relative to the given reference white.
+fn lab_to_xyz(l: f64, a: f64, b: f64, white: &Vector3<f64>) -> Vector3<f64> {
+    const DELTA: f64 = 6.0 / 29.0;
+    let f_inv = |t: f64| {
+        if t > DELTA {
+            t.powi(3)
+        } else {
+            3.0 * DELTA * DELTA * (t - 4.0 / 29.0)
+        }
+    };
+
+    let fy = (l + 16.0) / 116.0;
+    let fx = fy + a / 500.0;
+    let fz = fy - b / 200.0;
+
+    Vector3::new(white.x * f_inv(fx), white.y * f_inv(fy), white.z * f_inv(fz))
+}
+
+fn xyz_to_linear(primaries: &Primaries, xyz: Vector3<f64>) -> Result<(f32, f32, f32), psydkError> {
+    let xyz_to_rgb = rgb_to_xyz_matrix(primaries)?
+        .try_inverse()
+        .ok_or_else(degenerate_primaries)?;
+    let rgb = xyz_to_rgb * xyz;
+    Ok((rgb.x as f32, rgb.y as f32, rgb.z as f32))
+}
+
+/// Converts DKL coordinates to linear RGB values. The luminance axis scales
+/// all cones by the same contrast, the L-M axis changes L and M in opposite
+/// directions without changing luminance, and the S axis only changes S.
+fn dkl_to_linear(
+    primaries: &Primaries,
+    elevation: f32,
+    azimuth: f32,
+    contrast: f32,
+    background: f32,
+) -> Result<(f32, f32, f32), psydkError> {
+    let rgb_to_lms = XYZ_TO_LMS * rgb_to_xyz_matrix(primaries)?;
+    let lms_to_rgb = rgb_to_lms.try_inverse().ok_or_else(degenerate_primaries)?;
+
+    let background = rgb_to_lms * Vector3::repeat(background as f64);
+
+    let (elevation, azimuth, contrast) = (
+        (elevation as f64).to_radians(),
+        (azimuth as f64).to_radians(),
+        contrast as f64,
+    );
+    let lum = contrast * elevation.sin();
+    let l_m = contrast * elevation.cos() * azimuth.cos();
+    let s = contrast * elevation.cos() * azimuth.sin();
+
+    let delta = Vector3::new(
+        background.x * (lum + l_m),
+        background.y * lum - background.x * l_m,
+        background.z * (lum + s),
+    );
+
+    let rgb = lms_to_rgb * (background + delta);
+    Ok((rgb.x as f32, rgb.y as f32, rgb.z as f32))
+}
@@ -1 +1,316 @@
+use psydk_proc::FromPyStr;
+use pyo3::{exceptions::PyValueError, prelude::*};
+use renderer::brushes::ColorStop;
+use strum_macros::EnumString;
 
+use super::{
+    color::{IntoLinRgba, LinRgba},
+    geometry::{Axis, IntoSize, Size},
+    window::{PhysicalScreen, PixelSize},
+};
+
+/// How a gradient is continued outside of its geometry.
+#[derive(EnumString, Debug, Clone, Copy, PartialEq, Default, FromPyStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum GradientExtend {
+    /// The colors of the first and last stop are continued.
+    #[default]
+    Pad,
+    /// The gradient is repeated.
+    Repeat,
+    /// The gradient is repeated, mirrored every other time.
+    Reflect,
+}
+
+impl From<GradientExtend> for renderer::brushes::Extend {
+    fn from(extend: GradientExtend) -> Self {
+        match extend {
+            GradientExtend::Pad => renderer::brushes::Extend::Pad,
+            GradientExtend::Repeat => renderer::brushes::Extend::Repeat,
+            GradientExtend::Reflect => renderer::brushes::Extend::Reflect,
+        }
+    }
+}
+
+/// The geometry of a gradient, relative to the position of the stimulus it fills.
+#[derive(Debug, Clone)]
+pub enum GradientKind {
+    /// Colors change along the line from (x1, y1) to (x2, y2).
+    Linear { x1: Size, y1: Size, x2: Size, y2: Size },
+    /// Colors change from the center (x, y) outwards to the radius.
+    Radial { x: Size, y: Size, radius: Size },
+    /// Colors change around the center (x, y), between two angles in degrees.
+    Sweep {
+        x: Size,
+        y: Size,
+        start_angle: f32,
+        end_angle: f32,
+    },
+}
+
+/// The color stops of a gradient, given either as a list of colors (which
+/// are spread evenly) or as a list of (offset, color) tuples.
+pub struct IntoColorStops(Vec<(f32, LinRgba)>);
+
+impl<'py> FromPyObject<'py> for IntoColorStops {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let stops = if let Ok(stops) = ob.extract::<Vec<(f32, IntoLinRgba)>>() {
+            stops
+                .into_iter()
+                .map(|(offset, color)| (offset, color.into()))
+                .collect()
+        } else {
+            let colors = ob.extract::<Vec<IntoLinRgba>>()?;
+            let n = colors.len().max(2) - 1;
+            colors
+                .into_iter()
+                .enumerate()
+                .map(|(i, color)| (i as f32 / n as f32, color.into()))
+                .collect::<Vec<_>>()
+        };
+
+        if stops.len() < 2 {
+            return Err(PyValueError::new_err("A gradient needs at least two color stops"));
+        }
+
+        if stops.iter().any(|(offset, _)| !(0.0..=1.0).contains(offset)) {
+            return Err(PyValueError::new_err("Color stop offsets must be between 0.0 and 1.0"));
+        }
+
+        if stops.windows(2).any(|pair| pair[0].0 > pair[1].0) {
+            return Err(PyValueError::new_err("Color stop offsets must be in increasing order"));
+        }
+
+        Ok(Self(stops))
+    }
+}
+
+#[pyclass(module = "psydk.visual.fill")]
+#[derive(Debug, Clone)]
+/// A gradient that can be used as the fill color of shape and pattern
+/// stimuli.
+///
+/// Use `Gradient.linear`, `Gradient.radial` or `Gradient.sweep` to create a
+/// gradient. Its geometry is relative to the position of the stimulus.
+pub struct Gradient {
+    kind: GradientKind,
+    stops: Vec<(f32, LinRgba)>,
+    extend: GradientExtend,
+}
+
+impl Gradient {
+    pub fn new(kind: GradientKind, stops: Vec<(f32, LinRgba)>, extend: GradientExtend) -> Self {
+        Self { kind, stops, extend }
+    }
+
+    /// Returns the renderer gradient for a stimulus at (x, y) in pixels.
+    /// The colors are passed through `resolve_color`, so that calibrated
+    /// colors are converted before drawing.
+    pub fn to_renderer_gradient(
+        &self,
+        x: f64,
+        y: f64,
+        window_size: PixelSize,
+        screen: PhysicalScreen,
+        mut resolve_color: impl FnMut(LinRgba) -> LinRgba,
+    ) -> renderer::brushes::Gradient {
+        let extent = |size: &Size, start: f64, axis: Axis| size.eval_extent(start as f32, axis, window_size, screen);
+        let point = |px: &Size, py: &Size| (x as f32 + extent(px, x, Axis::X), y as f32 + extent(py, y, Axis::Y));
+
+        let kind = match &self.kind {
+            GradientKind::Linear { x1, y1, x2, y2 } => renderer::brushes::GradientKind::Linear {
+                start: point(x1, y1).into(),
+                end: point(x2, y2).into(),
+            },
+            GradientKind::Radial { x: cx, y: cy, radius } => {
+                let center = point(cx, cy);
+                renderer::brushes::GradientKind::Radial {
+                    center: center.into(),
+                    radius: extent(radius, center.0 as f64, Axis::X),
+                }
+            }
+            GradientKind::Sweep {
+                x: cx,
+                y: cy,
+                start_angle,
+                end_angle,
+            } => renderer::brushes::GradientKind::Sweep {
+                center: point(cx, cy).into(),
+                start_angle: *start_angle,
+                end_angle: *end_angle,
+            },
+        };
+
+        renderer::brushes::Gradient {
+            extend: self.extend.into(),
+            kind,
+            stops: self
+                .stops
+                .iter()
+                .map(|(offset, color)| ColorStop {
+                    offset: *offset,
+                    color: resolve_color(*color).into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl Gradient {
+    /// Create a gradient along a line.
+    ///
+    /// Parameters
+    /// ----------
+    /// x1 : Size
+    ///    The x-coordinate of the start of the line.
+    /// y1 : Size
+    ///    The y-coordinate of the start of the line.
+    /// x2 : Size
+    ///    The x-coordinate of the end of the line.
+    /// y2 : Size
+    ///    The y-coordinate of the end of the line.
+    /// stops : list
+    ///    The colors of the gradient, either as a list of colors that are spread
+    ///    evenly or as a list of (offset, color) tuples with offsets from 0.0 to 1.0.
+    /// extend : str, optional
+    ///    How the gradient continues beyond the line: "pad", "repeat" or "reflect".
+    ///
+    /// Returns
+    /// -------
+    /// Gradient
+    ///   The linear gradient.
+    ///
+    /// Examples
+    /// --------
+    /// >>> ramp = Gradient.linear(-200, 0, 200, 0, ["black", "white"])
+    #[staticmethod]
+    #[pyo3(signature = (x1, y1, x2, y2, stops, extend = GradientExtend::default()))]
+    fn linear(
+        x1: IntoSize,
+        y1: IntoSize,
+        x2: IntoSize,
+        y2: IntoSize,
+        stops: IntoColorStops,
+        extend: GradientExtend,
+    ) -> Gradient {
+        Gradient::new(
+            GradientKind::Linear {
+                x1: x1.into(),
+                y1: y1.into(),
+                x2: x2.into(),
+                y2: y2.into(),
+            },
+            stops.0,
+            extend,
+        )
+    }
+
+    /// Create a gradient that radiates from a center.
+    ///
+    /// Parameters
+    /// ----------
+    /// x : Size
+    ///    The x-coordinate of the center.
+    /// y : Size
+    ///    The y-coordinate of the center.
+    /// radius : Size
+    ///    The radius at which the last color is reached.
+    /// stops : list
+    ///    The colors of the gradient, either as a list of colors that are spread
+    ///    evenly or as a list of (offset, color) tuples with offsets from 0.0 to 1.0.
+    /// extend : str, optional
+    ///    How the gradient continues beyond the radius: "pad", "repeat" or "reflect".
+    ///
+    /// Returns
+    /// -------
+    /// Gradient
+    ///   The radial gradient.
+    ///
+    /// Examples
+    /// --------
+    /// >>> vignette = Gradient.radial(0, 0, 300, [(0.6, (0, 0, 0, 0)), (1.0, "black")])
+    #[staticmethod]
+    #[pyo3(signature = (x, y, radius, stops, extend = GradientExtend::default()))]
+    fn radial(x: IntoSize, y: IntoSize, radius: IntoSize, stops: IntoColorStops, extend: GradientExtend) -> Gradient {
+        Gradient::new(
+            GradientKind::Radial {
+                x: x.into(),
+                y: y.into(),
+                radius: radius.into(),
+            },
+            stops.0,
+            extend,
+        )
+    }
+
+    /// Create a gradient that sweeps around a center.
+    ///
+    /// Parameters
+    /// ----------
+    /// x : Size
+    ///    The x-coordinate of the center.
+    /// y : Size
+    ///    The y-coordinate of the center.
+    /// stops : list
+    ///    The colors of the gradient, either as a list of colors that are spread
+    ///    evenly or as a list of (offset, color) tuples with offsets from 0.0 to 1.0.
+    /// start_angle : float, optional
+    ///    The angle of the first color in degrees, clockwise from the positive x-axis.
+    /// end_angle : float, optional
+    ///    The angle of the last color in degrees, clockwise from the positive x-axis.
+    /// extend : str, optional
+    ///    How the gradient continues beyond the angles: "pad", "repeat" or "reflect".
+    ///
+    /// Returns
+    /// -------
+    /// Gradient
+    ///   The sweep gradient.
+    #[staticmethod]
+    #[pyo3(signature = (x, y, stops, start_angle = 0.0, end_angle = 360.0, extend = GradientExtend::default()))]
+    fn sweep(
+        x: IntoSize,
+        y: IntoSize,
+        stops: IntoColorStops,
+        start_angle: f32,
+        end_angle: f32,
+        extend: GradientExtend,
+    ) -> Gradient {
+        Gradient::new(
+            GradientKind::Sweep {
+                x: x.into(),
+                y: y.into(),
+                start_angle,
+                end_angle,
+            },
+            stops.0,
+            extend,
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        let kind = match self.kind {
+            GradientKind::Linear { .. } => "linear",
+            GradientKind::Radial { .. } => "radial",
+            GradientKind::Sweep { .. } => "sweep",
+        };
+        format!("Gradient.{}(<{} stops>)", kind, self.stops.len())
+    }
+}
+
+/// A fill that is either a single color or a gradient.
+#[derive(FromPyObject)]
+pub enum IntoFill {
+    Gradient(Gradient),
+    Color(IntoLinRgba),
+}
+
+impl IntoFill {
+    /// Splits the fill into a color and a gradient, one of which is set.
+    pub fn split(self) -> (Option<LinRgba>, Option<Gradient>) {
+        match self {
+            IntoFill::Gradient(gradient) => (None, Some(gradient)),
+            IntoFill::Color(color) => (Some(color.into()), None),
+        }
+    }
+}
@@ -2,9 +2,13 @@
 //! geometry of a stimulus. This includes rectangles, circles, and
 //! transformations.
 
+use std::ops::Range;
+
 use nalgebra::{Matrix3, Vector3};
 use num_traits::Float;
+use psydk_proc::FromPyStr;
 use pyo3::{prelude::*, PyClass};
+use strum::EnumString;
 
 use super::window::{PhysicalScreen, PixelSize, Window};
 
@@ -33,10 +37,10 @@ pub enum NumberOrSize {
 }
 
 impl NumberOrSize {
-    pub fn eval(&self, window_size: PixelSize, window_props: PhysicalScreen) -> f32 {
+    pub fn eval(&self, axis: Axis, window_size: PixelSize, window_props: PhysicalScreen) -> f32 {
         match self {
             NumberOrSize::Dimensionless(value) => *value,
-            NumberOrSize::Size(size) => size.eval(window_size, window_props),
+            NumberOrSize::Size(size) => size.eval(axis, window_size, window_props),
         }
     }
 }
@@ -83,6 +87,8 @@ pub enum Size {
     Millimeters(f32),
     /// Centimeters.
     Centimeters(f32),
+    /// Meters.
+    Meters(f32),
     /// Inches.
     Inches(f32),
     /// Points.
@@ -97,6 +103,150 @@ pub enum Size {
     Difference(BoxedSize, BoxedSize),
 }
 
+/// The unit of a single-valued `Size`. In Python, units can be called or
+/// multiplied by a number to create a size (e.g., `px(100)` or `100*px`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[pyclass(module = "psydk.size", eq, eq_int)]
+pub enum Unit {
+    /// Physical pixels (`px`).
+    Pixels,
+    /// Fraction of the screen width (`vw` or `sw`).
+    ViewportWidth,
+    /// Fraction of the screen height (`vh` or `sh`).
+    ViewportHeight,
+    /// Degrees of visual angle (`deg`).
+    Degrees,
+    /// Millimeters (`mm`).
+    Millimeters,
+    /// Centimeters (`cm`).
+    Centimeters,
+    /// Meters (`m`).
+    Meters,
+    /// Inches (`in` or `inch`).
+    Inches,
+    /// Points (`pt`).
+    Points,
+}
+
+impl Unit {
+    /// Returns the unit with the given symbol (e.g., `cm`), or `None` if the
+    /// symbol is unknown.
+    pub fn from_symbol(symbol: &str) -> Option<Unit> {
+        match symbol {
+            "px" => Some(Unit::Pixels),
+            "vw" | "sw" => Some(Unit::ViewportWidth),
+            "vh" | "sh" => Some(Unit::ViewportHeight),
+            "deg" => Some(Unit::Degrees),
+            "mm" => Some(Unit::Millimeters),
+            "cm" => Some(Unit::Centimeters),
+            "m" => Some(Unit::Meters),
+            "in" | "inch" => Some(Unit::Inches),
+            "pt" => Some(Unit::Points),
+            _ => None,
+        }
+    }
+
+    /// The symbol of the unit, as used in size expressions.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::Pixels => "px",
+            Unit::ViewportWidth => "vw",
+            Unit::ViewportHeight => "vh",
+            Unit::Degrees => "deg",
+            Unit::Millimeters => "mm",
+            Unit::Centimeters => "cm",
+            Unit::Meters => "m",
+            Unit::Inches => "in",
+            Unit::Points => "pt",
+        }
+    }
+
+    /// Creates a size with the given value in this unit.
+    pub fn size(&self, value: f32) -> Size {
+        match self {
+            Unit::Pixels => Size::Pixels(value),
+            Unit::ViewportWidth => Size::ViewportWidth(value),
+            Unit::ViewportHeight => Size::ViewportHeight(value),
+            Unit::Degrees => Size::Degrees(value),
+            Unit::Millimeters => Size::Millimeters(value),
+            Unit::Centimeters => Size::Centimeters(value),
+            Unit::Meters => Size::Meters(value),
+            Unit::Inches => Size::Inches(value),
+            Unit::Points => Size::Points(value),
+        }
+    }
+
+    /// Converts a number of pixels along the given axis to this unit. This is
+    /// the inverse of `Size::eval`.
+    pub fn from_pixels(&self, pixels: f32, axis: Axis, window_size: PixelSize, window_props: PhysicalScreen) -> f32 {
+        match self {
+            // visual angle is not proportional to the size on screen
+            Unit::Degrees => {
+                let millimeters = Unit::Millimeters.from_pixels(pixels, axis, window_size, window_props);
+                2.0 * (millimeters / (2.0 * window_props.viewing_distance))
+                    .atan()
+                    .to_degrees()
+            }
+            unit => pixels / unit.size(1.0).eval(axis, window_size, window_props),
+        }
+    }
+}
+
+#[pymethods]
+impl Unit {
+    fn __call__(&self, value: f32) -> Size {
+        self.size(value)
+    }
+
+    fn __mul__(&self, value: f32) -> Size {
+        self.size(value)
+    }
+
+    fn __rmul__(&self, value: f32) -> Size {
+        self.size(value)
+    }
+
+    fn __repr__(&self) -> String {
+        self.symbol().to_string()
+    }
+}
+
+/// How degrees of visual angle are converted to pixels.
+#[derive(EnumString, Debug, Clone, Copy, PartialEq, Default, FromPyStr)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum AngleMode {
+    /// Every angle is converted as a chord centred on the line of sight
+    /// (`2·d·tan(θ/2)`), regardless of where it is on the screen.
+    #[default]
+    Chord,
+    /// Positions are eccentricities relative to the fixation point, i.e., a
+    /// point at eccentricity `e` is `d·tan(e)` away from the fixation point.
+    /// Extents are measured between the eccentricities of their edges.
+    Eccentricity,
+}
+
+impl AngleMode {
+    /// Returns the name of the mode as used in Python.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AngleMode::Chord => "chord",
+            AngleMode::Eccentricity => "eccentricity",
+        }
+    }
+}
+
+/// A screen axis. Physical units are converted with the pixel density along
+/// the axis, which differs between the axes for non-square pixels.
+#[derive(EnumString, Debug, Clone, Copy, PartialEq, Default, FromPyStr)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum Axis {
+    /// The horizontal axis (widths and x-coordinates).
+    #[default]
+    X,
+    /// The vertical axis (heights and y-coordinates).
+    Y,
+}
+
 pub struct IntoSize(pub Size);
 
 impl From<IntoSize> for Size {
@@ -117,9 +267,11 @@ impl<'py> FromPyObject<'py> for IntoSize {
         } else if let Ok(value) = ob.extract::<i32>() {
             return Ok(IntoSize(Size::Pixels(value as f32)));
         }
-        // try to extract a string and use a regex to parse it
+        // try to extract a string and parse it as a size expression
         else if let Ok(value) = ob.extract::<String>() {
-            Ok(IntoSize(Size::from_str(&value).unwrap()))
+            Size::from_str(&value)
+                .map(IntoSize)
+                .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
         } else {
             return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>("Size must be a float."));
         }
@@ -256,43 +408,49 @@ impl Size {
     ///
     /// # Arguments
     ///
+    /// * `axis` - The axis along which the size is measured. Physical units
+    ///   use the pixel density of that axis.
     /// * `props` - The physical properties of the window.
     ///
     /// # Returns
     ///
     /// The unit converted to pixels (as a float).
-    pub fn eval(&self, window_size: PixelSize, window_props: PhysicalScreen) -> f32 {
+    pub fn eval(&self, axis: Axis, window_size: PixelSize, window_props: PhysicalScreen) -> f32 {
         match self {
             Size::Pixels(pixels) => *pixels,
             Size::ViewportWidth(normalised) => *normalised * window_size.width as f32,
             Size::ViewportHeight(normalised) => *normalised * window_size.height as f32,
             Size::Degrees(degrees) => {
-                Size::angle_to_milimeter(*degrees, window_props.viewing_distance).eval(window_size, window_props)
+                Size::angle_to_milimeter(*degrees, window_props.viewing_distance).eval(axis, window_size, window_props)
             }
-            Size::Millimeters(millimeters) => {
-                *millimeters * window_size.width as f32 / window_props.width(window_size.width)
+            Size::Millimeters(millimeters) => match axis {
+                Axis::X => *millimeters * window_size.width as f32 / window_props.width(window_size.width),
+                Axis::Y => *millimeters * window_size.height as f32 / window_props.height(window_size.height),
+            },
+            Size::Centimeters(centimeters) => {
+                Size::Millimeters(*centimeters * 10.0).eval(axis, window_size, window_props)
             }
-            Size::Centimeters(centimeters) => Size::Millimeters(*centimeters * 10.0).eval(window_size, window_props),
-            Size::Inches(inches) => Size::Millimeters(*inches * 25.4).eval(window_size, window_props),
-            Size::Points(points) => Size::Inches(*points / 72.0).eval(window_size, window_props),
+            Size::Meters(meters) => Size::Millimeters(*meters * 1000.0).eval(axis, window_size, window_props),
+            Size::Inches(inches) => Size::Millimeters(*inches * 25.4).eval(axis, window_size, window_props),
+            Size::Points(points) => Size::Inches(*points / 72.0).eval(axis, window_size, window_props),
             Size::Quotient(a, b) => {
                 // first, we resolve `a` to pixels, the we divide by b
-                let a = a.eval(window_size, window_props);
+                let a = a.eval(axis, window_size, window_props);
                 a / b
             }
             Size::Product(a, b) => {
                 // first, we resolve `a` to pixels, the we multiply with b
-                let a = a.eval(window_size, window_props);
+                let a = a.eval(axis, window_size, window_props);
                 a * b
             }
             Size::Sum(a, b) => {
-                let a = a.eval(window_size, window_props);
-                let b = b.eval(window_size, window_props);
+                let a = a.eval(axis, window_size, window_props);
+                let b = b.eval(axis, window_size, window_props);
                 a + b
             }
             Size::Difference(a, b) => {
-                let a = a.eval(window_size, window_props);
-                let b = b.eval(window_size, window_props);
+                let a = a.eval(axis, window_size, window_props);
+                let b = b.eval(axis, window_size, window_props);
                 a - b
             }
         }
@@ -303,32 +461,388 @@ impl Size {
         Size::Pixels(value)
     }
 
+    /// Parses a size expression such as `"10px"`, `"50vw - 2cm"`, `"3deg * 2"`
+    /// or `"-(1vh / 4)"`. Sizes can be added and subtracted, and multiplied or
+    /// divided by numbers. Numbers without a unit are pixels. The error
+    /// message points at the offending part of the expression.
     pub fn from_str(string: &str) -> Result<Size, String> {
-        let string = string.trim();
-        // check if negative
-        let negative = string.starts_with('-');
-        let string = if negative { &string[1..] } else { string };
-
-        // extract the number and the unit
-        let (number, unit) = string.split_at(
-            string
-                .find(|c: char| !c.is_ascii_digit() && c != '.')
-                .unwrap_or(string.len()),
-        );
-        let number = number.parse::<f32>().map_err(|e| e.to_string())?;
-        let unit = unit.trim();
+        SizeParser::new(string)?.parse()
+    }
+
+    /// Creates a size from a value and the name of its unit. Returns `None`
+    /// if the unit is unknown.
+    pub fn from_unit(value: f32, unit: &str) -> Option<Size> {
+        Unit::from_symbol(unit).map(|unit| unit.size(value))
+    }
+
+    /// Returns the value and the unit if the size consists of a single value,
+    /// or `None` for sums, differences, products and quotients.
+    pub fn value_and_unit(&self) -> Option<(f32, Unit)> {
+        match *self {
+            Size::Pixels(v) => Some((v, Unit::Pixels)),
+            Size::ViewportWidth(v) => Some((v, Unit::ViewportWidth)),
+            Size::ViewportHeight(v) => Some((v, Unit::ViewportHeight)),
+            Size::Degrees(v) => Some((v, Unit::Degrees)),
+            Size::Millimeters(v) => Some((v, Unit::Millimeters)),
+            Size::Centimeters(v) => Some((v, Unit::Centimeters)),
+            Size::Meters(v) => Some((v, Unit::Meters)),
+            Size::Inches(v) => Some((v, Unit::Inches)),
+            Size::Points(v) => Some((v, Unit::Points)),
+            _ => None,
+        }
+    }
+
+    /// Converts the size along the given axis to a single value in the given
+    /// unit for a window with the given size and physical properties.
+    pub fn to(&self, unit: Unit, axis: Axis, window_size: PixelSize, window_props: PhysicalScreen) -> Size {
+        let pixels = self.eval(axis, window_size, window_props);
+        unit.size(unit.from_pixels(pixels, axis, window_size, window_props))
+    }
+
+    /// Converts the size to pixels when it is used as a position along the
+    /// given axis. In `AngleMode::Eccentricity`, degrees are eccentricities
+    /// relative to the fixation point and any further terms of a sum are
+    /// extents starting at that position (e.g., `5deg + 1cm` is 1 cm to the
+    /// right of the point at 5° eccentricity). Otherwise, this is the same as
+    /// `eval`.
+    pub fn eval_position(&self, axis: Axis, window_size: PixelSize, window_props: PhysicalScreen) -> f32 {
+        if window_props.angle_mode == AngleMode::Chord {
+            return self.eval(axis, window_size, window_props);
+        }
+
+        match self {
+            Size::Degrees(degrees) => {
+                let (fixation, distance) = window_props.eccentricity_frame(axis);
+                fixation + distance * degrees.to_radians().tan()
+            }
+            Size::Sum(a, b) => {
+                let start = a.eval_position(axis, window_size, window_props);
+                start + b.eval_extent(start, axis, window_size, window_props)
+            }
+            Size::Difference(a, b) => {
+                let start = a.eval_position(axis, window_size, window_props);
+                start + b.scaled(-1.0).eval_extent(start, axis, window_size, window_props)
+            }
+            Size::Quotient(..) | Size::Product(..) => self.scaled(1.0).eval_position(axis, window_size, window_props),
+            size => size.eval(axis, window_size, window_props),
+        }
+    }
+
+    /// Converts the size to pixels when it is used as an extent along the
+    /// given axis that starts at `start` (in pixels). In
+    /// `AngleMode::Eccentricity`, an extent in degrees spans the difference
+    /// between the eccentricities of its two edges, so the same angle covers
+    /// more pixels in the periphery than at fixation. Otherwise, this is the
+    /// same as `eval`.
+    pub fn eval_extent(&self, start: f32, axis: Axis, window_size: PixelSize, window_props: PhysicalScreen) -> f32 {
+        if window_props.angle_mode == AngleMode::Chord {
+            return self.eval(axis, window_size, window_props);
+        }
+
+        match self {
+            Size::Degrees(degrees) => {
+                let (fixation, distance) = window_props.eccentricity_frame(axis);
+                let start_eccentricity = ((start - fixation) / distance).atan();
+                fixation + distance * (start_eccentricity + degrees.to_radians()).tan() - start
+            }
+            Size::Sum(a, b) => {
+                let a = a.eval_extent(start, axis, window_size, window_props);
+                a + b.eval_extent(start + a, axis, window_size, window_props)
+            }
+            Size::Difference(a, b) => {
+                let a = a.eval_extent(start, axis, window_size, window_props);
+                a + b.scaled(-1.0).eval_extent(start + a, axis, window_size, window_props)
+            }
+            Size::Quotient(..) | Size::Product(..) => {
+                self.scaled(1.0).eval_extent(start, axis, window_size, window_props)
+            }
+            size => size.eval(axis, window_size, window_props),
+        }
+    }
+
+    /// Returns the size with every single value multiplied by the factor, so
+    /// that products and quotients only remain as sums and differences of
+    /// single values.
+    fn scaled(&self, factor: f32) -> Size {
+        match self {
+            Size::Quotient(a, b) => a.scaled(factor / b),
+            Size::Product(a, b) => a.scaled(factor * b),
+            Size::Sum(a, b) => Size::Sum(BoxedSize::new(a.scaled(factor)), BoxedSize::new(b.scaled(factor))),
+            Size::Difference(a, b) => {
+                Size::Difference(BoxedSize::new(a.scaled(factor)), BoxedSize::new(b.scaled(factor)))
+            }
+            size => {
+                let (value, unit) = size.value_and_unit().expect("a single value");
+                unit.size(value * factor)
+            }
+        }
+    }
+
+    /// Negates the size. Unlike the `-` operator, this keeps single units as
+    /// they are (e.g., `-2cm` instead of `2cm * -1`).
+    fn negated(self) -> Size {
+        match self.value_and_unit() {
+            Some((value, unit)) => unit.size(-value),
+            None => -self,
+        }
+    }
+}
+
+impl std::fmt::Display for Size {
+    /// Formats the size as an expression that can be parsed by `Size::from_str`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // sums and differences need parentheses when they are operands
+        let operand = |size: &Size| match size {
+            Size::Sum(..) | Size::Difference(..) => format!("({})", size),
+            size => size.to_string(),
+        };
+
+        match self {
+            Size::Sum(a, b) => write!(f, "{} + {}", **a, **b),
+            Size::Difference(a, b) => write!(f, "{} - {}", **a, operand(b)),
+            Size::Product(a, factor) => write!(f, "{} * {}", operand(a), factor),
+            Size::Quotient(a, divisor) => write!(f, "{} / {}", operand(a), divisor),
+            size => {
+                let (value, unit) = size.value_and_unit().unwrap();
+                write!(f, "{}{}", value, unit.symbol())
+            }
+        }
+    }
+}
+
+/// A token of a size expression.
+#[derive(Debug, Clone, PartialEq)]
+enum SizeToken {
+    Number(f32),
+    Unit(String),
+    Operator(char),
+    OpenParen,
+    CloseParen,
+    End,
+}
+
+/// A recursive descent parser for size expressions:
+///
+/// ```text
+/// expression := term (("+" | "-") term)*
+/// term       := unary (("*" | "/") unary)*
+/// unary      := ("-" | "+") unary | primary
+/// primary    := number [unit] | "(" expression ")"
+/// ```
+struct SizeParser<'a> {
+    input: &'a str,
+    tokens: Vec<(SizeToken, Range<usize>)>,
+    position: usize,
+}
+
+impl<'a> SizeParser<'a> {
+    fn new(input: &'a str) -> Result<Self, String> {
+        let mut tokens = Vec::new();
+        let mut chars = input.char_indices().peekable();
+
+        while let Some(&(start, c)) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+            } else if c.is_ascii_digit() || c == '.' {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_ascii_digit() || c == '.') {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                let number = input[start..end]
+                    .parse::<f32>()
+                    .map_err(|_| Self::error(input, &(start..end), "invalid number"))?;
+                tokens.push((SizeToken::Number(number), start..end));
+            } else if c.is_alphabetic() {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !c.is_alphabetic() {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push((SizeToken::Unit(input[start..end].to_string()), start..end));
+            } else {
+                let token = match c {
+                    '+' | '-' | '*' | '/' => SizeToken::Operator(c),
+                    '(' => SizeToken::OpenParen,
+                    ')' => SizeToken::CloseParen,
+                    _ => {
+                        return Err(Self::error(
+                            input,
+                            &(start..start + c.len_utf8()),
+                            &format!("unexpected character '{}'", c),
+                        ))
+                    }
+                };
+                tokens.push((token, start..start + c.len_utf8()));
+                chars.next();
+            }
+        }
+        tokens.push((SizeToken::End, input.len()..input.len() + 1));
+
+        Ok(Self {
+            input,
+            tokens,
+            position: 0,
+        })
+    }
+
+    /// Formats an error message that marks the given range of the input.
+    fn error(input: &str, range: &Range<usize>, message: &str) -> String {
+        let start = input[..range.start.min(input.len())].chars().count();
+        let width = input.get(range.clone()).map_or(1, |token| token.chars().count()).max(1);
+        format!(
+            "Invalid size \"{}\" at position {}: {}\n  {}\n  {}{}",
+            input,
+            start,
+            message,
+            input,
+            " ".repeat(start),
+            "^".repeat(width)
+        )
+    }
+
+    fn peek(&self) -> &SizeToken {
+        &self.tokens[self.position].0
+    }
+
+    fn next(&mut self) -> (SizeToken, Range<usize>) {
+        let token = self.tokens[self.position].clone();
+        self.position = (self.position + 1).min(self.tokens.len() - 1);
+        token
+    }
+
+    fn parse(mut self) -> Result<Size, String> {
+        let (_, value) = self.expression()?;
+        match self.next() {
+            (SizeToken::End, _) => {}
+            (_, range) => return Err(Self::error(self.input, &range, "expected an operator")),
+        }
+
+        // numbers without a unit are pixels
+        Ok(Self::into_size(value))
+    }
+
+    /// Parses an expression and returns it with the range of the input it spans.
+    fn expression(&mut self) -> Result<(Range<usize>, NumberOrSize), String> {
+        let (mut range, mut lhs) = self.term()?;
+
+        while let SizeToken::Operator(op @ ('+' | '-')) = *self.peek() {
+            self.next();
+            let (rhs_range, rhs) = self.term()?;
+            range = range.start..rhs_range.end;
+
+            // numbers are pixels when they are added to a size
+            lhs = match (lhs, rhs) {
+                (NumberOrSize::Dimensionless(a), NumberOrSize::Dimensionless(b)) => {
+                    NumberOrSize::Dimensionless(if op == '+' { a + b } else { a - b })
+                }
+                (a, b) => {
+                    let (a, b) = (Self::into_size(a), Self::into_size(b));
+                    NumberOrSize::Size(if op == '+' { a + b } else { a - b })
+                }
+            };
+        }
+
+        Ok((range, lhs))
+    }
+
+    fn term(&mut self) -> Result<(Range<usize>, NumberOrSize), String> {
+        let (mut range, mut lhs) = self.unary()?;
+
+        while let SizeToken::Operator(op @ ('*' | '/')) = *self.peek() {
+            self.next();
+            let (rhs_range, rhs) = self.unary()?;
+
+            lhs = match (lhs, rhs, op) {
+                (NumberOrSize::Dimensionless(a), NumberOrSize::Dimensionless(b), '*') => {
+                    NumberOrSize::Dimensionless(a * b)
+                }
+                (NumberOrSize::Dimensionless(a), NumberOrSize::Dimensionless(b), _) => {
+                    NumberOrSize::Dimensionless(a / b)
+                }
+                (NumberOrSize::Size(a), NumberOrSize::Dimensionless(b), '*')
+                | (NumberOrSize::Dimensionless(b), NumberOrSize::Size(a), '*') => NumberOrSize::Size(a * b),
+                (NumberOrSize::Size(a), NumberOrSize::Dimensionless(b), _) => NumberOrSize::Size(a / b),
+                (NumberOrSize::Size(_), NumberOrSize::Size(_), '*') => {
+                    return Err(Self::error(
+                        self.input,
+                        &rhs_range,
+                        "a size can only be multiplied by a number",
+                    ))
+                }
+                (_, NumberOrSize::Size(_), _) => {
+                    return Err(Self::error(self.input, &rhs_range, "can not divide by a size"))
+                }
+            };
+            range = range.start..rhs_range.end;
+        }
+
+        Ok((range, lhs))
+    }
 
-        // match the unit
-        match unit {
-            "px" => Ok(Size::Pixels(if negative { -number } else { number })),
-            "vw" => Ok(Size::ViewportWidth(if negative { -number } else { number })),
-            "vh" => Ok(Size::ViewportHeight(if negative { -number } else { number })),
-            "deg" => Ok(Size::Degrees(if negative { -number } else { number })),
-            "mm" => Ok(Size::Millimeters(if negative { -number } else { number })),
-            "cm" => Ok(Size::Centimeters(if negative { -number } else { number })),
-            "in" => Ok(Size::Inches(if negative { -number } else { number })),
-            "pt" => Ok(Size::Points(if negative { -number } else { number })),
-            _ => Err(format!("Unknown unit: {}", unit)),
+    fn unary(&mut self) -> Result<(Range<usize>, NumberOrSize), String> {
+        match *self.peek() {
+            SizeToken::Operator(op @ ('+' | '-')) => {
+                let (_, op_range) = self.next();
+                let (range, value) = self.unary()?;
+                let value = match (op, value) {
+                    ('+', value) => value,
+                    (_, NumberOrSize::Dimensionless(value)) => NumberOrSize::Dimensionless(-value),
+                    (_, NumberOrSize::Size(size)) => NumberOrSize::Size(size.negated()),
+                };
+                Ok((op_range.start..range.end, value))
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<(Range<usize>, NumberOrSize), String> {
+        match self.next() {
+            (SizeToken::Number(value), range) => {
+                if let SizeToken::Unit(unit) = self.peek().clone() {
+                    let (_, unit_range) = self.next();
+                    let size = Size::from_unit(value, &unit).ok_or_else(|| {
+                        Self::error(
+                            self.input,
+                            &unit_range,
+                            &format!(
+                                "unknown unit \"{}\" (expected one of px, vw, sw, vh, sh, deg, mm, cm, m, in, inch or pt)",
+                                unit
+                            ),
+                        )
+                    })?;
+                    Ok((range.start..unit_range.end, NumberOrSize::Size(size)))
+                } else {
+                    Ok((range, NumberOrSize::Dimensionless(value)))
+                }
+            }
+            (SizeToken::OpenParen, open_range) => {
+                let (_, value) = self.expression()?;
+                match self.next() {
+                    (SizeToken::CloseParen, close_range) => Ok((open_range.start..close_range.end, value)),
+                    (_, range) => Err(Self::error(self.input, &range, "expected \")\"")),
+                }
+            }
+            (SizeToken::Unit(unit), range) => Err(Self::error(
+                self.input,
+                &range,
+                &format!("expected a number before the unit \"{}\"", unit),
+            )),
+            (SizeToken::End, range) => Err(Self::error(self.input, &range, "unexpected end of the size")),
+            (_, range) => Err(Self::error(self.input, &range, "expected a number or \"(\"")),
+        }
+    }
+
+    fn into_size(value: NumberOrSize) -> Size {
+        match value {
+            NumberOrSize::Dimensionless(value) => Size::Pixels(value),
+            NumberOrSize::Size(size) => size,
         }
     }
 }
@@ -338,13 +852,12 @@ impl Size {
     // constructors
     #[new]
     fn __new__(string: String) -> PyResult<Size> {
-        let size = Size::from_str(&string).unwrap();
-        Ok(size)
+        Size::from_str(&string).map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
     }
 
     // printing
     fn __repr__(&self) -> PyResult<String> {
-        Ok(format!("{:?}", self))
+        Ok(format!("Size(\"{}\")", self))
     }
 
     // addition
@@ -359,14 +872,115 @@ impl Size {
 
     // negation
     fn __neg__(&self) -> Size {
-        -self.clone()
+        self.clone().negated()
+    }
+
+    // multiplication and division by numbers
+    fn __mul__(&self, factor: f32) -> Size {
+        self.clone() * factor
+    }
+
+    fn __rmul__(&self, factor: f32) -> Size {
+        self.clone() * factor
+    }
+
+    fn __truediv__(&self, divisor: f32) -> PyResult<Size> {
+        if divisor == 0.0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyZeroDivisionError, _>(
+                "division of a size by zero",
+            ));
+        }
+        Ok(self.clone() / divisor)
+    }
+
+    // comparison (only sizes with a single value in the same unit can be ordered
+    // without a window, sizes in different units or compound sizes are unequal)
+    fn __richcmp__(&self, other: IntoSize, op: pyo3::basic::CompareOp) -> PyResult<bool> {
+        use pyo3::basic::CompareOp;
+
+        match (self.value_and_unit(), other.0.value_and_unit()) {
+            (Some((a, unit_a)), Some((b, unit_b))) if unit_a == unit_b => match a.partial_cmp(&b) {
+                Some(ordering) => Ok(op.matches(ordering)),
+                // NaN is unequal to everything and can not be ordered
+                None => Ok(op == CompareOp::Ne),
+            },
+            _ => match op {
+                CompareOp::Eq => Ok(false),
+                CompareOp::Ne => Ok(true),
+                _ => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Can not compare {} and {} without a window. Convert them to the same unit with .to(unit, window) first.",
+                    self, other.0
+                ))),
+            },
+        }
+    }
+
+    /// Evaluate the size in pixels.
+    ///
+    /// Parameters
+    /// ----------
+    /// window : Window
+    ///   The window whose size and physical properties are used.
+    /// axis : str, optional
+    ///   The axis along which the size is measured, "x" (default) or "y".
+    ///   This matters for physical units on screens with non-square pixels.
+    ///
+    /// Returns
+    /// -------
+    /// float
+    ///   The size in pixels.
+    #[pyo3(name = "eval", signature = (window, axis = Axis::X))]
+    fn py_eval(&self, window: &Window, axis: Axis) -> f32 {
+        let window_state = window.state.lock().unwrap();
+        self.eval(axis, window_state.size, window_state.physical_screen)
     }
 
-    // evaluation
-    #[pyo3(name = "eval")]
-    fn py_eval(&self, window: &Window) -> f32 {
+    /// Convert the size to another unit.
+    ///
+    /// Parameters
+    /// ----------
+    /// unit : Unit
+    ///   The unit to convert to, e.g., `psydk.size.cm`.
+    /// window : Window
+    ///   The window whose size and physical properties are used for the
+    ///   conversion.
+    /// axis : str, optional
+    ///   The axis along which the size is measured, "x" (default) or "y".
+    ///
+    /// Returns
+    /// -------
+    /// Size
+    ///   The size as a single value in the given unit.
+    ///
+    /// Examples
+    /// --------
+    /// >>> from psydk.size import px, cm
+    /// >>> width_cm = (100*px).to(cm, window)
+    /// >>> height_cm = (100*px).to(cm, window, axis="y")
+    #[pyo3(name = "to", signature = (unit, window, axis = Axis::X))]
+    fn py_to(&self, unit: Unit, window: &Window, axis: Axis) -> Size {
         let window_state = window.state.lock().unwrap();
-        self.eval(window_state.size, window_state.physical_screen)
+        self.to(unit, axis, window_state.size, window_state.physical_screen)
+    }
+
+    /// The numerical value of the size. Only available for sizes with a single
+    /// value, e.g., the result of `to()`.
+    #[getter]
+    #[pyo3(name = "value")]
+    fn py_value(&self) -> PyResult<f32> {
+        self.value_and_unit().map(|(value, _)| value).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "The size {} has no single value. Convert it to a unit with .to(unit, window) first.",
+                self
+            ))
+        })
+    }
+
+    /// The unit of the size, or None if the size combines several values.
+    #[getter]
+    #[pyo3(name = "unit")]
+    fn py_unit(&self) -> Option<Unit> {
+        self.value_and_unit().map(|(_, unit)| unit)
     }
 }
 
@@ -386,8 +1000,8 @@ impl SizeVector2D {
     /// The point converted to pixels.
     fn to_pixels(&self, window_size: PixelSize, window_props: PhysicalScreen) -> (f32, f32) {
         (
-            self.x.eval(window_size, window_props),
-            self.y.eval(window_size, window_props),
+            self.x.eval(Axis::X, window_size, window_props),
+            self.y.eval(Axis::Y, window_size, window_props),
         )
     }
 }
@@ -440,6 +1054,9 @@ pub enum Transformation2D {
     ShearPoint(f32, f32, Size, Size),
     /// Translation by x and y.
     Translation(Size, Size),
+    /// An affine transformation given by the first two rows of its matrix
+    /// (`a, b, tx, c, d, ty`), with the translation in pixels.
+    Matrix(f32, f32, f32, f32, f32, f32),
     /// Product of two transformations.
     Product(BoxedTransformation2D, BoxedTransformation2D),
 }
@@ -470,8 +1087,8 @@ impl Transformation2D {
                 let angle = angle.to_radians();
                 let cos = angle.cos();
                 let sin = angle.sin();
-                let x = x.eval(window_size, window_props);
-                let y = y.eval(window_size, window_props);
+                let x = x.eval(Axis::X, window_size, window_props);
+                let y = y.eval(Axis::Y, window_size, window_props);
 
                 Matrix3::new(
                     cos, -sin, x * (1.0 - cos) + y * sin,
@@ -516,8 +1133,8 @@ impl Transformation2D {
                 t3 * t2 * t1
             }
             Transformation2D::Translation(x, y) => {
-                let x = x.eval(window_size, window_props);
-                let y = y.eval(window_size, window_props);
+                let x = x.eval(Axis::X, window_size, window_props);
+                let y = y.eval(Axis::Y, window_size, window_props);
 
                 Matrix3::new(
                     1.0, 0.0, x,
@@ -525,6 +1142,13 @@ impl Transformation2D {
                     0.0, 0.0, 1.0,
                 )
             }
+            Transformation2D::Matrix(a, b, tx, c, d, ty) => {
+                Matrix3::new(
+                    *a, *b, *tx,
+                    *c, *d, *ty,
+                    0.0, 0.0, 1.0,
+                )
+            }
             Transformation2D::Product(a,b) =>
             {
                 let a = a.eval(window_size, window_props);
@@ -534,11 +1158,50 @@ impl Transformation2D {
         }
     }
 
+    /// Creates an affine transformation from a (homogeneous) 2D transformation
+    /// matrix in pixels. The last row of the matrix is ignored.
+    pub fn from_matrix(matrix: Matrix3<f32>) -> Transformation2D {
+        Transformation2D::Matrix(
+            matrix[(0, 0)],
+            matrix[(0, 1)],
+            matrix[(0, 2)],
+            matrix[(1, 0)],
+            matrix[(1, 1)],
+            matrix[(1, 2)],
+        )
+    }
+
+    /// Returns the inverse of the transformation for a window with the given
+    /// size and physical properties, or `None` if the transformation can not
+    /// be inverted (e.g., a scale by zero). As the inverse is computed from the
+    /// evaluated matrix, it is only valid as long as the window does not change.
+    pub fn inverse(&self, window_size: PixelSize, window_props: PhysicalScreen) -> Option<Transformation2D> {
+        self.eval(window_size, window_props)
+            .try_inverse()
+            .map(Transformation2D::from_matrix)
+    }
+
+    /// Maps a point (in pixels) through the transformation.
     pub fn transform_point(&self, x: f32, y: f32, window_size: PixelSize, window_props: PhysicalScreen) -> (f32, f32) {
-        let matrix = self.eval(window_size, window_props).transpose();
+        let matrix = self.eval(window_size, window_props);
         let newpoint = matrix * Vector3::new(x, y, 1.0);
         (newpoint.x, newpoint.y)
     }
+
+    /// Maps a point (in pixels) through the inverse of the transformation, i.e.,
+    /// returns the point that is mapped to (x, y). Returns `None` if the
+    /// transformation can not be inverted.
+    pub fn inverse_transform_point(
+        &self,
+        x: f32,
+        y: f32,
+        window_size: PixelSize,
+        window_props: PhysicalScreen,
+    ) -> Option<(f32, f32)> {
+        let matrix = self.eval(window_size, window_props).try_inverse()?;
+        let newpoint = matrix * Vector3::new(x, y, 1.0);
+        Some((newpoint.x, newpoint.y))
+    }
 }
 
 #[pymethods]
@@ -568,77 +1231,868 @@ impl Transformation2D {
     fn rotation_origin(angle: f32) -> Transformation2D {
         Transformation2D::RotationOrigin(angle)
     }
-}
-// allow multiplication of transformations
-impl std::ops::Mul for Transformation2D {
-    type Output = Transformation2D;
-
-    fn mul(self, rhs: Self) -> Self::Output {
-        Transformation2D::Product(BoxedTransformation2D::new(self), BoxedTransformation2D::new(rhs))
-    }
-}
-
-// basic 2d shapes
-#[derive(Debug, Clone)]
-#[pyclass]
-pub enum Shape {
-    /// A rectangle.
-    Rectangle {
-        x: Size,
-        y: Size,
-        width: Size,
-        height: Size,
-    },
-
-    /// A circle.
-    Circle { x: Size, y: Size, radius: Size },
 
-    /// A line.
-    Line { x1: Size, y1: Size, x2: Size, y2: Size },
-
-    /// An ellipse.
-    Ellipse {
-        x: Size,
-        y: Size,
-        radius_x: Size,
-        radius_y: Size,
-    },
-
-    /// A polygon.
-    Polygon { points: Vec<(Size, Size)> },
-}
-
-#[pymethods]
-impl Shape {
+    /// Create a new rotation around a point.
+    ///
+    /// Parameters
+    /// ----------
+    /// angle : float
+    ///    The angle of rotation in degrees.
+    /// x : Size
+    ///    The x-coordinate of the center of rotation.
+    /// y : Size
+    ///    The y-coordinate of the center of rotation.
+    /// Returns
+    /// -------
+    /// Transformation2D
+    ///   The rotation transformation.
     #[staticmethod]
-    #[pyo3(signature = (
-        width,
-        height,
-        x = IntoSize(Size::Pixels(0.0)),
-        y = IntoSize(Size::Pixels(0.0)),
-    ))]
-    /// Create a new rectangle.
-    fn rectangle(width: IntoSize, height: IntoSize, x: IntoSize, y: IntoSize) -> Shape {
-        Shape::Rectangle {
-            x: x.into(),
-            y: y.into(),
-            width: width.into(),
-            height: height.into(),
-        }
+    fn rotation_point(angle: f32, x: IntoSize, y: IntoSize) -> Transformation2D {
+        Transformation2D::RotationPoint(angle, x.into(), y.into())
     }
 
-    #[staticmethod]
-    #[pyo3(signature = (
-        radius,
-        x = IntoSize(Size::Pixels(0.0)),
-        y = IntoSize(Size::Pixels(0.0)),
-    ))]
-    /// Create a new circle, centered at (x, y) with the given radius.
+    /// Create a new scale around the origin.
     ///
     /// Parameters
     /// ----------
-    /// radius : Size or float
-    ///    The radius of the circle.
+    /// x : float
+    ///    The horizontal scale factor.
+    /// y : float
+    ///    The vertical scale factor.
+    /// Returns
+    /// -------
+    /// Transformation2D
+    ///   The scale transformation.
+    #[staticmethod]
+    fn scale_origin(x: f32, y: f32) -> Transformation2D {
+        Transformation2D::ScaleOrigin(x, y)
+    }
+
+    /// Create a new scale around a point.
+    ///
+    /// Parameters
+    /// ----------
+    /// x : float
+    ///    The horizontal scale factor.
+    /// y : float
+    ///    The vertical scale factor.
+    /// x0 : Size
+    ///    The x-coordinate of the point that is kept in place.
+    /// y0 : Size
+    ///    The y-coordinate of the point that is kept in place.
+    /// Returns
+    /// -------
+    /// Transformation2D
+    ///   The scale transformation.
+    #[staticmethod]
+    fn scale_point(x: f32, y: f32, x0: IntoSize, y0: IntoSize) -> Transformation2D {
+        Transformation2D::ScalePoint(x, y, x0.into(), y0.into())
+    }
+
+    /// Create a new shear around the origin.
+    ///
+    /// Parameters
+    /// ----------
+    /// x : float
+    ///    The horizontal shear factor.
+    /// y : float
+    ///    The vertical shear factor.
+    /// Returns
+    /// -------
+    /// Transformation2D
+    ///   The shear transformation.
+    #[staticmethod]
+    fn shear_origin(x: f32, y: f32) -> Transformation2D {
+        Transformation2D::ShearOrigin(x, y)
+    }
+
+    /// Create a new shear around a point.
+    ///
+    /// Parameters
+    /// ----------
+    /// x : float
+    ///    The horizontal shear factor.
+    /// y : float
+    ///    The vertical shear factor.
+    /// x0 : Size
+    ///    The x-coordinate of the point that is kept in place.
+    /// y0 : Size
+    ///    The y-coordinate of the point that is kept in place.
+    /// Returns
+    /// -------
+    /// Transformation2D
+    ///   The shear transformation.
+    #[staticmethod]
+    fn shear_point(x: f32, y: f32, x0: IntoSize, y0: IntoSize) -> Transformation2D {
+        Transformation2D::ShearPoint(x, y, x0.into(), y0.into())
+    }
+
+    /// Create a new translation.
+    ///
+    /// Parameters
+    /// ----------
+    /// x : Size
+    ///    The horizontal translation.
+    /// y : Size
+    ///    The vertical translation.
+    /// Returns
+    /// -------
+    /// Transformation2D
+    ///   The translation.
+    #[staticmethod]
+    #[pyo3(name = "translation")]
+    fn py_translation(x: IntoSize, y: IntoSize) -> Transformation2D {
+        Transformation2D::Translation(x.into(), y.into())
+    }
+
+    /// Create a new transformation that first applies `b` and then `a`. This
+    /// is the same as `a @ b`.
+    ///
+    /// Parameters
+    /// ----------
+    /// a : Transformation2D
+    ///    The transformation that is applied last.
+    /// b : Transformation2D
+    ///    The transformation that is applied first.
+    /// Returns
+    /// -------
+    /// Transformation2D
+    ///   The combined transformation.
+    #[staticmethod]
+    fn product(a: Transformation2D, b: Transformation2D) -> Transformation2D {
+        a * b
+    }
+
+    /// Create an affine transformation from a matrix in pixels.
+    ///
+    /// Parameters
+    /// ----------
+    /// matrix : list of list of float
+    ///    The 2x3 or 3x3 (homogeneous) transformation matrix as a list of rows.
+    ///    The last row of a 3x3 matrix must be `[0, 0, 1]`.
+    /// Returns
+    /// -------
+    /// Transformation2D
+    ///   The affine transformation.
+    #[staticmethod]
+    #[pyo3(name = "from_matrix")]
+    fn py_from_matrix(matrix: Vec<Vec<f32>>) -> PyResult<Transformation2D> {
+        let is_affine = match matrix.len() {
+            2 => true,
+            3 => matrix[2] == [0.0, 0.0, 1.0],
+            _ => false,
+        };
+        if !is_affine || matrix.iter().any(|row| row.len() != 3) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Expected a 2x3 or 3x3 affine transformation matrix",
+            ));
+        }
+
+        Ok(Transformation2D::Matrix(
+            matrix[0][0],
+            matrix[0][1],
+            matrix[0][2],
+            matrix[1][0],
+            matrix[1][1],
+            matrix[1][2],
+        ))
+    }
+
+    /// Returns the 3x3 (homogeneous) transformation matrix in pixels.
+    ///
+    /// Parameters
+    /// ----------
+    /// window : Window
+    ///    The window that determines the conversion of sizes to pixels.
+    /// Returns
+    /// -------
+    /// list of list of float
+    ///   The matrix as a list of rows.
+    fn to_matrix(&self, window: &Window) -> Vec<Vec<f32>> {
+        let window_state = window.state.lock().unwrap();
+        let matrix = self.eval(window_state.size, window_state.physical_screen);
+        matrix.row_iter().map(|row| row.iter().copied().collect()).collect()
+    }
+
+    /// Returns the inverse of the transformation. The inverse is computed in
+    /// pixels, so it is only valid as long as the size and physical properties
+    /// of the window do not change.
+    ///
+    /// Parameters
+    /// ----------
+    /// window : Window
+    ///    The window that determines the conversion of sizes to pixels.
+    /// Returns
+    /// -------
+    /// Transformation2D
+    ///   The inverse transformation.
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the transformation can not be inverted (e.g., a scale by zero).
+    #[pyo3(name = "inverse")]
+    fn py_inverse(&self, window: &Window) -> PyResult<Transformation2D> {
+        let window_state = window.state.lock().unwrap();
+        self.inverse(window_state.size, window_state.physical_screen)
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>("The transformation is not invertible"))
+    }
+
+    /// Maps a point through the transformation.
+    ///
+    /// Parameters
+    /// ----------
+    /// x : Size
+    ///    The x-coordinate of the point.
+    /// y : Size
+    ///    The y-coordinate of the point.
+    /// window : Window
+    ///    The window that determines the conversion of sizes to pixels.
+    /// Returns
+    /// -------
+    /// tuple of float
+    ///   The transformed point in pixels.
+    fn apply(&self, x: IntoSize, y: IntoSize, window: &Window) -> (f32, f32) {
+        let window_state = window.state.lock().unwrap();
+        let (size, screen) = (window_state.size, window_state.physical_screen);
+        let x = x.0.eval_position(Axis::X, size, screen);
+        let y = y.0.eval_position(Axis::Y, size, screen);
+        self.transform_point(x, y, size, screen)
+    }
+
+    // composition (`a @ b` and `a * b` first apply `b`, then `a`)
+    fn __matmul__(&self, other: Transformation2D) -> Transformation2D {
+        self.clone() * other
+    }
+
+    fn __mul__(&self, other: Transformation2D) -> Transformation2D {
+        self.clone() * other
+    }
+}
+// allow multiplication of transformations
+impl std::ops::Mul for Transformation2D {
+    type Output = Transformation2D;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Transformation2D::Product(BoxedTransformation2D::new(self), BoxedTransformation2D::new(rhs))
+    }
+}
+
+/// An axis-aligned rectangle in pixels, e.g., the bounds of a stimulus.
+#[pyclass(module = "psydk.visual.geometry", eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rect {
+    /// The x-coordinate of the left edge.
+    #[pyo3(get)]
+    pub x: f32,
+    /// The y-coordinate of the top edge.
+    #[pyo3(get)]
+    pub y: f32,
+    /// The width of the rectangle.
+    #[pyo3(get)]
+    pub width: f32,
+    /// The height of the rectangle.
+    #[pyo3(get)]
+    pub height: f32,
+}
+
+impl Rect {
+    /// Creates a new rectangle. Negative widths and heights are flipped, so
+    /// that (x, y) is always the top-left corner.
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x: x.min(x + width),
+            y: y.min(y + height),
+            width: width.abs(),
+            height: height.abs(),
+        }
+    }
+
+    /// Returns the smallest rectangle that contains all points, or an empty
+    /// rectangle at the origin if there are no points.
+    pub fn bounding(points: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        let (x0, y0, x1, y1) = points.into_iter().fold(
+            (f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+            |(x0, y0, x1, y1), (x, y)| (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+        );
+
+        if x0 > x1 {
+            Self::default()
+        } else {
+            Self::new(x0, y0, x1 - x0, y1 - y0)
+        }
+    }
+
+    /// The x-coordinate of the right edge.
+    pub fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    /// The y-coordinate of the bottom edge.
+    pub fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+
+    /// The center of the rectangle.
+    pub fn center(&self) -> (f32, f32) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// The corners of the rectangle, clockwise from the top-left corner.
+    pub fn corners(&self) -> [(f32, f32); 4] {
+        [
+            (self.x, self.y),
+            (self.right(), self.y),
+            (self.right(), self.bottom()),
+            (self.x, self.bottom()),
+        ]
+    }
+
+    /// Returns the rectangle grown by the margin on every side (or shrunk,
+    /// if the margin is negative).
+    pub fn expanded(&self, margin: f32) -> Self {
+        Self::new(
+            self.x - margin,
+            self.y - margin,
+            (self.width + 2.0 * margin).max(0.0),
+            (self.height + 2.0 * margin).max(0.0),
+        )
+    }
+
+    /// Returns true if the point lies within the rectangle.
+    pub fn contains_point(&self, x: f32, y: f32) -> bool {
+        x >= self.x && x <= self.right() && y >= self.y && y <= self.bottom()
+    }
+
+    /// Returns true if the two rectangles overlap.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.right() && other.x < self.right() && self.y < other.bottom() && other.y < self.bottom()
+    }
+
+    /// Returns the smallest rectangle that contains both rectangles.
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect::bounding(self.corners().into_iter().chain(other.corners()))
+    }
+
+    /// The overlap of two rectangles, or an empty rectangle if they do not
+    /// intersect.
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        if !self.intersects(other) {
+            return Rect::default();
+        }
+
+        let (x, y) = (self.x.max(other.x), self.y.max(other.y));
+        Rect::new(
+            x,
+            y,
+            self.right().min(other.right()) - x,
+            self.bottom().min(other.bottom()) - y,
+        )
+    }
+}
+
+#[pymethods]
+impl Rect {
+    #[new]
+    fn py_new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self::new(x, y, width, height)
+    }
+
+    /// The x-coordinate of the left edge.
+    #[getter]
+    fn left(&self) -> f32 {
+        self.x
+    }
+
+    /// The y-coordinate of the top edge.
+    #[getter]
+    fn top(&self) -> f32 {
+        self.y
+    }
+
+    /// The x-coordinate of the right edge.
+    #[getter]
+    #[pyo3(name = "right")]
+    fn py_right(&self) -> f32 {
+        self.right()
+    }
+
+    /// The y-coordinate of the bottom edge.
+    #[getter]
+    #[pyo3(name = "bottom")]
+    fn py_bottom(&self) -> f32 {
+        self.bottom()
+    }
+
+    /// The center of the rectangle.
+    #[getter]
+    #[pyo3(name = "center")]
+    fn py_center(&self) -> (f32, f32) {
+        self.center()
+    }
+
+    /// The corners of the rectangle, clockwise from the top-left corner.
+    #[getter]
+    #[pyo3(name = "corners")]
+    fn py_corners(&self) -> Vec<(f32, f32)> {
+        self.corners().to_vec()
+    }
+
+    /// Returns the rectangle grown by the margin on every side.
+    ///
+    /// Parameters
+    /// ----------
+    /// margin : float
+    ///    The margin in pixels. Negative margins shrink the rectangle.
+    ///
+    /// Returns
+    /// -------
+    /// Rect
+    ///    The expanded rectangle.
+    #[pyo3(name = "expanded")]
+    fn py_expanded(&self, margin: f32) -> Rect {
+        self.expanded(margin)
+    }
+
+    /// Check whether a point (in pixels) lies within the rectangle.
+    #[pyo3(name = "contains")]
+    fn py_contains(&self, x: f32, y: f32) -> bool {
+        self.contains_point(x, y)
+    }
+
+    /// Check whether the rectangle overlaps with another rectangle.
+    #[pyo3(name = "intersects")]
+    fn py_intersects(&self, other: Rect) -> bool {
+        self.intersects(&other)
+    }
+
+    /// Returns the smallest rectangle that contains both rectangles.
+    #[pyo3(name = "union")]
+    fn py_union(&self, other: Rect) -> Rect {
+        self.union(&other)
+    }
+
+    /// Convert the rectangle to another unit.
+    ///
+    /// Parameters
+    /// ----------
+    /// unit : Unit
+    ///   The unit to convert to, e.g., `psydk.size.deg`.
+    /// window : Window
+    ///   The window that determines the conversion.
+    ///
+    /// Returns
+    /// -------
+    /// tuple of Size
+    ///   The x- and y-coordinate of the top-left corner, the width and the
+    ///   height in the given unit.
+    fn to(&self, unit: Unit, window: &Window) -> (Size, Size, Size, Size) {
+        let window_state = window.state.lock().unwrap();
+        let convert =
+            |pixels: f32, axis| Size::Pixels(pixels).to(unit, axis, window_state.size, window_state.physical_screen);
+        (
+            convert(self.x, Axis::X),
+            convert(self.y, Axis::Y),
+            convert(self.width, Axis::X),
+            convert(self.height, Axis::Y),
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Rect(x={}, y={}, width={}, height={})",
+            self.x, self.y, self.width, self.height
+        )
+    }
+}
+
+/// A rectangle that has been transformed, e.g., the bounds of a rotated
+/// stimulus. Shears turn the rectangle into a parallelogram.
+#[pyclass(module = "psydk.visual.geometry", eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrientedRect {
+    /// The corners in pixels, in the order of `Rect::corners` before the
+    /// transformation.
+    pub corners: [(f32, f32); 4],
+}
+
+impl OrientedRect {
+    /// Transforms the rectangle with the given (homogeneous) matrix.
+    pub fn new(rect: Rect, matrix: &Matrix3<f32>) -> Self {
+        let corners = rect.corners().map(|(x, y)| {
+            let p = matrix * Vector3::new(x, y, 1.0);
+            (p.x, p.y)
+        });
+        Self { corners }
+    }
+
+    /// The axis-aligned bounds of the transformed rectangle.
+    pub fn bounds(&self) -> Rect {
+        Rect::bounding(self.corners)
+    }
+
+    /// The center of the transformed rectangle.
+    pub fn center(&self) -> (f32, f32) {
+        let [a, _, c, _] = self.corners;
+        ((a.0 + c.0) / 2.0, (a.1 + c.1) / 2.0)
+    }
+
+    /// The angle of the (transformed) top edge in degrees, clockwise from the
+    /// x-axis.
+    pub fn angle(&self) -> f32 {
+        let [a, b, ..] = self.corners;
+        (b.1 - a.1).atan2(b.0 - a.0).to_degrees()
+    }
+}
+
+#[pymethods]
+impl OrientedRect {
+    /// The corners in pixels, clockwise from the (transformed) top-left corner.
+    #[getter]
+    fn corners(&self) -> Vec<(f32, f32)> {
+        self.corners.to_vec()
+    }
+
+    /// The length of the (transformed) top edge in pixels.
+    #[getter]
+    fn width(&self) -> f32 {
+        let [a, b, ..] = self.corners;
+        (b.0 - a.0).hypot(b.1 - a.1)
+    }
+
+    /// The length of the (transformed) left edge in pixels.
+    #[getter]
+    fn height(&self) -> f32 {
+        let [a, _, _, d] = self.corners;
+        (d.0 - a.0).hypot(d.1 - a.1)
+    }
+
+    /// The angle of the (transformed) top edge in degrees, clockwise from the
+    /// x-axis.
+    #[getter]
+    #[pyo3(name = "angle")]
+    fn py_angle(&self) -> f32 {
+        self.angle()
+    }
+
+    /// The center of the transformed rectangle.
+    #[getter]
+    #[pyo3(name = "center")]
+    fn py_center(&self) -> (f32, f32) {
+        self.center()
+    }
+
+    /// The axis-aligned bounds of the transformed rectangle.
+    #[getter]
+    #[pyo3(name = "bounds")]
+    fn py_bounds(&self) -> Rect {
+        self.bounds()
+    }
+
+    /// Convert the corners to another unit.
+    ///
+    /// Parameters
+    /// ----------
+    /// unit : Unit
+    ///   The unit to convert to, e.g., `psydk.size.deg`.
+    /// window : Window
+    ///   The window that determines the conversion.
+    ///
+    /// Returns
+    /// -------
+    /// list of tuple of Size
+    ///   The corners in the given unit.
+    fn to(&self, unit: Unit, window: &Window) -> Vec<(Size, Size)> {
+        let window_state = window.state.lock().unwrap();
+        let convert =
+            |pixels: f32, axis| Size::Pixels(pixels).to(unit, axis, window_state.size, window_state.physical_screen);
+        self.corners
+            .iter()
+            .map(|&(x, y)| (convert(x, Axis::X), convert(y, Axis::Y)))
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("OrientedRect(corners={:?})", self.corners)
+    }
+}
+
+// basic 2d shapes
+#[derive(Debug, Clone)]
+#[pyclass]
+pub enum Shape {
+    /// A rectangle.
+    Rectangle {
+        x: Size,
+        y: Size,
+        width: Size,
+        height: Size,
+    },
+
+    /// A circle.
+    Circle { x: Size, y: Size, radius: Size },
+
+    /// A line.
+    Line { x1: Size, y1: Size, x2: Size, y2: Size },
+
+    /// An ellipse. The rotation is in degrees, clockwise around the center.
+    Ellipse {
+        x: Size,
+        y: Size,
+        radius_x: Size,
+        radius_y: Size,
+        rotation: f64,
+    },
+
+    /// A polygon.
+    Polygon { points: Vec<(Size, Size)> },
+
+    /// An open sequence of connected line segments.
+    Polyline { points: Vec<(Size, Size)> },
+
+    /// A general path made of lines and Bézier curves.
+    Path { commands: Vec<PathCommand> },
+
+    /// A circular arc. Angles are in degrees, clockwise from the positive x-axis.
+    Arc {
+        x: Size,
+        y: Size,
+        radius: Size,
+        start_angle: f64,
+        sweep_angle: f64,
+    },
+
+    /// A pie slice of a circle. Angles are in degrees, clockwise from the positive x-axis.
+    Wedge {
+        x: Size,
+        y: Size,
+        radius: Size,
+        start_angle: f64,
+        sweep_angle: f64,
+    },
+
+    /// A ring between two concentric circles.
+    Annulus {
+        x: Size,
+        y: Size,
+        inner_radius: Size,
+        outer_radius: Size,
+    },
+}
+
+/// A single command of a `Shape.path`.
+#[derive(Debug, Clone)]
+#[pyclass]
+pub enum PathCommand {
+    /// Start a new subpath at (x, y).
+    MoveTo { x: Size, y: Size },
+    /// Draw a straight line to (x, y).
+    LineTo { x: Size, y: Size },
+    /// Draw a quadratic Bézier curve with control point (cx, cy) to (x, y).
+    QuadTo { cx: Size, cy: Size, x: Size, y: Size },
+    /// Draw a cubic Bézier curve with control points (c1x, c1y) and (c2x, c2y) to (x, y).
+    CubicTo {
+        c1x: Size,
+        c1y: Size,
+        c2x: Size,
+        c2y: Size,
+        x: Size,
+        y: Size,
+    },
+    /// Close the current subpath.
+    Close(),
+}
+
+/// A path command given either as a `PathCommand` or as a tuple such as
+/// `("move_to", x, y)`, `("line_to", x, y)`, `("quad_to", cx, cy, x, y)`,
+/// `("cubic_to", c1x, c1y, c2x, c2y, x, y)` or `("close",)`.
+pub struct IntoPathCommand(pub PathCommand);
+
+impl<'py> FromPyObject<'py> for IntoPathCommand {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(command) = ob.extract::<PathCommand>() {
+            return Ok(IntoPathCommand(command));
+        }
+
+        let tuple = ob.downcast::<pyo3::types::PyTuple>().map_err(|_| {
+            PyErr::new::<pyo3::exceptions::PyTypeError, _>("A path command must be a PathCommand or a tuple.")
+        })?;
+        let name = tuple.get_item(0)?.extract::<String>()?;
+        let args = (1..tuple.len())
+            .map(|i| Ok(tuple.get_item(i)?.extract::<IntoSize>()?.0))
+            .collect::<PyResult<Vec<Size>>>()?;
+
+        let command = match (name.as_str(), args.as_slice()) {
+            ("move_to", [x, y]) => PathCommand::MoveTo {
+                x: x.clone(),
+                y: y.clone(),
+            },
+            ("line_to", [x, y]) => PathCommand::LineTo {
+                x: x.clone(),
+                y: y.clone(),
+            },
+            ("quad_to", [cx, cy, x, y]) => PathCommand::QuadTo {
+                cx: cx.clone(),
+                cy: cy.clone(),
+                x: x.clone(),
+                y: y.clone(),
+            },
+            ("cubic_to", [c1x, c1y, c2x, c2y, x, y]) => PathCommand::CubicTo {
+                c1x: c1x.clone(),
+                c1y: c1y.clone(),
+                c2x: c2x.clone(),
+                c2y: c2y.clone(),
+                x: x.clone(),
+                y: y.clone(),
+            },
+            ("close", []) => PathCommand::Close(),
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Invalid path command: {} with {} arguments.",
+                    name,
+                    args.len()
+                )))
+            }
+        };
+
+        Ok(IntoPathCommand(command))
+    }
+}
+
+impl Shape {
+    /// Converts the shape into a renderer shape in pixels, moved by (x, y) pixels.
+    /// Points are extents from (x, y), and widths and radii are extents from
+    /// the corner or center they belong to (see `Size::eval_extent`).
+    pub fn to_renderer_shape(
+        &self,
+        x: f64,
+        y: f64,
+        window_size: PixelSize,
+        screen: PhysicalScreen,
+    ) -> renderer::shapes::Shape {
+        use renderer::shapes::{PathElement, Shape as RShape};
+
+        let extent =
+            |size: &Size, start: f64, axis: Axis| size.eval_extent(start as f32, axis, window_size, screen) as f64;
+        let point = |px: &Size, py: &Size| (x + extent(px, x, Axis::X), y + extent(py, y, Axis::Y));
+        // radii are measured along the x-axis from the center
+        let radius = |r: &Size, (cx, _): (f64, f64)| extent(r, cx, Axis::X);
+
+        match self {
+            Shape::Rectangle {
+                x: rx,
+                y: ry,
+                width,
+                height,
+            } => {
+                let (ax, ay) = point(rx, ry);
+                RShape::rectangle((ax, ay), extent(width, ax, Axis::X), extent(height, ay, Axis::Y))
+            }
+            Shape::Circle {
+                x: cx,
+                y: cy,
+                radius: r,
+            } => {
+                let center = point(cx, cy);
+                RShape::circle(center, radius(r, center))
+            }
+            Shape::Line { x1, y1, x2, y2 } => RShape::line(point(x1, y1), point(x2, y2)),
+            Shape::Ellipse {
+                x: cx,
+                y: cy,
+                radius_x,
+                radius_y,
+                rotation,
+            } => {
+                let center = point(cx, cy);
+                RShape::ellipse(
+                    center,
+                    extent(radius_x, center.0, Axis::X),
+                    extent(radius_y, center.1, Axis::Y),
+                    *rotation,
+                )
+            }
+            Shape::Polygon { points } => RShape::polygon(points.iter().map(|(px, py)| point(px, py))),
+            Shape::Polyline { points } => RShape::polyline(points.iter().map(|(px, py)| point(px, py))),
+            Shape::Path { commands } => RShape::path(commands.iter().map(|command| match command {
+                PathCommand::MoveTo { x: px, y: py } => PathElement::MoveTo(point(px, py).into()),
+                PathCommand::LineTo { x: px, y: py } => PathElement::LineTo(point(px, py).into()),
+                PathCommand::QuadTo { cx, cy, x: px, y: py } => {
+                    PathElement::QuadTo(point(cx, cy).into(), point(px, py).into())
+                }
+                PathCommand::CubicTo {
+                    c1x,
+                    c1y,
+                    c2x,
+                    c2y,
+                    x: px,
+                    y: py,
+                } => PathElement::CubicTo(point(c1x, c1y).into(), point(c2x, c2y).into(), point(px, py).into()),
+                PathCommand::Close() => PathElement::Close,
+            })),
+            Shape::Arc {
+                x: cx,
+                y: cy,
+                radius: r,
+                start_angle,
+                sweep_angle,
+            } => {
+                let center = point(cx, cy);
+                RShape::arc(center, radius(r, center), *start_angle, *sweep_angle)
+            }
+            Shape::Wedge {
+                x: cx,
+                y: cy,
+                radius: r,
+                start_angle,
+                sweep_angle,
+            } => {
+                let center = point(cx, cy);
+                RShape::wedge(center, radius(r, center), *start_angle, *sweep_angle)
+            }
+            Shape::Annulus {
+                x: cx,
+                y: cy,
+                inner_radius,
+                outer_radius,
+            } => {
+                let center = point(cx, cy);
+                RShape::annulus(center, radius(inner_radius, center), radius(outer_radius, center))
+            }
+        }
+    }
+}
+
+#[pymethods]
+impl Shape {
+    #[staticmethod]
+    #[pyo3(signature = (
+        width,
+        height,
+        x = IntoSize(Size::Pixels(0.0)),
+        y = IntoSize(Size::Pixels(0.0)),
+    ))]
+    /// Create a new rectangle.
+    fn rectangle(width: IntoSize, height: IntoSize, x: IntoSize, y: IntoSize) -> Shape {
+        Shape::Rectangle {
+            x: x.into(),
+            y: y.into(),
+            width: width.into(),
+            height: height.into(),
+        }
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (
+        radius,
+        x = IntoSize(Size::Pixels(0.0)),
+        y = IntoSize(Size::Pixels(0.0)),
+    ))]
+    /// Create a new circle, centered at (x, y) with the given radius.
+    ///
+    /// Parameters
+    /// ----------
+    /// radius : Size or float
+    ///    The radius of the circle.
     /// x : Size or float (optional)
     ///    The x-coordinate of the center of the circle.
     /// y : Size or float (optional)
@@ -683,6 +2137,7 @@ impl Shape {
     }
 
     #[staticmethod]
+    #[pyo3(signature = (x, y, radius_x, radius_y, rotation = 0.0))]
     /// Create a new ellipse.
     ///
     /// Parameters
@@ -692,19 +2147,186 @@ impl Shape {
     /// y : Size or float
     ///     The y-coordinate of the center of the ellipse.
     /// radius_x : Size or float
-    ///     The radius of the ellipse in the x-direction.
+    ///     The radius of the ellipse in the x-direction (before rotation).
     /// radius_y : Size or float
-    ///     The radius of the ellipse in the y-direction.
+    ///     The radius of the ellipse in the y-direction (before rotation).
+    /// rotation : float (optional)
+    ///     The rotation of the ellipse around its center in degrees, clockwise.
+    ///
     /// Returns
     /// -------
     /// Shape
     ///     The ellipse.
-    fn ellipse(x: IntoSize, y: IntoSize, radius_x: IntoSize, radius_y: IntoSize) -> Shape {
+    fn ellipse(x: IntoSize, y: IntoSize, radius_x: IntoSize, radius_y: IntoSize, rotation: f64) -> Shape {
         Shape::Ellipse {
             x: x.into(),
             y: y.into(),
             radius_x: radius_x.into(),
             radius_y: radius_y.into(),
+            rotation,
+        }
+    }
+
+    #[staticmethod]
+    /// Create a new closed polygon.
+    ///
+    /// Parameters
+    /// ----------
+    /// points : list of (Size or float, Size or float)
+    ///     The vertices of the polygon.
+    ///
+    /// Returns
+    /// -------
+    /// Shape
+    ///     The polygon.
+    fn polygon(points: Vec<(IntoSize, IntoSize)>) -> Shape {
+        Shape::Polygon {
+            points: points.into_iter().map(|(x, y)| (x.into(), y.into())).collect(),
+        }
+    }
+
+    #[staticmethod]
+    /// Create a new polyline, i.e. an open sequence of connected line segments.
+    ///
+    /// Parameters
+    /// ----------
+    /// points : list of (Size or float, Size or float)
+    ///     The vertices of the polyline.
+    ///
+    /// Returns
+    /// -------
+    /// Shape
+    ///     The polyline.
+    fn polyline(points: Vec<(IntoSize, IntoSize)>) -> Shape {
+        Shape::Polyline {
+            points: points.into_iter().map(|(x, y)| (x.into(), y.into())).collect(),
+        }
+    }
+
+    #[staticmethod]
+    /// Create a new path from a list of commands.
+    ///
+    /// Parameters
+    /// ----------
+    /// commands : list of PathCommand or tuple
+    ///     The commands of the path. Commands can also be given as tuples:
+    ///     ("move_to", x, y), ("line_to", x, y), ("quad_to", cx, cy, x, y),
+    ///     ("cubic_to", c1x, c1y, c2x, c2y, x, y) or ("close",).
+    ///
+    /// Returns
+    /// -------
+    /// Shape
+    ///     The path.
+    fn path(commands: Vec<IntoPathCommand>) -> Shape {
+        Shape::Path {
+            commands: commands.into_iter().map(|c| c.0).collect(),
+        }
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (
+        radius,
+        start_angle,
+        sweep_angle,
+        x = IntoSize(Size::Pixels(0.0)),
+        y = IntoSize(Size::Pixels(0.0)),
+    ))]
+    /// Create a new circular arc, centered at (x, y).
+    ///
+    /// Parameters
+    /// ----------
+    /// radius : Size or float
+    ///     The radius of the arc.
+    /// start_angle : float
+    ///     The start angle in degrees, clockwise from the positive x-axis.
+    /// sweep_angle : float
+    ///     The angle spanned by the arc in degrees.
+    /// x : Size or float (optional)
+    ///     The x-coordinate of the center of the arc.
+    /// y : Size or float (optional)
+    ///     The y-coordinate of the center of the arc.
+    ///
+    /// Returns
+    /// -------
+    /// Shape
+    ///     The arc.
+    fn arc(radius: IntoSize, start_angle: f64, sweep_angle: f64, x: IntoSize, y: IntoSize) -> Shape {
+        Shape::Arc {
+            x: x.into(),
+            y: y.into(),
+            radius: radius.into(),
+            start_angle,
+            sweep_angle,
+        }
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (
+        radius,
+        start_angle,
+        sweep_angle,
+        x = IntoSize(Size::Pixels(0.0)),
+        y = IntoSize(Size::Pixels(0.0)),
+    ))]
+    /// Create a new wedge (pie slice), centered at (x, y).
+    ///
+    /// Parameters
+    /// ----------
+    /// radius : Size or float
+    ///     The radius of the wedge.
+    /// start_angle : float
+    ///     The start angle in degrees, clockwise from the positive x-axis.
+    /// sweep_angle : float
+    ///     The angle spanned by the wedge in degrees.
+    /// x : Size or float (optional)
+    ///     The x-coordinate of the center of the wedge.
+    /// y : Size or float (optional)
+    ///     The y-coordinate of the center of the wedge.
+    ///
+    /// Returns
+    /// -------
+    /// Shape
+    ///     The wedge.
+    fn wedge(radius: IntoSize, start_angle: f64, sweep_angle: f64, x: IntoSize, y: IntoSize) -> Shape {
+        Shape::Wedge {
+            x: x.into(),
+            y: y.into(),
+            radius: radius.into(),
+            start_angle,
+            sweep_angle,
+        }
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (
+        inner_radius,
+        outer_radius,
+        x = IntoSize(Size::Pixels(0.0)),
+        y = IntoSize(Size::Pixels(0.0)),
+    ))]
+    /// Create a new annulus (ring), centered at (x, y).
+    ///
+    /// Parameters
+    /// ----------
+    /// inner_radius : Size or float
+    ///     The radius of the hole.
+    /// outer_radius : Size or float
+    ///     The outer radius of the ring.
+    /// x : Size or float (optional)
+    ///     The x-coordinate of the center of the annulus.
+    /// y : Size or float (optional)
+    ///     The y-coordinate of the center of the annulus.
+    ///
+    /// Returns
+    /// -------
+    /// Shape
+    ///     The annulus.
+    fn annulus(inner_radius: IntoSize, outer_radius: IntoSize, x: IntoSize, y: IntoSize) -> Shape {
+        Shape::Annulus {
+            x: x.into(),
+            y: y.into(),
+            inner_radius: inner_radius.into(),
+            outer_radius: outer_radius.into(),
         }
     }
 
@@ -779,3 +2401,119 @@ impl<'py> FromPyObject<'py> for Anchor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(string: &str) -> Size {
+        Size::from_str(string).unwrap_or_else(|e| panic!("failed to parse {:?}: {}", string, e))
+    }
+
+    /// Sizes do not implement `PartialEq`, so trees are compared by their
+    /// debug representation.
+    fn assert_tree(string: &str, expected: Size) {
+        assert_eq!(format!("{:?}", parse(string)), format!("{:?}", expected));
+    }
+
+    fn error_position(string: &str) -> usize {
+        let error = Size::from_str(string).expect_err("the size should be invalid");
+        let position = error
+            .split("at position ")
+            .nth(1)
+            .and_then(|rest| rest.split(':').next())
+            .expect("the error should contain a position");
+        position.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_unit_aliases() {
+        let cases = [
+            ("2px", Unit::Pixels),
+            ("2vw", Unit::ViewportWidth),
+            ("2sw", Unit::ViewportWidth),
+            ("2vh", Unit::ViewportHeight),
+            ("2sh", Unit::ViewportHeight),
+            ("2deg", Unit::Degrees),
+            ("2mm", Unit::Millimeters),
+            ("2cm", Unit::Centimeters),
+            ("2m", Unit::Meters),
+            ("2in", Unit::Inches),
+            ("2inch", Unit::Inches),
+            ("2pt", Unit::Points),
+        ];
+
+        for (string, unit) in cases {
+            assert_eq!(parse(string).value_and_unit(), Some((2.0, unit)), "{}", string);
+        }
+    }
+
+    #[test]
+    fn numbers_without_unit_are_pixels() {
+        assert_eq!(parse("12.5").value_and_unit(), Some((12.5, Unit::Pixels)));
+        assert_tree("10px + 5", Size::Pixels(10.0) + Size::Pixels(5.0));
+    }
+
+    #[test]
+    fn parses_difference() {
+        assert_tree("50vw - 2cm", Size::ViewportWidth(50.0) - Size::Centimeters(2.0));
+    }
+
+    #[test]
+    fn parses_product() {
+        assert_tree("3deg * 2", Size::Degrees(3.0) * 2.0);
+        assert_tree("2 * 3deg", Size::Degrees(3.0) * 2.0);
+    }
+
+    #[test]
+    fn parses_negated_parenthesis() {
+        assert_tree("-(1vh/4)", -(Size::ViewportHeight(1.0) / 4.0));
+        assert_tree("-2cm", Size::Centimeters(-2.0));
+    }
+
+    #[test]
+    fn multiplication_binds_tighter_than_addition() {
+        assert_tree("1px + 2mm * 3", Size::Pixels(1.0) + Size::Millimeters(2.0) * 3.0);
+        assert_tree("(1px + 2mm) * 3", (Size::Pixels(1.0) + Size::Millimeters(2.0)) * 3.0);
+        assert_tree(
+            "1cm - 2mm - 3px",
+            (Size::Centimeters(1.0) - Size::Millimeters(2.0)) - Size::Pixels(3.0),
+        );
+    }
+
+    #[test]
+    fn reports_error_positions() {
+        assert_eq!(error_position("10 foo"), 3);
+        assert_eq!(error_position("10px $"), 5);
+        assert_eq!(error_position("10px 5"), 5);
+        assert_eq!(error_position("10px +"), 6);
+        assert_eq!(error_position("10px * 2px"), 7);
+        assert_eq!(error_position("2 / (1px)"), 4);
+        assert_eq!(error_position("(1px + 2px"), 10);
+        assert_eq!(error_position("px"), 0);
+    }
+
+    #[test]
+    fn marks_error_with_carets() {
+        let error = Size::from_str("10 foo").unwrap_err();
+        assert!(error.ends_with("\n  10 foo\n     ^^^"), "{}", error);
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let sizes = [
+            Size::Pixels(10.0),
+            Size::Centimeters(-2.0),
+            Size::ViewportWidth(50.0) - Size::Centimeters(2.0),
+            Size::Degrees(3.0) * 2.0,
+            -(Size::ViewportHeight(1.0) / 4.0),
+            Size::Millimeters(1.0) - (Size::Inches(0.5) + Size::Points(3.0)),
+            (Size::Pixels(1.0) + Size::Meters(0.01)) / 2.0,
+        ];
+
+        for size in sizes {
+            let string = size.to_string();
+            assert_tree(&string, size);
+        }
+    }
+}
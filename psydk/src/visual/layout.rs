@@ -0,0 +1,364 @@
+use pyo3::{pyfunction, FromPyObject, PyResult};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{
+    geometry::{Axis, IntoSize, Size},
+    stimuli::PyStimulus,
+    window::Window,
+};
+use crate::errors::psydkError;
+
+/// The maximum number of candidates that are drawn per position before random
+/// placement gives up.
+const MAX_ATTEMPTS_PER_POSITION: usize = 1000;
+
+/// Creates a random number generator. If no seed is given, the generator is
+/// seeded from the operating system's entropy source.
+pub fn rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Returns the number of columns and rows of a grid with `count` cells. If
+/// neither is given, the grid is as close to square as possible (with more
+/// columns than rows).
+pub fn grid_shape(count: usize, cols: Option<usize>, rows: Option<usize>) -> Result<(usize, usize), psydkError> {
+    let (cols, rows) = match (cols, rows) {
+        (Some(cols), Some(rows)) => (cols, rows),
+        (Some(cols), None) => (cols, (count + cols.max(1) - 1) / cols.max(1)),
+        (None, Some(rows)) => ((count + rows.max(1) - 1) / rows.max(1), rows),
+        (None, None) => {
+            let cols = (count as f64).sqrt().ceil() as usize;
+            (cols, (count + cols.max(1) - 1) / cols.max(1))
+        }
+    };
+
+    if cols * rows < count {
+        return Err(psydkError::CustomError(format!(
+            "A grid with {} columns and {} rows can not hold {} positions",
+            cols, rows, count
+        )));
+    }
+
+    Ok((cols, rows))
+}
+
+/// Returns the centers of the cells of a grid (in row-major order) that
+/// covers the rectangle with the given center and size.
+pub fn grid(count: usize, cols: usize, rows: usize, center: (f32, f32), size: (f32, f32)) -> Vec<(f32, f32)> {
+    let cell_width = size.0 / cols.max(1) as f32;
+    let cell_height = size.1 / rows.max(1) as f32;
+    let left = center.0 - size.0 / 2.0;
+    let top = center.1 - size.1 / 2.0;
+
+    (0..count)
+        .map(|i| {
+            let (col, row) = (i % cols, i / cols);
+            (
+                left + (col as f32 + 0.5) * cell_width,
+                top + (row as f32 + 0.5) * cell_height,
+            )
+        })
+        .collect()
+}
+
+/// Returns `count` evenly spaced positions on an ellipse with the given center
+/// and radii. The first position lies at `start_angle` (in degrees, clockwise
+/// from the positive x-axis).
+pub fn ring(count: usize, center: (f32, f32), radius: (f32, f32), start_angle: f32) -> Vec<(f32, f32)> {
+    (0..count)
+        .map(|i| {
+            let angle = (start_angle + 360.0 * i as f32 / count as f32).to_radians();
+            (center.0 + radius.0 * angle.cos(), center.1 + radius.1 * angle.sin())
+        })
+        .collect()
+}
+
+/// Returns `count` uniformly distributed positions within the rectangle with
+/// the given center and size, such that no two positions are closer than
+/// `min_separation`. Fails if the positions can not be placed.
+pub fn random(
+    count: usize,
+    center: (f32, f32),
+    size: (f32, f32),
+    min_separation: f32,
+    rng: &mut impl Rng,
+) -> Result<Vec<(f32, f32)>, psydkError> {
+    let mut positions: Vec<(f32, f32)> = Vec::with_capacity(count);
+    let mut attempts = 0;
+
+    while positions.len() < count {
+        if attempts >= MAX_ATTEMPTS_PER_POSITION * count {
+            return Err(psydkError::CustomError(format!(
+                "Could not place {} positions with a minimum separation of {}px (placed {})",
+                count,
+                min_separation,
+                positions.len()
+            )));
+        }
+        attempts += 1;
+
+        let x = center.0 + (rng.gen::<f32>() - 0.5) * size.0;
+        let y = center.1 + (rng.gen::<f32>() - 0.5) * size.1;
+
+        if positions
+            .iter()
+            .all(|(px, py)| (x - px).hypot(y - py) >= min_separation)
+        {
+            positions.push((x, y));
+        }
+    }
+
+    Ok(positions)
+}
+
+/// Offsets each position by a random amount between `-amount_x` and
+/// `amount_x` horizontally and between `-amount_y` and `amount_y` vertically.
+pub fn jitter(positions: &mut [(f32, f32)], (amount_x, amount_y): (f32, f32), rng: &mut impl Rng) {
+    if amount_x <= 0.0 && amount_y <= 0.0 {
+        return;
+    }
+
+    for (x, y) in positions.iter_mut() {
+        if amount_x > 0.0 {
+            *x += rng.gen_range(-amount_x..=amount_x);
+        }
+        if amount_y > 0.0 {
+            *y += rng.gen_range(-amount_y..=amount_y);
+        }
+    }
+}
+
+/// The items to lay out: either a number of positions or a list of stimuli,
+/// which are moved to the positions.
+#[derive(FromPyObject)]
+pub enum LayoutItems {
+    Count(usize),
+    Stimuli(Vec<PyStimulus>),
+}
+
+impl LayoutItems {
+    fn count(&self) -> usize {
+        match self {
+            LayoutItems::Count(count) => *count,
+            LayoutItems::Stimuli(stimuli) => stimuli.len(),
+        }
+    }
+
+    /// Converts the positions to sizes and moves the stimuli (if any) to them.
+    fn apply(&self, positions: Vec<(f32, f32)>) -> Vec<(Size, Size)> {
+        let positions: Vec<(Size, Size)> = positions
+            .into_iter()
+            .map(|(x, y)| (Size::Pixels(x), Size::Pixels(y)))
+            .collect();
+
+        if let LayoutItems::Stimuli(stimuli) = self {
+            for (stimulus, (x, y)) in stimuli.iter().zip(positions.iter()) {
+                stimulus.as_super().lock().set_position(x.clone(), y.clone());
+            }
+        }
+
+        positions
+    }
+}
+
+/// Evaluates the center of a layout in pixels.
+fn center(x: IntoSize, y: IntoSize, window: &Window) -> (f32, f32) {
+    let (window_size, screen_props) = (window.size(), window.physical_screen());
+    (
+        x.0.eval_position(Axis::X, window_size, screen_props),
+        y.0.eval_position(Axis::Y, window_size, screen_props),
+    )
+}
+
+/// Evaluates a size (e.g., a width or a distance) along the given axis in
+/// pixels.
+fn extent(size: IntoSize, axis: Axis, window: &Window) -> f32 {
+    size.0.eval(axis, window.size(), window.physical_screen())
+}
+
+/// Evaluates the jitter along both axes, which differ for non-square pixels.
+fn jitter_amount(size: IntoSize, window: &Window) -> (f32, f32) {
+    let (window_size, screen) = (window.size(), window.physical_screen());
+    (
+        size.0.eval(Axis::X, window_size, screen),
+        size.0.eval(Axis::Y, window_size, screen),
+    )
+}
+
+/// Places positions on a grid that covers a rectangle.
+///
+/// Parameters
+/// ----------
+/// items : int or list of Stimulus
+///   The number of positions, or the stimuli to place. Stimuli are moved to
+///   their positions in the given order.
+/// window : Window
+///   The window that is used to convert sizes to pixels.
+/// x : Size, optional
+///   The x-coordinate of the center of the grid. Defaults to 0.
+/// y : Size, optional
+///   The y-coordinate of the center of the grid. Defaults to 0.
+/// width : Size, optional
+///   The width of the grid. Defaults to the width of the window.
+/// height : Size, optional
+///   The height of the grid. Defaults to the height of the window.
+/// cols : int, optional
+///   The number of columns. By default, this is inferred from `rows`, or the
+///   grid is made as square as possible.
+/// rows : int, optional
+///   The number of rows. By default, this is inferred from `cols`.
+/// jitter : Size, optional
+///   The maximum random offset that is added to each coordinate. Defaults to 0.
+/// seed : int, optional
+///   The seed of the random number generator used for jittering.
+///
+/// Returns
+/// -------
+/// list of tuple of (Size, Size)
+///   The positions, in row-major order.
+///
+/// Raises
+/// ------
+/// ValueError
+///   If the grid has fewer cells than positions.
+#[pyfunction]
+#[pyo3(name = "grid", signature = (items, window, x = IntoSize(Size::Pixels(0.0)), y = IntoSize(Size::Pixels(0.0)), width = IntoSize(Size::ViewportWidth(1.0)), height = IntoSize(Size::ViewportHeight(1.0)), cols = None, rows = None, jitter = IntoSize(Size::Pixels(0.0)), seed = None))]
+pub fn py_grid(
+    items: LayoutItems,
+    window: &Window,
+    x: IntoSize,
+    y: IntoSize,
+    width: IntoSize,
+    height: IntoSize,
+    cols: Option<usize>,
+    rows: Option<usize>,
+    jitter: IntoSize,
+    seed: Option<u64>,
+) -> PyResult<Vec<(Size, Size)>> {
+    let count = items.count();
+    let (cols, rows) =
+        grid_shape(count, cols, rows).map_err(|err| pyo3::exceptions::PyValueError::new_err(err.to_string()))?;
+
+    let size = (extent(width, Axis::X, window), extent(height, Axis::Y, window));
+    let mut positions = grid(count, cols, rows, center(x, y, window), size);
+    self::jitter(&mut positions, jitter_amount(jitter, window), &mut rng(seed));
+
+    Ok(items.apply(positions))
+}
+
+/// Places positions evenly on a circle around a center point.
+///
+/// Parameters
+/// ----------
+/// items : int or list of Stimulus
+///   The number of positions, or the stimuli to place. Stimuli are moved to
+///   their positions in the given order.
+/// eccentricity : Size
+///   The radius of the circle, e.g., `5 * deg`. When the window's angle mode
+///   is "eccentricity" and the circle is centered on the fixation point, all
+///   positions lie at exactly this eccentricity.
+/// window : Window
+///   The window that is used to convert sizes to pixels.
+/// start_angle : float, optional
+///   The angle of the first position in degrees, clockwise from the positive
+///   x-axis. Defaults to 0.
+/// x : Size, optional
+///   The x-coordinate of the center of the circle. Defaults to 0.
+/// y : Size, optional
+///   The y-coordinate of the center of the circle. Defaults to 0.
+/// jitter : Size, optional
+///   The maximum random offset that is added to each coordinate. Defaults to 0.
+/// seed : int, optional
+///   The seed of the random number generator used for jittering.
+///
+/// Returns
+/// -------
+/// list of tuple of (Size, Size)
+///   The positions, in clockwise order.
+#[pyfunction]
+#[pyo3(name = "ring", signature = (items, eccentricity, window, start_angle = 0.0, x = IntoSize(Size::Pixels(0.0)), y = IntoSize(Size::Pixels(0.0)), jitter = IntoSize(Size::Pixels(0.0)), seed = None))]
+pub fn py_ring(
+    items: LayoutItems,
+    eccentricity: IntoSize,
+    window: &Window,
+    start_angle: f32,
+    x: IntoSize,
+    y: IntoSize,
+    jitter: IntoSize,
+    seed: Option<u64>,
+) -> PyResult<Vec<(Size, Size)>> {
+    let (window_size, screen_props) = (window.size(), window.physical_screen());
+    let center = center(x, y, window);
+    let radius = (
+        eccentricity.0.eval_extent(center.0, Axis::X, window_size, screen_props),
+        eccentricity.0.eval_extent(center.1, Axis::Y, window_size, screen_props),
+    );
+
+    let mut positions = ring(items.count(), center, radius, start_angle);
+    self::jitter(&mut positions, jitter_amount(jitter, window), &mut rng(seed));
+
+    Ok(items.apply(positions))
+}
+
+/// Places positions at random within a rectangle, keeping a minimum distance
+/// between any two of them.
+///
+/// Parameters
+/// ----------
+/// items : int or list of Stimulus
+///   The number of positions, or the stimuli to place. Stimuli are moved to
+///   their positions in the given order.
+/// window : Window
+///   The window that is used to convert sizes to pixels.
+/// x : Size, optional
+///   The x-coordinate of the center of the rectangle. Defaults to 0.
+/// y : Size, optional
+///   The y-coordinate of the center of the rectangle. Defaults to 0.
+/// width : Size, optional
+///   The width of the rectangle. Defaults to the width of the window.
+/// height : Size, optional
+///   The height of the rectangle. Defaults to the height of the window.
+/// min_separation : Size, optional
+///   The minimum distance between two positions (before jittering). Defaults
+///   to 0.
+/// jitter : Size, optional
+///   The maximum random offset that is added to each coordinate. Defaults to 0.
+/// seed : int, optional
+///   The seed of the random number generator. The same seed always results in
+///   the same positions.
+///
+/// Returns
+/// -------
+/// list of tuple of (Size, Size)
+///   The positions.
+///
+/// Raises
+/// ------
+/// ValueError
+///   If the positions can not be placed with the minimum separation.
+#[pyfunction]
+#[pyo3(name = "random", signature = (items, window, x = IntoSize(Size::Pixels(0.0)), y = IntoSize(Size::Pixels(0.0)), width = IntoSize(Size::ViewportWidth(1.0)), height = IntoSize(Size::ViewportHeight(1.0)), min_separation = IntoSize(Size::Pixels(0.0)), jitter = IntoSize(Size::Pixels(0.0)), seed = None))]
+pub fn py_random(
+    items: LayoutItems,
+    window: &Window,
+    x: IntoSize,
+    y: IntoSize,
+    width: IntoSize,
+    height: IntoSize,
+    min_separation: IntoSize,
+    jitter: IntoSize,
+    seed: Option<u64>,
+) -> PyResult<Vec<(Size, Size)>> {
+    let mut rng = rng(seed);
+    let size = (extent(width, Axis::X, window), extent(height, Axis::Y, window));
+    let min_separation = extent(min_separation, Axis::X, window);
+
+    let mut positions = random(items.count(), center(x, y, window), size, min_separation, &mut rng)
+        .map_err(|err| pyo3::exceptions::PyValueError::new_err(err.to_string()))?;
+    self::jitter(&mut positions, jitter_amount(jitter, window), &mut rng);
+
+    Ok(items.apply(positions))
+}
@@ -1,5 +1,10 @@
+pub mod calibration;
 pub mod color;
-mod fill;
+pub mod color2;
+pub mod color_space;
+pub mod fill;
 pub mod geometry;
+pub mod layout;
+pub mod rig;
 pub mod stimuli;
 pub mod window;
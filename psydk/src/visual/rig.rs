@@ -0,0 +1,229 @@
+//! The physical setup of the monitors used in an experiment. The physical size
+//! of a monitor and the viewing distance are needed to convert physical units
+//! (e.g., millimeters or degrees of visual angle) to pixels.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use winit::monitor::MonitorHandle;
+
+use super::{
+    calibration::{is_toml, psydk_dir},
+    window::{check_positive, PhysicalScreen},
+};
+use crate::errors::psydkError;
+
+/// The name of the environment variable that can be used to override the path
+/// of the rig profile.
+pub const RIG_PROFILE_ENV: &str = "PSYDK_RIG_PROFILE";
+
+/// The width of the screen that is assumed if it is neither set in the rig
+/// profile nor reported by the operating system.
+pub const DEFAULT_WIDTH_MM: f32 = 300.0;
+
+/// The viewing distance that is assumed if it is not set in the rig profile.
+pub const DEFAULT_VIEWING_DISTANCE_MM: f32 = 1000.0;
+
+/// The physical setup of a single monitor. Unset values fall back to the size
+/// reported by the operating system or to the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorProfile {
+    /// The name of the monitor, as reported by the operating system.
+    pub device_name: String,
+    /// The width of the visible area of the screen in millimeters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width_mm: Option<f32>,
+    /// The height of the visible area of the screen in millimeters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height_mm: Option<f32>,
+    /// The distance between the observer's eyes and the screen in millimeters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub viewing_distance_mm: Option<f32>,
+    /// The width of a pixel divided by its height. Ignored if the height is
+    /// set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pixel_aspect_ratio: Option<f32>,
+}
+
+impl MonitorProfile {
+    /// Returns an error if one of the physical properties is set but not
+    /// positive.
+    fn validate(&self) -> Result<(), psydkError> {
+        [
+            ("width", self.width_mm),
+            ("height", self.height_mm),
+            ("viewing distance", self.viewing_distance_mm),
+            ("pixel aspect ratio", self.pixel_aspect_ratio),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value?)))
+        .try_for_each(|(name, value)| check_positive(name, value))
+        .map_err(|e| psydkError::CustomError(format!("Invalid profile for monitor {}: {}", self.device_name, e)))
+    }
+}
+
+/// A rig profile lists the physical setup of the monitors of an experimental
+/// setup. It can be stored as JSON or TOML.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct RigProfile {
+    #[serde(default)]
+    pub monitors: Vec<MonitorProfile>,
+}
+
+impl RigProfile {
+    /// Loads a rig profile from a JSON or TOML file. Returns an error if a
+    /// physical property of a monitor is not positive.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, psydkError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+
+        let profile: RigProfile = if is_toml(path) {
+            toml::from_str(&contents).map_err(|e| psydkError::CustomError(e.to_string()))?
+        } else {
+            serde_json::from_str(&contents).map_err(|e| psydkError::CustomError(e.to_string()))?
+        };
+
+        for monitor in &profile.monitors {
+            monitor.validate()?;
+        }
+
+        Ok(profile)
+    }
+
+    /// Returns the profile of the monitor with the given name from the rig
+    /// profile (see `rig_profile_path`), if there is one.
+    pub fn find(monitor_name: &str) -> Option<MonitorProfile> {
+        let path = rig_profile_path()?;
+        let profile = match Self::load(&path) {
+            Ok(profile) => profile,
+            Err(e) => {
+                log::warn!("Failed to load rig profile {}: {}", path.display(), e);
+                return None;
+            }
+        };
+
+        profile.monitors.into_iter().find(|m| m.device_name == monitor_name)
+    }
+}
+
+/// Returns the path of the rig profile, i.e., the path given by the
+/// `PSYDK_RIG_PROFILE` environment variable, or `~/.psydk/rig.toml` (or
+/// `rig.json`) if it exists.
+pub fn rig_profile_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(RIG_PROFILE_ENV) {
+        return Some(PathBuf::from(path));
+    }
+
+    let dir = psydk_dir()?;
+    ["rig.toml", "rig.json"]
+        .into_iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+}
+
+/// Determines the physical properties of a monitor with the given resolution.
+/// Values from the rig profile take precedence over the size reported by the
+/// operating system, which takes precedence over the defaults.
+pub fn physical_screen(monitor: &MonitorHandle, monitor_name: &str, resolution: (u32, u32)) -> PhysicalScreen {
+    let profile = RigProfile::find(monitor_name);
+    let reported = reported_physical_size(monitor);
+
+    let width_mm = profile
+        .as_ref()
+        .and_then(|p| p.width_mm)
+        .or(reported.map(|(width, _)| width))
+        .unwrap_or_else(|| {
+            log::warn!(
+                "The physical size of monitor {} is unknown, assuming a width of {} mm. Set it in the rig profile or with Window.set_physical_width().",
+                monitor_name,
+                DEFAULT_WIDTH_MM
+            );
+            DEFAULT_WIDTH_MM
+        });
+    let viewing_distance = profile
+        .as_ref()
+        .and_then(|p| p.viewing_distance_mm)
+        .unwrap_or(DEFAULT_VIEWING_DISTANCE_MM);
+
+    let mut screen = PhysicalScreen::new(resolution.0, width_mm, viewing_distance);
+
+    // the height determines the pixel aspect ratio
+    let height_mm = match &profile {
+        Some(p) if p.height_mm.is_some() => p.height_mm,
+        // the reported height does not necessarily match the width from the profile
+        Some(p) if p.width_mm.is_some() => None,
+        _ => reported.map(|(_, height)| height),
+    };
+    match (height_mm, profile.and_then(|p| p.pixel_aspect_ratio)) {
+        (Some(height_mm), _) => screen.set_height(resolution.1, height_mm),
+        (None, Some(ratio)) => screen.pixel_aspect_ratio = ratio,
+        (None, None) => {}
+    }
+
+    screen
+}
+
+/// Returns the physical width and height of the monitor in millimeters, as
+/// reported by the operating system.
+#[cfg(target_os = "macos")]
+fn reported_physical_size(monitor: &MonitorHandle) -> Option<(f32, f32)> {
+    use winit::platform::macos::MonitorHandleExtMacOS;
+
+    #[repr(C)]
+    struct CGSize {
+        width: f64,
+        height: f64,
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGDisplayScreenSize(display: u32) -> CGSize;
+    }
+
+    let size = unsafe { CGDisplayScreenSize(monitor.native_id()) };
+    (size.width > 0.0 && size.height > 0.0).then(|| (size.width as f32, size.height as f32))
+}
+
+/// Returns the physical width and height of the monitor in millimeters, as
+/// reported by the operating system.
+#[cfg(target_os = "windows")]
+fn reported_physical_size(monitor: &MonitorHandle) -> Option<(f32, f32)> {
+    use std::ffi::c_void;
+
+    use winit::platform::windows::MonitorHandleExtWindows;
+
+    const HORZSIZE: i32 = 4;
+    const VERTSIZE: i32 = 6;
+
+    #[link(name = "gdi32")]
+    extern "system" {
+        fn CreateDCW(driver: *const u16, device: *const u16, port: *const u16, mode: *const c_void) -> isize;
+        fn GetDeviceCaps(hdc: isize, index: i32) -> i32;
+        fn DeleteDC(hdc: isize) -> i32;
+    }
+
+    let device = monitor
+        .native_id()
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect::<Vec<u16>>();
+
+    let (width, height) = unsafe {
+        let hdc = CreateDCW(device.as_ptr(), device.as_ptr(), std::ptr::null(), std::ptr::null());
+        if hdc == 0 {
+            return None;
+        }
+        let size = (GetDeviceCaps(hdc, HORZSIZE), GetDeviceCaps(hdc, VERTSIZE));
+        DeleteDC(hdc);
+        size
+    };
+
+    (width > 0 && height > 0).then(|| (width as f32, height as f32))
+}
+
+/// Returns the physical width and height of the monitor in millimeters, as
+/// reported by the operating system.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn reported_physical_size(_monitor: &MonitorHandle) -> Option<(f32, f32)> {
+    None
+}
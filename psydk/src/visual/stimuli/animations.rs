@@ -3,9 +3,12 @@ use std::time::Instant;
 use pyo3::{types::PyAnyMethods, Bound, FromPyObject, PyAny, PyResult};
 
 use super::{Stimulus, StimulusParamValue};
-use crate::visual::{
-    geometry::Size,
-    window::{Window, WindowState},
+use crate::{
+    errors::psydkError,
+    visual::{
+        color::InterpolationSpace,
+        window::{Window, WindowState},
+    },
 };
 
 #[derive(FromPyObject, Debug, Clone)]
@@ -88,6 +91,8 @@ pub struct Animation {
     repeat: Repeat,
     /// The easing function that should be used for the animation.
     easing: TransitionFunction,
+    /// The color space in which colors are interpolated.
+    interpolation: InterpolationSpace,
 }
 
 impl Animation {
@@ -108,9 +113,16 @@ impl Animation {
             start_time,
             repeat,
             easing,
+            interpolation: InterpolationSpace::default(),
         }
     }
 
+    /// Sets the color space in which colors are interpolated.
+    pub fn with_interpolation(mut self, interpolation: InterpolationSpace) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
     /// Returns the name of the attribute that should be animated.
     pub fn parameter(&self) -> &str {
         &self.paramter
@@ -118,8 +130,13 @@ impl Animation {
 
     /// Returns the current value of the animated parameter at the specified time (f64).
     pub fn value_f64(from: f64, to: f64, elapsed: f64, duration: f64, easing: TransitionFunction) -> f64 {
+        from + (to - from) * Self::progress(elapsed, duration, easing)
+    }
+
+    /// Returns the progress of the animation (from 0 to 1) after applying the easing function.
+    pub fn progress(elapsed: f64, duration: f64, easing: TransitionFunction) -> f64 {
         let t = elapsed / duration;
-        let t = match easing {
+        match easing {
             TransitionFunction::None => t,
             TransitionFunction::Linear(a, b) => a + (b - a) * t,
             TransitionFunction::CubicBezier(p1, p2, p3, p4) => {
@@ -130,15 +147,14 @@ impl Animation {
                 let a = 1.0 - c - b;
                 a * t3 + b * t2 + c * t
             }
-        };
-
-        from + (to - from) * t
+        }
     }
 
-    /// Returns the current value of the animated parameter at the specified time.
-    pub fn value(&self, time: Instant, window_state: &WindowState) -> StimulusParamValue {
+    /// Returns the current value of the animated parameter at the specified
+    /// time. Returns an error if a color can not be resolved for the window.
+    pub fn value(&self, time: Instant, window_state: &WindowState) -> Result<StimulusParamValue, psydkError> {
         if self.finished(time) {
-            return self.to.clone();
+            return Ok(self.to.clone());
         }
 
         // let elapsed = time.duration_since(self.start_time).as_secs_f64();
@@ -163,21 +179,27 @@ impl Animation {
         let from = self.from.clone();
         let to = self.to.clone();
 
-        let window_size = window_state.size;
-        let screen_props = window_state.physical_screen;
-
-        match (from, to) {
+        let value = match (from, to) {
             (StimulusParamValue::f64(f), StimulusParamValue::f64(t)) => {
                 StimulusParamValue::f64(Self::value_f64(f, t, elapsed, duration, easing))
             }
             (StimulusParamValue::Size(f), StimulusParamValue::Size(t)) => {
-                let f = f.eval(window_size, screen_props) as f64;
-                let t = t.eval(window_size, screen_props) as f64;
-                let value = Self::value_f64(f, t, elapsed, duration, easing);
-                StimulusParamValue::Size(Size::Pixels(value as f32))
+                // the sizes are only evaluated once it is known along which
+                // axis the parameter is measured
+                let progress = Self::progress(elapsed, duration, easing) as f32;
+                StimulusParamValue::Size(f * (1.0 - progress) + t * progress)
+            }
+            (StimulusParamValue::LinRgba(f), StimulusParamValue::LinRgba(t)) => {
+                // colors in device-independent color spaces are interpolated once resolved
+                let calibration = window_state.calibration.as_ref();
+                let progress = Self::progress(elapsed, duration, easing) as f32;
+                let (f, t) = (f.resolve(calibration)?, t.resolve(calibration)?);
+                StimulusParamValue::LinRgba(f.mix(&t, progress, self.interpolation)?)
             }
             _ => self.to.clone(),
-        }
+        };
+
+        Ok(value)
     }
 
     /// Returns whether the animation has finished.
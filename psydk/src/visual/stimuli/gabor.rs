@@ -7,18 +7,18 @@ use renderer::{
     brushes::{Brush, Extend, Gradient, GradientKind},
     colors::RGBA,
     shapes::{Point, Shape},
-    styles::BlendMode,
+    styles::{BlendMode, FillStyle},
 };
 use strum::EnumString;
 use uuid::Uuid;
 
 use super::{
     animations::Animation, impl_pystimulus_for_wrapper, PyStimulus, Stimulus, StimulusParamValue, StimulusParams,
-    StrokeStyle,
+    StrokeCap, StrokeJoin, StrokeStyle,
 };
 use crate::visual::{
     color::LinRgba,
-    geometry::{Anchor, Size, Transformation2D},
+    geometry::{Anchor, Axis, Rect, Size, Transformation2D},
     window::Frame,
 };
 
@@ -60,6 +60,8 @@ pub struct GaborStimulus {
     pattern: Pattern,
     color_interpolation: ColorInterpolation,
 
+    blend_mode: super::BlendMode,
+
     transformation: Transformation2D,
     anchor: Anchor,
     animations: Vec<Animation>,
@@ -82,6 +84,7 @@ impl GaborStimulus {
         stroke_color: Option<LinRgba>,
         stroke_width: Option<Size>,
         alpha: Option<f64>,
+        blend_mode: super::BlendMode,
     ) -> Self {
         let gaussian_colors: Vec<RGBA> = (0..128)
             .map(|i| {
@@ -124,6 +127,7 @@ impl GaborStimulus {
             gaussian_colors: Some(gaussian_colors),
             pattern,
             color_interpolation,
+            blend_mode,
         }
     }
 
@@ -194,6 +198,9 @@ impl GaborStimulus {
 ///   With of the stroke.
 /// alpha : float, optional
 ///   The alpha value of the stimulus.
+/// blend_mode : str, optional
+///   How the stimulus is blended with what is behind it, e.g., "source_over"
+///   (the default), "lighter" (additive), "multiply" or "xor".
 pub struct PyGaborStimulus();
 
 #[pymethods]
@@ -213,7 +220,8 @@ impl PyGaborStimulus {
         stroke_style = None,
         stroke_color = None,
         stroke_width = None,
-        alpha = None
+        alpha = None,
+        blend_mode = super::BlendMode::default()
     ))]
     /// Create a new Gabor stimulus.
     fn __new__(
@@ -231,6 +239,7 @@ impl PyGaborStimulus {
         stroke_color: Option<LinRgba>,
         stroke_width: Option<IntoSize>,
         alpha: Option<f64>,
+        blend_mode: super::BlendMode,
     ) -> (Self, PyStimulus) {
         (
             Self(),
@@ -249,6 +258,7 @@ impl PyGaborStimulus {
                 stroke_color,
                 stroke_width.map(Into::into),
                 alpha,
+                blend_mode,
             )),
         )
     }
@@ -271,14 +281,19 @@ impl Stimulus for GaborStimulus {
         let window_size = window_state.size;
         let screen_props = window_state.physical_screen;
 
+        // colors need to be resolved before the scene is borrowed
+        let stroke_color = self.params.stroke_color.map(|c| frame.resolve_color(c, &window_state));
+
         let mut scene = frame.scene_mut();
 
-        // convert physical units to pixels
-        let radius = self.params.radius.eval(window_size, screen_props) as f64;
-        let sigma = self.params.sigma.eval(window_size, screen_props);
-        let cycle_length = self.params.cycle_length.eval(window_size, screen_props) as f64;
-        let pos_x = self.params.cx.eval(window_size, screen_props) as f64;
-        let pos_y = self.params.cy.eval(window_size, screen_props) as f64;
+        // convert physical units to pixels, extents are measured from the position
+        let pos_x = self.params.cx.eval_position(Axis::X, window_size, screen_props);
+        let pos_y = self.params.cy.eval_position(Axis::Y, window_size, screen_props);
+        let extent = |size: &Size| size.eval_extent(pos_x, Axis::X, window_size, screen_props);
+        let radius = extent(&self.params.radius) as f64;
+        let sigma = extent(&self.params.sigma);
+        let cycle_length = extent(&self.params.cycle_length) as f64;
+        let (pos_x, pos_y) = (pos_x as f64, pos_y as f64);
 
         // apply the anchor
         let bb_width = radius * 2.0;
@@ -326,7 +341,7 @@ impl Stimulus for GaborStimulus {
         let alpha = self.params.alpha.unwrap_or(1.0);
         scene.start_layer(
             BlendMode::SourceOver,
-            gaussian_shape,
+            gaussian_shape.clone(),
             Some(transform.into()),
             None,
             alpha as f32,
@@ -334,12 +349,14 @@ impl Stimulus for GaborStimulus {
         scene.draw_shape_fill(
             gaussian_shape,
             gaussian_brush,
+            FillStyle::NonZero,
             Some(transform.into()),
             Some(BlendMode::SourceOver),
         );
         scene.draw_shape_fill(
             grating_shape,
             grating_brush,
+            FillStyle::NonZero,
             Some(transform.into()),
             Some(BlendMode::SourceIn),
         );
@@ -347,14 +364,23 @@ impl Stimulus for GaborStimulus {
 
         // if the stimulus has a stroke, draw it
         if let Some(stroke_style) = &self.params.stroke_style {
-            let stroke_color = self.params.stroke_color.unwrap_or(LinRgba::new(0.0, 0.0, 0.0, 1.0));
+            let stroke_color = stroke_color.unwrap_or(LinRgba::new(0.0, 0.0, 0.0, 1.0));
             let stroke_brush = Brush::Solid(stroke_color.into());
             let stroke_width = self.params.stroke_width.clone().unwrap_or(Size::Pixels(0.0));
-            let stroke_width = stroke_width.eval(window_size, screen_props) as f64;
-            let stroke_options = renderer::styles::StrokeStyle::new(stroke_width);
-
-            let shape = Shape::circle(Point { x: pos_x, y: pos_y }, radius);
-            scene.draw_shape_stroke(shape, stroke_brush, stroke_options, Some(transform.into()), None);
+            let stroke_width = extent(&stroke_width) as f64;
+            let stroke_options = stroke_style.to_renderer_style(
+                stroke_width,
+                pos_x as f32,
+                StrokeCap::default(),
+                StrokeJoin::default(),
+                window_size,
+                screen_props,
+            );
+
+            if let Some(stroke_options) = stroke_options {
+                let shape = Shape::circle(Point { x: pos_x, y: pos_y }, radius);
+                scene.draw_shape_stroke(shape, stroke_brush, stroke_options, Some(transform.into()), None);
+            }
         }
     }
 
@@ -366,6 +392,14 @@ impl Stimulus for GaborStimulus {
         self.visible
     }
 
+    fn blend_mode(&self) -> super::BlendMode {
+        self.blend_mode
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: super::BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
     fn animations(&mut self) -> &mut Vec<Animation> {
         &mut self.animations
     }
@@ -387,25 +421,35 @@ impl Stimulus for GaborStimulus {
     }
 
     fn contains(&self, x: Size, y: Size, window: &Window) -> bool {
-        // let cx = self.params.cx.eval(&window.physical_properties);
-        // let cy = self.params.cy.eval(&window.physical_properties);
-        // let radius = self.params.radius.eval(&window.physical_properties);
-        // let trans_mat = self.transformation.eval(&window.physical_properties);
+        let Some(point) = self.local_point(&x, &y, window) else {
+            return false;
+        };
+
+        // the grating is only visible within the circular aperture
+        let bounds = self.local_bounds(window);
+        let (cx, cy) = bounds.center();
+        Shape::circle((cx, cy), bounds.width as f64 / 2.0).contains(point, FillStyle::NonZero, 0.0)
+    }
 
-        // let x = x.eval(&window.physical_properties);
-        // let y = y.eval(&window.physical_properties);
+    fn local_bounds(&self, window: &Window) -> Rect {
+        let window_state = window.lock_state();
+        let window_size = window_state.size;
+        let screen_props = window_state.physical_screen;
 
-        // // apply transformation by multiplying the point with the transformation matrix
-        // let p = nalgebra::Vector3::new(x, y, 1.0);
-        // let p_new = trans_mat * p;
+        let pos_x = self.params.cx.eval_position(Axis::X, window_size, screen_props);
+        let pos_y = self.params.cy.eval_position(Axis::Y, window_size, screen_props);
+        let radius = self
+            .params
+            .radius
+            .eval_extent(pos_x, Axis::X, window_size, screen_props);
+        let (x, y) = self.anchor.to_top_left(pos_x, pos_y, radius * 2.0, radius * 2.0);
 
-        // // check if the point is inside the circle
-        // let dx = p_new[0] - cx;
-        // let dy = p_new[1] - cy;
-        // let distance = (dx * dx + dy * dy).sqrt();
+        Rect::new(x, y, radius * 2.0, radius * 2.0)
+    }
 
-        // distance <= radius
-        false
+    fn set_position(&mut self, x: Size, y: Size) {
+        self.params.cx = x;
+        self.params.cy = y;
     }
 
     fn get_param(&self, name: &str) -> Option<StimulusParamValue> {
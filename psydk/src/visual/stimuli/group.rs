@@ -0,0 +1,406 @@
+use std::time::Instant;
+
+use psydk_proc::StimulusParams;
+use renderer::affine::Affine;
+use uuid::Uuid;
+
+use super::{
+    animations::Animation, impl_pystimulus_for_wrapper, BlendMode, DynamicStimulus, PyStimulus, Stimulus,
+    StimulusParamValue, StimulusParams,
+};
+use crate::visual::{
+    geometry::{Axis, Rect, Shape, Size, Transformation2D},
+    window::Frame,
+};
+
+#[derive(StimulusParams, Clone, Debug)]
+pub struct GroupParams {
+    pub x: Size,
+    pub y: Size,
+    pub alpha: f64,
+    pub clip: Option<Shape>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GroupStimulus {
+    id: uuid::Uuid,
+    params: GroupParams,
+    children: Vec<DynamicStimulus>,
+    blend_mode: BlendMode,
+
+    transform: Transformation2D,
+    animations: Vec<Animation>,
+    visible: bool,
+}
+
+impl GroupStimulus {
+    pub fn new(
+        children: Vec<DynamicStimulus>,
+        x: Size,
+        y: Size,
+        alpha: f64,
+        clip: Option<Shape>,
+        blend_mode: BlendMode,
+        transform: Transformation2D,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            params: GroupParams { x, y, alpha, clip },
+            children,
+            blend_mode,
+            transform,
+            animations: Vec::new(),
+            visible: true,
+        }
+    }
+
+    /// Returns the children of the group.
+    pub fn children(&self) -> &[DynamicStimulus] {
+        &self.children
+    }
+
+    /// Adds a child that is drawn on top of the existing children.
+    pub fn add(&mut self, child: DynamicStimulus) {
+        self.children.push(child);
+    }
+
+    /// Removes the child with the given UUID. Returns true if it was found.
+    pub fn remove(&mut self, id: Uuid) -> bool {
+        let len = self.children.len();
+        self.children.retain(|child| child.lock().uuid() != id);
+        self.children.len() != len
+    }
+
+    /// Returns true if the stimulus with the given id is one of the
+    /// descendants of the group.
+    pub fn has_descendant(&self, id: Uuid) -> bool {
+        self.children.iter().any(|child| {
+            let child = child.lock();
+            child.uuid() == id
+                || child
+                    .downcast_ref::<GroupStimulus>()
+                    .is_some_and(|group| group.has_descendant(id))
+        })
+    }
+
+    /// Removes all children.
+    pub fn clear(&mut self) {
+        self.children.clear();
+    }
+
+    /// The transformation that maps the coordinates of the children to
+    /// window coordinates, i.e., the position of the group followed by its
+    /// transformation. The position is evaluated in pixels, so the children
+    /// share their origin with the clip, the hit test and the bounds.
+    fn children_transform(&self, window: &Window) -> Transformation2D {
+        let (x, y) = self.position(window);
+        self.transform.clone() * Transformation2D::Translation(Size::Pixels(x), Size::Pixels(y))
+    }
+
+    /// Returns the position of the group in pixels.
+    fn position(&self, window: &Window) -> (f32, f32) {
+        let (window_size, screen_props) = (window.size(), window.physical_screen());
+        (
+            self.params.x.eval_position(Axis::X, window_size, screen_props),
+            self.params.y.eval_position(Axis::Y, window_size, screen_props),
+        )
+    }
+
+    /// Returns the clip shape in pixels, relative to the position of the
+    /// group, before the transformation of the group is applied.
+    fn clip_shape(&self, window: &Window) -> Option<renderer::shapes::Shape> {
+        let (x, y) = self.position(window);
+        self.params
+            .clip
+            .as_ref()
+            .map(|clip| clip.to_renderer_shape(x as f64, y as f64, window.size(), window.physical_screen()))
+    }
+}
+
+#[derive(Debug, Clone)]
+#[pyclass(name = "GroupStimulus", extends=PyStimulus)]
+/// A stimulus that draws several stimuli as one unit.
+///
+/// The children are drawn in order, moved by the position of the group and
+/// transformed with its transformation. The group alpha is applied to the
+/// children as a whole, so overlapping children do not show through each
+/// other.
+///
+/// Parameters
+/// ----------
+/// children : list of Stimulus, optional
+///     The stimuli in the group.
+/// x : Size, optional
+///     The x-coordinate of the origin of the group.
+/// y : Size, optional
+///     The y-coordinate of the origin of the group.
+/// alpha : float, optional
+///     The opacity of the group.
+/// clip : Shape, optional
+///     A shape (relative to the origin of the group) outside of which the
+///     children are not drawn.
+/// blend_mode : str, optional
+///     How the group is blended with what is behind it, e.g., "source_over"
+///     (the default), "lighter" (additive), "multiply" or "xor".
+/// transform : Transformation2D, optional
+///     The transformation of the group.
+pub struct PyGroupStimulus();
+
+#[pymethods]
+impl PyGroupStimulus {
+    #[new]
+    #[pyo3(signature = (
+        children = Vec::new(),
+        x = IntoSize(Size::Pixels(0.0)),
+        y = IntoSize(Size::Pixels(0.0)),
+        alpha = 1.0,
+        clip = None,
+        blend_mode = BlendMode::default(),
+        transform = Transformation2D::Identity()
+    ))]
+    /// A stimulus that draws several stimuli as one unit.
+    ///
+    /// Parameters
+    /// ----------
+    /// children : list of Stimulus, optional
+    ///     The stimuli in the group.
+    /// x : Size, optional
+    ///     The x-coordinate of the origin of the group.
+    /// y : Size, optional
+    ///     The y-coordinate of the origin of the group.
+    /// alpha : float, optional
+    ///     The opacity of the group.
+    /// clip : Shape, optional
+    ///     A shape (relative to the origin of the group) outside of which the
+    ///     children are not drawn.
+    /// blend_mode : str, optional
+    ///     How the group is blended with what is behind it, e.g., "source_over"
+    ///     (the default), "lighter" (additive), "multiply" or "xor".
+    /// transform : Transformation2D, optional
+    ///     The transformation of the group.
+    fn __new__(
+        children: Vec<PyStimulus>,
+        x: IntoSize,
+        y: IntoSize,
+        alpha: f64,
+        clip: Option<Shape>,
+        blend_mode: BlendMode,
+        transform: Transformation2D,
+    ) -> (Self, PyStimulus) {
+        (
+            Self(),
+            PyStimulus::new(GroupStimulus::new(
+                children.iter().map(|child| child.as_super().clone()).collect(),
+                x.into(),
+                y.into(),
+                alpha,
+                clip,
+                blend_mode,
+                transform,
+            )),
+        )
+    }
+
+    /// Add a stimulus to the group. It is drawn on top of the existing
+    /// children.
+    ///
+    /// Parameters
+    /// ----------
+    /// stimulus : Stimulus
+    ///     The stimulus to add.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///     If the stimulus is the group itself or contains the group.
+    fn add(mut slf: PyRefMut<'_, Self>, stimulus: PyStimulus) -> PyResult<()> {
+        // a group that contains itself could never be drawn
+        let id = downcast_stimulus!(slf, GroupStimulus).uuid();
+        let contains_self = {
+            let stimulus = stimulus.as_super().lock();
+            stimulus.uuid() == id
+                || stimulus
+                    .downcast_ref::<GroupStimulus>()
+                    .is_some_and(|group| group.has_descendant(id))
+        };
+        if contains_self {
+            return Err(PyValueError::new_err("A group can not contain itself"));
+        }
+
+        downcast_py_stimulus_mut!(slf, GroupStimulus).add(stimulus.as_super().clone());
+        Ok(())
+    }
+
+    /// Remove a stimulus from the group.
+    ///
+    /// Parameters
+    /// ----------
+    /// stimulus : Stimulus
+    ///     The stimulus to remove.
+    ///
+    /// Returns
+    /// -------
+    /// bool
+    ///     True if the stimulus was part of the group.
+    fn remove(mut slf: PyRefMut<'_, Self>, stimulus: PyStimulus) -> bool {
+        let id = stimulus.as_super().lock().uuid();
+        downcast_py_stimulus_mut!(slf, GroupStimulus).remove(id)
+    }
+
+    /// Remove all stimuli from the group.
+    fn clear(mut slf: PyRefMut<'_, Self>) {
+        downcast_py_stimulus_mut!(slf, GroupStimulus).clear();
+    }
+
+    fn __len__(slf: PyRef<'_, Self>) -> usize {
+        downcast_stimulus!(slf, GroupStimulus).children().len()
+    }
+}
+
+impl_pystimulus_for_wrapper!(PyGroupStimulus, GroupStimulus);
+
+impl Stimulus for GroupStimulus {
+    fn uuid(&self) -> Uuid {
+        self.id
+    }
+
+    fn animations(&mut self) -> &mut Vec<Animation> {
+        &mut self.animations
+    }
+
+    fn add_animation(&mut self, animation: Animation) {
+        self.animations.push(animation);
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        if !self.visible {
+            return;
+        }
+
+        let window = frame.window();
+
+        // the layer covers the whole window unless the group is clipped
+        let (clip, clip_transform) = match self.clip_shape(&window) {
+            Some(clip) => {
+                let transform: Affine = self.transform.eval(window.size(), window.physical_screen()).into();
+                (clip, Some(transform))
+            }
+            None => {
+                let size = window.size();
+                let (width, height) = (size.width as f64, size.height as f64);
+                (
+                    renderer::shapes::Shape::rectangle((-width / 2.0, -height / 2.0), width, height),
+                    None,
+                )
+            }
+        };
+
+        frame.scene_mut().start_layer(
+            renderer::styles::BlendMode::SourceOver,
+            clip,
+            clip_transform,
+            None,
+            self.params.alpha as f32,
+        );
+
+        let transform = self.children_transform(&window);
+        let now = Instant::now();
+
+        for child in &self.children {
+            let mut child = child.lock();
+
+            {
+                let window_state = window.lock_state();
+                child.update_animations(now, &window_state);
+            }
+
+            // the children are drawn with the transformation of the group
+            // applied on top of their own
+            let child_transform = child.transformation();
+            child.set_transformation(transform.clone() * child_transform.clone());
+            frame.draw_stimulus(&mut *child);
+            child.set_transformation(child_transform);
+        }
+
+        frame.scene_mut().end_layer();
+    }
+
+    fn contains(&self, x: Size, y: Size, window: &Window) -> bool {
+        let Some((local_x, local_y)) = self.local_point(&x, &y, window) else {
+            return false;
+        };
+
+        if let Some(clip) = self.clip_shape(window) {
+            if !clip.contains(
+                (local_x as f64, local_y as f64),
+                renderer::styles::FillStyle::NonZero,
+                0.0,
+            ) {
+                return false;
+            }
+        }
+
+        // the children are positioned relative to the origin of the group
+        let (pos_x, pos_y) = self.position(window);
+        let (child_x, child_y) = (Size::Pixels(local_x - pos_x), Size::Pixels(local_y - pos_y));
+
+        self.children
+            .iter()
+            .any(|child| child.lock().contains(child_x.clone(), child_y.clone(), window))
+    }
+
+    fn local_bounds(&self, window: &Window) -> Rect {
+        let (pos_x, pos_y) = self.position(window);
+
+        let bounds = self
+            .children
+            .iter()
+            .map(|child| child.lock().bounds(window))
+            .reduce(|a, b| a.union(&b))
+            .map(|bounds| Rect::new(bounds.x + pos_x, bounds.y + pos_y, bounds.width, bounds.height))
+            .unwrap_or(Rect::new(pos_x, pos_y, 0.0, 0.0));
+
+        match self.clip_shape(window) {
+            Some(clip) => {
+                let (min, max) = clip.bounds();
+                bounds.intersection(&Rect::bounding([
+                    (min.x as f32, min.y as f32),
+                    (max.x as f32, max.y as f32),
+                ]))
+            }
+            None => bounds,
+        }
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    fn set_transformation(&mut self, transformation: Transformation2D) {
+        self.transform = transformation;
+    }
+
+    fn transformation(&self) -> Transformation2D {
+        self.transform.clone()
+    }
+
+    fn get_param(&self, name: &str) -> Option<StimulusParamValue> {
+        self.params.get_param(name)
+    }
+
+    fn set_param(&mut self, name: &str, value: StimulusParamValue) {
+        self.params.set_param(name, value)
+    }
+}
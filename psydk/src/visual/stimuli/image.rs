@@ -3,28 +3,81 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use psydk_proc::StimulusParams;
+use psydk_proc::{FromPyStr, StimulusParams};
 use pyo3::ffi::c_str;
 use renderer::{
-    brushes::{Brush, Extend, ImageSampling},
+    brushes::Brush,
     shapes::Shape,
-    styles::ImageFitMode,
+    styles::{FillStyle, ImageFitMode},
     DynamicBitmap,
 };
+use strum::EnumString;
 use uuid::Uuid;
 
 use super::{
-    animations::Animation, helpers, impl_pystimulus_for_wrapper, PyStimulus, Stimulus, StimulusParamValue,
+    animations::Animation, helpers, impl_pystimulus_for_wrapper, BlendMode, PyStimulus, Stimulus, StimulusParamValue,
     StimulusParams,
 };
 use crate::{
     experiment::PyRendererFactory,
     visual::{
-        geometry::{Anchor, Size, Transformation2D},
+        geometry::{Anchor, Axis, Rect, Size, Transformation2D},
         window::Frame,
     },
 };
 
+/// How the pixels of an image are sampled when it is drawn at a different
+/// size than its original resolution.
+#[derive(EnumString, Debug, Clone, Copy, PartialEq, Default, FromPyStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum ImageSampling {
+    /// The nearest pixel, which keeps pixel art and noise stimuli crisp.
+    Nearest,
+    /// Linear interpolation between the neighbouring pixels.
+    #[default]
+    Linear,
+    /// Bicubic interpolation, which is sharper than linear when upscaling.
+    Cubic,
+    /// Linear interpolation between mipmap levels, which avoids aliasing when
+    /// images are downscaled.
+    Mipmap,
+}
+
+impl From<ImageSampling> for renderer::brushes::ImageSampling {
+    fn from(sampling: ImageSampling) -> Self {
+        match sampling {
+            ImageSampling::Nearest => renderer::brushes::ImageSampling::Nearest,
+            ImageSampling::Linear => renderer::brushes::ImageSampling::Linear,
+            ImageSampling::Cubic => renderer::brushes::ImageSampling::Cubic,
+            ImageSampling::Mipmap => renderer::brushes::ImageSampling::Mipmap,
+        }
+    }
+}
+
+/// How an image is continued outside of its bounds when it is shifted with
+/// `image_x` and `image_y`.
+#[derive(EnumString, Debug, Clone, Copy, PartialEq, Default, FromPyStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum ImageEdgeMode {
+    /// The pixels at the edges are continued.
+    #[default]
+    Pad,
+    /// The image is repeated.
+    Repeat,
+    /// The image is repeated, mirrored every other time.
+    Reflect,
+}
+
+impl From<ImageEdgeMode> for renderer::brushes::Extend {
+    fn from(edge_mode: ImageEdgeMode) -> Self {
+        match edge_mode {
+            ImageEdgeMode::Pad => renderer::brushes::Extend::Pad,
+            ImageEdgeMode::Repeat => renderer::brushes::Extend::Repeat,
+            ImageEdgeMode::Reflect => renderer::brushes::Extend::Reflect,
+        }
+    }
+}
+
 #[derive(StimulusParams, Clone, Debug)]
 pub struct ImageParams {
     pub x: Size,
@@ -44,6 +97,9 @@ pub struct ImageStimulus {
 
     image: DynamicBitmap,
     anchor: Anchor,
+    sampling: ImageSampling,
+    edge_mode: ImageEdgeMode,
+    blend_mode: BlendMode,
     transformation: Transformation2D,
     animations: Vec<Animation>,
     visible: bool,
@@ -57,6 +113,9 @@ impl ImageStimulus {
         params: ImageParams,
         transform: Option<Transformation2D>,
         anchor: Anchor,
+        sampling: ImageSampling,
+        edge_mode: ImageEdgeMode,
+        blend_mode: BlendMode,
     ) -> Self {
         Self {
             id: Uuid::new_v4(),
@@ -65,6 +124,9 @@ impl ImageStimulus {
             visible: true,
             image,
             anchor,
+            sampling,
+            edge_mode,
+            blend_mode,
             params,
         }
     }
@@ -86,7 +148,10 @@ impl PyImageStimulus {
         opacity = 1.0,
         anchor = Anchor::Center,
         transform = None,
-        srgb = true
+        srgb = true,
+        blend_mode = BlendMode::default(),
+        sampling = ImageSampling::default(),
+        edge_mode = ImageEdgeMode::default()
     ))]
     fn __new__(
         py: Python,
@@ -99,6 +164,9 @@ impl PyImageStimulus {
         anchor: Anchor,
         transform: Option<Transformation2D>,
         srgb: bool,
+        blend_mode: BlendMode,
+        sampling: ImageSampling,
+        edge_mode: ImageEdgeMode,
     ) -> (Self, PyStimulus) {
         let renderer_factory = helpers::get_renderer_factory(py).unwrap();
 
@@ -119,6 +187,9 @@ impl PyImageStimulus {
                 },
                 transform,
                 anchor,
+                sampling,
+                edge_mode,
+                blend_mode,
             )),
         )
     }
@@ -142,16 +213,16 @@ impl Stimulus for ImageStimulus {
         let screen_props = window_state.physical_screen;
 
         // convert physical units to pixels
-        let x = self.params.x.eval(window_size, screen_props);
-        let y = self.params.y.eval(window_size, screen_props);
+        let x = self.params.x.eval_position(Axis::X, window_size, screen_props);
+        let y = self.params.y.eval_position(Axis::Y, window_size, screen_props);
 
-        let width = self.params.width.eval(window_size, screen_props);
-        let height = self.params.height.eval(window_size, screen_props);
+        let width = self.params.width.eval_extent(x, Axis::X, window_size, screen_props);
+        let height = self.params.height.eval_extent(y, Axis::Y, window_size, screen_props);
 
         let (x, y) = self.anchor.to_top_left(x, y, width, height);
 
-        let image_offset_x = self.params.image_x.eval(window_size, screen_props);
-        let image_offset_y = self.params.image_y.eval(window_size, screen_props);
+        let image_offset_x = self.params.image_x.eval(Axis::X, window_size, screen_props);
+        let image_offset_y = self.params.image_y.eval(Axis::Y, window_size, screen_props);
 
         let trans_mat = self.transformation.eval(window_size, screen_props);
 
@@ -165,11 +236,12 @@ impl Stimulus for ImageStimulus {
                 image: &self.image,
                 start: (x + image_offset_x, y + image_offset_y).into(),
                 fit_mode: ImageFitMode::Exact { width, height },
-                sampling: ImageSampling::Linear,
-                edge_mode: (Extend::Pad, Extend::Pad),
+                sampling: self.sampling.into(),
+                edge_mode: renderer::brushes::Extend::from(self.edge_mode).into(),
                 transform: None,
                 alpha: Some(self.params.opacity as f32),
             },
+            FillStyle::NonZero,
             Some(self.transformation.eval(window_size, screen_props).into()),
             None,
         );
@@ -183,6 +255,14 @@ impl Stimulus for ImageStimulus {
         self.visible
     }
 
+    fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
     fn animations(&mut self) -> &mut Vec<Animation> {
         &mut self.animations
     }
@@ -204,26 +284,25 @@ impl Stimulus for ImageStimulus {
     }
 
     fn contains(&self, x: Size, y: Size, window: &Window) -> bool {
-        let window_state = window.state.lock().unwrap();
-        let window_size = window_state.size;
-        let screen_props = window_state.physical_screen;
-
-        let ix = self.params.x.eval(window_size, screen_props);
-        let iy = self.params.y.eval(window_size, screen_props);
-        let width = self.params.width.eval(window_size, screen_props);
-        let height = self.params.height.eval(window_size, screen_props);
+        let Some((x, y)) = self.local_point(&x, &y, window) else {
+            return false;
+        };
 
-        let trans_mat = self.transformation.eval(window_size, screen_props);
+        self.local_bounds(window).contains_point(x, y)
+    }
 
-        let x = x.eval(window_size, screen_props);
-        let y = y.eval(window_size, screen_props);
+    fn local_bounds(&self, window: &Window) -> Rect {
+        let window_state = window.lock_state();
+        let window_size = window_state.size;
+        let screen_props = window_state.physical_screen;
 
-        // apply transformation by multiplying the point with the transformation matrix
-        let p = nalgebra::Vector3::new(x, y, 1.0);
-        let p_new = trans_mat * p;
+        let x = self.params.x.eval_position(Axis::X, window_size, screen_props);
+        let y = self.params.y.eval_position(Axis::Y, window_size, screen_props);
+        let width = self.params.width.eval_extent(x, Axis::X, window_size, screen_props);
+        let height = self.params.height.eval_extent(y, Axis::Y, window_size, screen_props);
 
-        // check if the point is inside the rectangle
-        p_new[0] >= ix && p_new[0] <= ix + width && p_new[1] >= iy && p_new[1] <= iy + height
+        let (x, y) = self.anchor.to_top_left(x, y, width, height);
+        Rect::new(x, y, width, height)
     }
 
     fn get_param(&self, name: &str) -> Option<StimulusParamValue> {
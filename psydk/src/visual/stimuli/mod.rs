@@ -9,21 +9,23 @@ use numpy::PyUntypedArrayMethods;
 use uuid::Uuid;
 
 use dyn_clone::DynClone;
+use psydk_proc::FromPyStr;
 use pyo3::{exceptions::PyValueError, prelude::*};
 use renderer::image::GenericImageView;
 use strum_macros::{Display, EnumString};
 
 use super::{
-    geometry::{IntoSize, Size, Transformation2D},
-    window::{Frame, Window, WindowState},
+    geometry::{Axis, IntoSize, OrientedRect, Rect, Size, Transformation2D},
+    window::{Frame, PhysicalScreen, PixelSize, Window, WindowState},
 };
-use crate::visual::color::LinRgba;
+use crate::visual::color::{InterpolationSpace, LinRgba};
 
 pub mod animations;
 mod helpers;
 
 pub mod gabor;
 // pub mod grid;
+pub mod group;
 pub mod image;
 pub mod pattern;
 pub mod shape;
@@ -46,6 +48,7 @@ pub enum StimulusParamValue {
 }
 
 #[derive(Debug, Clone, EnumString, Display, Default)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
 pub enum StrokeStyle {
     #[default]
     None,
@@ -53,23 +56,176 @@ pub enum StrokeStyle {
     Dashed,
     Dotted,
     DashDot,
-    Dashes(Vec<f64>),
+    /// Alternating lengths of dashes and gaps.
+    Dashes(Vec<Size>),
 }
 
-// implement IntoPy for StrokeStyle (by converting it to a string)
+impl StrokeStyle {
+    /// Creates the renderer stroke style for a stroke of the given width in
+    /// pixels. Returns `None` if no stroke should be drawn. The named dash
+    /// patterns scale with the stroke width, explicit dash lengths are
+    /// measured from the horizontal position `start`.
+    pub fn to_renderer_style(
+        &self,
+        width: f64,
+        start: f32,
+        cap: StrokeCap,
+        join: StrokeJoin,
+        window_size: PixelSize,
+        screen: PhysicalScreen,
+    ) -> Option<renderer::styles::StrokeStyle> {
+        if width <= 0.0 {
+            return None;
+        }
+
+        let style = renderer::styles::StrokeStyle::new(width)
+            .with_caps(cap.into())
+            .with_join(join.into());
+
+        match self {
+            StrokeStyle::None => None,
+            StrokeStyle::Solid => Some(style),
+            StrokeStyle::Dashed => Some(style.with_dashes(0.0, [3.0 * width, 3.0 * width])),
+            // zero length dashes with round caps result in round dots
+            StrokeStyle::Dotted => Some(
+                style
+                    .with_caps(renderer::styles::Cap::Round)
+                    .with_dashes(0.0, [0.0, 2.0 * width]),
+            ),
+            StrokeStyle::DashDot => Some(style.with_dashes(0.0, [4.0 * width, 2.0 * width, width, 2.0 * width])),
+            StrokeStyle::Dashes(dashes) => Some(
+                style.with_dashes(
+                    0.0,
+                    dashes
+                        .iter()
+                        .map(|d| d.eval_extent(start, Axis::X, window_size, screen) as f64),
+                ),
+            ),
+        }
+    }
+}
+
+// implement IntoPy for StrokeStyle (by converting it to a snake_case string, or a list of sizes for custom dashes)
 impl IntoPy<PyObject> for StrokeStyle {
     fn into_py(self, py: Python) -> PyObject {
-        self.to_string().into_py(py)
+        match self {
+            StrokeStyle::Dashes(dashes) => dashes.into_py(py),
+            style => style.to_string().into_py(py),
+        }
     }
 }
 
-// implement FromPyObject for StrokeStyle (by parsing it from a string)
+// implement FromPyObject for StrokeStyle (by parsing it from a string or a list of sizes)
 impl<'p> FromPyObject<'p> for StrokeStyle {
     fn extract_bound(ob: &Bound<'p, PyAny>) -> PyResult<Self> {
-        let s = ob.extract::<String>()?;
-        match TryFrom::try_from(s.as_str()) {
-            Ok(style) => Ok(style),
-            Err(_) => Err(PyValueError::new_err("invalid stroke style")),
+        if let Ok(s) = ob.extract::<String>() {
+            return match TryFrom::try_from(s.as_str()) {
+                Ok(style) => Ok(style),
+                Err(_) => Err(PyValueError::new_err("invalid stroke style")),
+            };
+        }
+
+        let dashes = ob.extract::<Vec<IntoSize>>()?;
+        Ok(StrokeStyle::Dashes(dashes.into_iter().map(|d| d.into()).collect()))
+    }
+}
+
+/// The shape at the ends of a stroke.
+#[derive(EnumString, Debug, Clone, Copy, PartialEq, Default, FromPyStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum StrokeCap {
+    #[default]
+    Butt,
+    Square,
+    Round,
+}
+
+impl From<StrokeCap> for renderer::styles::Cap {
+    fn from(cap: StrokeCap) -> Self {
+        match cap {
+            StrokeCap::Butt => renderer::styles::Cap::Butt,
+            StrokeCap::Square => renderer::styles::Cap::Square,
+            StrokeCap::Round => renderer::styles::Cap::Round,
+        }
+    }
+}
+
+/// The shape at the corners of a stroke.
+#[derive(EnumString, Debug, Clone, Copy, PartialEq, Default, FromPyStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum StrokeJoin {
+    #[default]
+    Miter,
+    Bevel,
+    Round,
+}
+
+impl From<StrokeJoin> for renderer::styles::Join {
+    fn from(join: StrokeJoin) -> Self {
+        match join {
+            StrokeJoin::Miter => renderer::styles::Join::Miter,
+            StrokeJoin::Bevel => renderer::styles::Join::Bevel,
+            StrokeJoin::Round => renderer::styles::Join::Round,
+        }
+    }
+}
+
+/// The rule that decides which parts of a shape are filled.
+#[derive(EnumString, Debug, Clone, Copy, PartialEq, Default, FromPyStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum FillRule {
+    #[default]
+    NonZero,
+    EvenOdd,
+}
+
+impl From<FillRule> for renderer::styles::FillStyle {
+    fn from(rule: FillRule) -> Self {
+        match rule {
+            FillRule::NonZero => renderer::styles::FillStyle::NonZero,
+            FillRule::EvenOdd => renderer::styles::FillStyle::EvenOdd,
+        }
+    }
+}
+
+/// How a stimulus is combined with what has already been drawn. The names
+/// follow the composite operations of the HTML canvas, e.g., "lighter" adds
+/// the colors and "multiply" multiplies them.
+#[derive(EnumString, Display, Debug, Clone, Copy, PartialEq, Default, FromPyStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum BlendMode {
+    #[default]
+    SourceOver,
+    DestinationOver,
+    SourceIn,
+    DestinationIn,
+    SourceOut,
+    DestinationOut,
+    SourceAtop,
+    DestinationAtop,
+    Lighter,
+    Copy,
+    Xor,
+    Multiply,
+    Modulate,
+}
+
+impl From<BlendMode> for renderer::styles::BlendMode {
+    fn from(mode: BlendMode) -> Self {
+        match mode {
+            BlendMode::SourceOver => renderer::styles::BlendMode::SourceOver,
+            BlendMode::DestinationOver => renderer::styles::BlendMode::DestinationOver,
+            BlendMode::SourceIn => renderer::styles::BlendMode::SourceIn,
+            BlendMode::DestinationIn => renderer::styles::BlendMode::DestinationIn,
+            BlendMode::SourceOut => renderer::styles::BlendMode::SourceOut,
+            BlendMode::DestinationOut => renderer::styles::BlendMode::DestinationOut,
+            BlendMode::SourceAtop => renderer::styles::BlendMode::SourceAtop,
+            BlendMode::DestinationAtop => renderer::styles::BlendMode::DestinationAtop,
+            BlendMode::Lighter => renderer::styles::BlendMode::Lighter,
+            BlendMode::Copy => renderer::styles::BlendMode::Copy,
+            BlendMode::Xor => renderer::styles::BlendMode::Xor,
+            BlendMode::Multiply => renderer::styles::BlendMode::Multiply,
+            BlendMode::Modulate => renderer::styles::BlendMode::Modulate,
         }
     }
 }
@@ -136,12 +292,32 @@ pub trait Stimulus: downcast_rs::Downcast + std::fmt::Debug + Send {
     /// Draw the stimulus onto the frame.
     fn draw(&mut self, scene: &mut Frame);
 
-    /// Check if the stimulus contains a specific Point.
+    /// Check if the stimulus contains a specific Point. The point is given in
+    /// window coordinates, so the anchor and the transformation of the
+    /// stimulus are taken into account.
     fn contains(&self, x: Size, y: Size, window: &Window) -> bool {
         // by default, stimuli will report false for contains
         false
     }
 
+    /// Returns the axis-aligned bounds of the stimulus in pixels, before its
+    /// transformation is applied. The bounds include the stroke.
+    fn local_bounds(&self, window: &Window) -> Rect;
+
+    /// Returns the bounds of the stimulus after its transformation is
+    /// applied, i.e., the transformed `local_bounds`.
+    fn oriented_bounds(&self, window: &Window) -> OrientedRect {
+        let rect = self.local_bounds(window);
+        let matrix = self.transformation().eval(window.size(), window.physical_screen());
+        OrientedRect::new(rect, &matrix)
+    }
+
+    /// Returns the axis-aligned bounds of the stimulus in pixels, after its
+    /// transformation is applied.
+    fn bounds(&self, window: &Window) -> Rect {
+        self.oriented_bounds(window).bounds()
+    }
+
     /// Return the UUID that identifies the stimulus.
     fn uuid(&self) -> Uuid;
 
@@ -176,6 +352,16 @@ pub trait Stimulus: downcast_rs::Downcast + std::fmt::Debug + Send {
         self.set_visible(!self.visible());
     }
 
+    /// Returns the blend mode that is used to draw the stimulus.
+    fn blend_mode(&self) -> BlendMode {
+        BlendMode::default()
+    }
+
+    /// Set the blend mode that is used to draw the stimulus.
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        // do nothing by default
+    }
+
     // Animation methods
 
     /// Returns the animations that are associated with this stimulus.
@@ -197,17 +383,26 @@ pub trait Stimulus: downcast_rs::Downcast + std::fmt::Debug + Send {
         duration: f64,
         repeat: Repeat,
         easing: TransitionFunction,
+        interpolation: InterpolationSpace,
     ) {
-        let animation = Animation::new(parameter, from, to, duration, Instant::now(), repeat, easing);
+        let animation = Animation::new(parameter, from, to, duration, Instant::now(), repeat, easing)
+            .with_interpolation(interpolation);
         self.add_animation(animation);
     }
 
-    /// Update the object's state based on the current time. Finished animations are removed.
+    /// Update the object's state based on the current time. Finished animations are removed,
+    /// as are animations whose value can not be computed (the parameter keeps its value).
     fn update_animations(&mut self, time: Instant, window_state: &WindowState) {
         let mut params_to_set = Vec::new();
 
         self.animations().retain_mut(|animation| {
-            let value = animation.value(time, window_state);
+            let value = match animation.value(time, window_state) {
+                Ok(value) => value,
+                Err(e) => {
+                    log::warn!("Stopping the animation of {}: {}", animation.parameter(), e);
+                    return false;
+                }
+            };
             params_to_set.push((animation.parameter().to_string(), value));
             if animation.finished(time) {
                 return false;
@@ -239,6 +434,13 @@ pub trait Stimulus: downcast_rs::Downcast + std::fmt::Debug + Send {
         self.add_transformation(Transformation2D::ScalePoint(sx, sy, x, y));
     }
 
+    /// Move the object to the given x and y coordinates by setting its
+    /// position parameters (`x` and `y` by default).
+    fn set_position(&mut self, x: Size, y: Size) {
+        self.set_param("x", StimulusParamValue::Size(x));
+        self.set_param("y", StimulusParamValue::Size(y));
+    }
+
     /// Set the translation of the object to the given x and y coordinates. This
     /// overwrites any previously applied transformations.
     fn set_translation(&mut self, x: Size, y: Size) {
@@ -248,12 +450,23 @@ pub trait Stimulus: downcast_rs::Downcast + std::fmt::Debug + Send {
     /// Return the current transformation.
     fn transformation(&self) -> Transformation2D;
 
-    /// Transforms a point from the window coordinate system to the stimulus.
-    /// coordinate system.
-    fn transform_point(&self, x: f32, y: f32, window: &Window) -> (f32, f32) {
-        // TODO
-        // self.transformation().transform_point(x, y, &window.physical_properties)
-        (x, y)
+    /// Transforms a point (in pixels) from the window coordinate system to the
+    /// stimulus coordinate system, i.e., maps it through the inverse of the
+    /// stimulus' transformation. Returns `None` if the transformation can not
+    /// be inverted (e.g., the stimulus is scaled to zero).
+    fn transform_point(&self, x: f32, y: f32, window: &Window) -> Option<(f32, f32)> {
+        let window_state = window.lock_state();
+        self.transformation()
+            .inverse_transform_point(x, y, window_state.size, window_state.physical_screen)
+    }
+
+    /// Converts a point in window coordinates (in any unit) to pixels in the
+    /// stimulus coordinate system (see `transform_point`).
+    fn local_point(&self, x: &Size, y: &Size, window: &Window) -> Option<(f32, f32)> {
+        let (window_size, screen_props) = (window.size(), window.physical_screen());
+        let x = x.eval_position(Axis::X, window_size, screen_props);
+        let y = y.eval_position(Axis::Y, window_size, screen_props);
+        self.transform_point(x, y, window)
     }
 
     /// Rotate the object around the given point by the given angle.
@@ -517,6 +730,69 @@ macro_rules! impl_pystimulus_for_wrapper {
                 downcast_stimulus!(slf, $name).visible()
             }
 
+            /// The blend mode that is used to draw the stimulus, e.g.,
+            /// "source_over" (the default), "lighter" (additive), "multiply"
+            /// or "xor".
+            #[getter]
+            fn get_blend_mode(slf: PyRef<'_, Self>) -> String {
+                downcast_stimulus!(slf, $name).blend_mode().to_string()
+            }
+
+            #[setter]
+            fn set_blend_mode(mut slf: PyRefMut<'_, Self>, blend_mode: crate::visual::stimuli::BlendMode) {
+                downcast_py_stimulus_mut!(slf, $name).set_blend_mode(blend_mode);
+            }
+
+            /// Returns the axis-aligned bounds of the stimulus in pixels, taking
+            /// the anchor and the transformation of the stimulus into account.
+            /// Use `Rect.to()` to convert the bounds to another unit.
+            ///
+            /// Parameters
+            /// ----------
+            /// window : Window
+            ///    The window the stimulus is shown in.
+            ///
+            /// Returns
+            /// -------
+            /// Rect
+            ///    The bounds of the stimulus.
+            fn bounds(mut slf: PyRefMut<'_, Self>, window: &Window) -> crate::visual::geometry::Rect {
+                downcast_stimulus!(slf, $name).bounds(window)
+            }
+
+            /// Returns the bounds of the stimulus before its transformation,
+            /// transformed with it. Unlike `bounds()`, this follows rotations
+            /// of the stimulus.
+            ///
+            /// Parameters
+            /// ----------
+            /// window : Window
+            ///    The window the stimulus is shown in.
+            ///
+            /// Returns
+            /// -------
+            /// OrientedRect
+            ///    The oriented bounds of the stimulus.
+            fn oriented_bounds(mut slf: PyRefMut<'_, Self>, window: &Window) -> crate::visual::geometry::OrientedRect {
+                downcast_stimulus!(slf, $name).oriented_bounds(window)
+            }
+
+            /// Check whether the stimulus contains a point, taking the anchor
+            /// and the transformation of the stimulus into account.
+            ///
+            /// Parameters
+            /// ----------
+            /// x : Size
+            ///    The x-coordinate of the point.
+            /// y : Size
+            ///    The y-coordinate of the point.
+            /// window : Window
+            ///    The window the stimulus is shown in.
+            ///
+            /// Returns
+            /// -------
+            /// bool
+            ///    True if the point lies within the stimulus.
             fn contains(mut slf: PyRefMut<'_, Self>, x: IntoSize, y: IntoSize, window: &Window) -> bool {
                 downcast_stimulus!(slf, $name).contains(x.into(), y.into(), window)
             }
@@ -532,7 +808,17 @@ macro_rules! impl_pystimulus_for_wrapper {
             ///   The target value of the animation.
             /// duration : float
             ///  The duration of the animation in seconds.
-            fn animate(mut slf: PyRefMut<'_, Self>, param_name: &str, to: Py<PyAny>, duration: f64) -> PyResult<()> {
+            /// interpolation : str, optional
+            ///  The color space in which colors are interpolated: "linear" (the
+            ///  default), "srgb", "lab", "lch" or "oklab".
+            #[pyo3(signature = (param_name, to, duration, interpolation = crate::visual::color::InterpolationSpace::Linear))]
+            fn animate(
+                mut slf: PyRefMut<'_, Self>,
+                param_name: &str,
+                to: Py<PyAny>,
+                duration: f64,
+                interpolation: crate::visual::color::InterpolationSpace,
+            ) -> PyResult<()> {
                 let from = downcast_stimulus!(slf, $name)
                     .get_param(param_name)
                     .ok_or_else(|| PyValueError::new_err(format!("parameter {} not found", param_name)))?;
@@ -554,6 +840,9 @@ macro_rules! impl_pystimulus_for_wrapper {
                     StimulusParamValue::i64(_) => {
                         StimulusParamValue::i64(to.extract::<i64>(slf.py()).expect("invalid value"))
                     }
+                    StimulusParamValue::LinRgba(_) => {
+                        StimulusParamValue::LinRgba(to.extract::<crate::visual::color::IntoLinRgba>(slf.py())?.into())
+                    }
                     _ => return Err(PyValueError::new_err("invalid value type for animation")),
                 };
 
@@ -564,6 +853,7 @@ macro_rules! impl_pystimulus_for_wrapper {
                     duration,
                     Repeat::Loop(1),
                     TransitionFunction::None,
+                    interpolation,
                 );
                 Ok(())
             }
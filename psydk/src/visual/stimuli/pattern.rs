@@ -15,12 +15,13 @@ use uuid::Uuid;
 unsafe impl Send for PatternStimulus {}
 
 use super::{
-    animations::Animation, helpers, impl_pystimulus_for_wrapper, PyStimulus, Stimulus, StimulusParamValue,
-    StimulusParams, StrokeStyle,
+    animations::Animation, helpers, impl_pystimulus_for_wrapper, BlendMode, FillRule, PyStimulus, Stimulus,
+    StimulusParamValue, StimulusParams, StrokeCap, StrokeJoin, StrokeStyle,
 };
 use crate::visual::{
     color::{IntoLinRgba, LinRgba},
-    geometry::{Shape, Size, Transformation2D},
+    fill::{Gradient, IntoFill},
+    geometry::{Axis, Rect, Shape, Size, Transformation2D},
     window::Frame,
 };
 
@@ -55,8 +56,12 @@ pub struct PatternStimulus {
     id: uuid::Uuid,
     params: PatternParams,
     fill_pattern: FillPattern,
+    stroke_cap: StrokeCap,
+    stroke_join: StrokeJoin,
+    fill_rule: FillRule,
+    blend_mode: BlendMode,
 
-    gradient_colors: Option<Vec<LinRgba>>,
+    fill_gradient: Option<Gradient>,
     pattern_image: Option<DynamicBitmap>,
     transform: Transformation2D,
     animations: Vec<Animation>,
@@ -72,13 +77,18 @@ impl PatternStimulus {
         phase_y: f64,
         cycle_length: Size,
         fill_color: LinRgba,
+        fill_gradient: Option<Gradient>,
         background_color: LinRgba,
         pattern: FillPattern,
         pattern_rotation: f64,
         stroke_style: StrokeStyle,
         stroke_color: LinRgba,
         stroke_width: Size,
+        stroke_cap: StrokeCap,
+        stroke_join: StrokeJoin,
+        fill_rule: FillRule,
         alpha: Option<f64>,
+        blend_mode: BlendMode,
 
         transform: Transformation2D,
         renderer_factory: &dyn RendererFactory,
@@ -101,18 +111,35 @@ impl PatternStimulus {
                 alpha,
             },
             fill_pattern: pattern,
-            gradient_colors: None,
+            stroke_cap,
+            stroke_join,
+            fill_rule,
+            blend_mode,
+            fill_gradient,
             pattern_image: None,
             transform,
             animations: Vec::new(),
             visible: true,
         };
 
-        let fg = fill_color;
-        let bg = background_color;
+        // colors that depend on the window calibration are baked when the stimulus is drawn
+        if fill_color.is_resolved() && background_color.is_resolved() {
+            stim.pattern_image = Self::pattern_bitmap(pattern, fill_color, background_color, renderer_factory);
+        }
+
+        stim
+    }
 
-        match pattern {
-            FillPattern::Uniform => {}
+    /// Creates the bitmap of a single cycle of the pattern from two resolved
+    /// colors. Returns `None` for patterns that are not drawn from a bitmap.
+    fn pattern_bitmap(
+        pattern: FillPattern,
+        fg: LinRgba,
+        bg: LinRgba,
+        renderer_factory: &dyn RendererFactory,
+    ) -> Option<DynamicBitmap> {
+        let image = match pattern {
+            FillPattern::Uniform | FillPattern::Sinosoidal => return None,
             FillPattern::Stripes => {
                 let image_2x2_data = vec![
                     fg.r_u8(),
@@ -124,13 +151,8 @@ impl PatternStimulus {
                     bg.b_u8(),
                     bg.a_u8(),
                 ];
-                let image_2x2 = renderer::image::RgbaImage::from_raw(2, 1, image_2x2_data).unwrap();
-
-                let pattern_image =
-                    renderer_factory.create_bitmap(renderer::image::DynamicImage::ImageRgba8(image_2x2));
-                stim.pattern_image = Some(pattern_image);
+                renderer::image::RgbaImage::from_raw(2, 1, image_2x2_data).unwrap()
             }
-            FillPattern::Sinosoidal => todo!(),
             FillPattern::Checkerboard => {
                 let image_2x2_data = vec![
                     fg.r_u8(),
@@ -150,15 +172,11 @@ impl PatternStimulus {
                     fg.b_u8(),
                     fg.a_u8(),
                 ];
-                let image_2x2 = renderer::image::RgbaImage::from_raw(2, 2, image_2x2_data).unwrap();
-
-                let pattern_image =
-                    renderer_factory.create_bitmap(renderer::image::DynamicImage::ImageRgba8(image_2x2));
-                stim.pattern_image = Some(pattern_image);
+                renderer::image::RgbaImage::from_raw(2, 2, image_2x2_data).unwrap()
             }
-        }
+        };
 
-        stim
+        Some(renderer_factory.create_bitmap(renderer::image::DynamicImage::ImageRgba8(image)))
     }
 }
 
@@ -174,16 +192,25 @@ impl PatternStimulus {
 ///     The x-coordinate of the center of the shape.
 /// y : Size, optional
 ///     The y-coordinate of the center of the shape.
-/// fill_color : Union[LinRgba, (float, float, float), (float, float, float, float), str], optional
-///    The fill color of the shape.
+/// fill_color : Union[LinRgba, Gradient, (float, float, float), (float, float, float, float), str], optional
+///    The fill color of the shape. A gradient is drawn instead of the pattern.
 /// stroke_style : StrokeStyle, optional
 ///    The stroke style of the shape.
 /// stroke_color : Union[LinRgba, (float, float, float), (float, float, float, float), str], optional
 ///   The stroke color of the shape.
 /// stroke_width : Union[Size, float], optional
 ///  The stroke width of the shape.
+/// stroke_cap : str, optional
+///  The shape of the stroke ends: "butt", "square" or "round".
+/// stroke_join : str, optional
+///  The shape of the stroke corners: "miter", "bevel" or "round".
+/// fill_rule : str, optional
+///  The fill rule, "non_zero" or "even_odd". Use "even_odd" for shapes with holes.
 /// alpha : float, optional
 ///  The alpha channel of the shape.
+/// blend_mode : str, optional
+///  How the shape is blended with what is behind it, e.g., "source_over"
+///  (the default), "lighter" (additive), "multiply" or "xor".
 /// transform : Transformation2D, optional
 /// The transformation of the shape.
 pub struct PyPatternStimulus();
@@ -198,14 +225,18 @@ impl PyPatternStimulus {
         phase_x = 0.0,
         phase_y = 0.0,
         cycle_length = IntoSize(Size::Pixels(100.0)),
-        fill_color = IntoLinRgba(LinRgba::default()),
+        fill_color = IntoFill::Color(IntoLinRgba(LinRgba::default())),
         background_color = IntoLinRgba(LinRgba::default()),
         pattern = FillPattern::Uniform,
         pattern_rotation = 0.0,
         stroke_style = StrokeStyle::default(),
         stroke_color = IntoLinRgba(LinRgba::default()),
         stroke_width = IntoSize(Size::Pixels(0.0)),
+        stroke_cap = StrokeCap::default(),
+        stroke_join = StrokeJoin::default(),
+        fill_rule = FillRule::default(),
         alpha = None,
+        blend_mode = BlendMode::default(),
         transform = Transformation2D::Identity()
     ))]
     /// A stimulus that displays a shape.
@@ -218,16 +249,26 @@ impl PyPatternStimulus {
     ///     The x-coordinate of the center of the shape.
     /// y : Size, optional
     ///     The y-coordinate of the center of the shape.
-    /// fill_color : Union[LinRgba, (float, float, float), (float, float, float, float), str], optional
-    ///    The fill color of the shape.
+    /// fill_color : Union[LinRgba, Gradient, (float, float, float), (float, float, float, float), str], optional
+    ///    The fill color of the shape. A gradient is drawn instead of the pattern.
     /// stroke_style : StrokeStyle, optional
-    ///    The stroke style of the shape.
+    ///    The stroke style of the shape: "none", "solid", "dashed", "dotted", "dash_dot"
+    ///    or a list of dash and gap lengths.
     /// stroke_color : Union[LinRgba, (float, float, float), (float, float, float, float), str], optional
     ///   The stroke color of the shape.
     /// stroke_width : Union[Size, float], optional
     ///    The stroke width of the shape.
+    /// stroke_cap : str, optional
+    ///    The shape of the stroke ends: "butt", "square" or "round".
+    /// stroke_join : str, optional
+    ///    The shape of the stroke corners: "miter", "bevel" or "round".
+    /// fill_rule : str, optional
+    ///    The fill rule, "non_zero" or "even_odd". Use "even_odd" for shapes with holes.
     /// alpha : float, optional
     ///    The alpha channel of the shape.
+    /// blend_mode : str, optional
+    ///    How the shape is blended with what is behind it, e.g., "source_over"
+    ///    (the default), "lighter" (additive), "multiply" or "xor".
     /// transform : Transformation2D, optional
     ///    The transformation of the shape.
     fn __new__(
@@ -238,17 +279,22 @@ impl PyPatternStimulus {
         phase_x: f64,
         phase_y: f64,
         cycle_length: IntoSize,
-        fill_color: IntoLinRgba,
+        fill_color: IntoFill,
         background_color: IntoLinRgba,
         pattern: FillPattern,
         pattern_rotation: f64,
         stroke_style: StrokeStyle,
         stroke_color: IntoLinRgba,
         stroke_width: IntoSize,
+        stroke_cap: StrokeCap,
+        stroke_join: StrokeJoin,
+        fill_rule: FillRule,
         alpha: Option<f64>,
+        blend_mode: BlendMode,
         transform: Transformation2D,
     ) -> (Self, PyStimulus) {
         let renderer_factory = helpers::get_renderer_factory(py).unwrap();
+        let (fill_color, fill_gradient) = fill_color.split();
         (
             Self(),
             PyStimulus::new(PatternStimulus::new(
@@ -258,23 +304,71 @@ impl PyPatternStimulus {
                 phase_x,
                 phase_y,
                 cycle_length.into(),
-                fill_color.into(),
+                fill_color.unwrap_or_default(),
+                fill_gradient,
                 background_color.into(),
                 pattern,
                 pattern_rotation,
                 stroke_style,
                 stroke_color.into(),
                 stroke_width.into(),
+                stroke_cap,
+                stroke_join,
+                fill_rule,
                 alpha,
+                blend_mode,
                 transform,
                 renderer_factory.inner(),
             )),
         )
     }
+
+    /// The gradient that fills the shape instead of the pattern, or None.
+    /// Setting a gradient replaces the fill color.
+    #[getter]
+    fn get_fill_gradient(slf: PyRef<'_, Self>) -> Option<Gradient> {
+        downcast_stimulus!(slf, PatternStimulus).fill_gradient.clone()
+    }
+
+    #[setter]
+    fn set_fill_gradient(mut slf: PyRefMut<'_, Self>, gradient: Option<Gradient>) {
+        downcast_py_stimulus_mut!(slf, PatternStimulus).fill_gradient = gradient;
+    }
 }
 
 impl_pystimulus_for_wrapper!(PyPatternStimulus, PatternStimulus);
 
+impl PatternStimulus {
+    /// Returns the shape in pixels and how far its stroke extends beyond the
+    /// outline.
+    fn geometry(&self, window: &Window) -> (renderer::shapes::Shape, f64) {
+        let window_state = window.lock_state();
+        let window_size = window_state.size;
+        let screen_props = window_state.physical_screen;
+
+        let x_origin = self.params.x.eval_position(Axis::X, window_size, screen_props) as f64;
+        let y_origin = self.params.y.eval_position(Axis::Y, window_size, screen_props) as f64;
+
+        let shape = self
+            .params
+            .shape
+            .to_renderer_shape(x_origin, y_origin, window_size, screen_props);
+
+        // the stroke is centred on the outline of the shape
+        let half_stroke = match self.params.stroke_style {
+            StrokeStyle::None => 0.0,
+            _ => {
+                self.params
+                    .stroke_width
+                    .eval_extent(x_origin as f32, Axis::X, window_size, screen_props) as f64
+                    / 2.0
+            }
+        };
+
+        (shape, half_stroke)
+    }
+}
+
 impl Stimulus for PatternStimulus {
     fn uuid(&self) -> Uuid {
         self.id
@@ -300,108 +394,116 @@ impl Stimulus for PatternStimulus {
 
         let renderer_factory = window_state.renderer.create_renderer_factory();
 
-        let x_origin = self.params.x.eval(windows_size, screen_props) as f64;
-        let y_origin = self.params.y.eval(windows_size, screen_props) as f64;
+        let x_origin = self.params.x.eval_position(Axis::X, windows_size, screen_props) as f64;
+        let y_origin = self.params.y.eval_position(Axis::Y, windows_size, screen_props) as f64;
 
-        let cycle_length = self.params.cycle_length.eval(windows_size, screen_props);
+        let cycle_length = self
+            .params
+            .cycle_length
+            .eval_extent(x_origin as f32, Axis::X, windows_size, screen_props);
 
         let shift_x = (self.params.phase_x % 360.0) / 360.0 * cycle_length as f64;
         let shift_y = (self.params.phase_y % 360.0) / 360.0 * cycle_length as f64;
 
         let pattern_transform = Affine::rotate(self.params.pattern_rotation);
 
-        let fill_brush = match self.fill_pattern {
-            FillPattern::Uniform => Brush::Solid(self.params.fill_color.into()),
-            FillPattern::Sinosoidal => todo!(),
-            FillPattern::Checkerboard | FillPattern::Stripes => Brush::Image {
-                image: &self.pattern_image.as_ref().unwrap(),
-                start: (shift_x, shift_y).into(),
-                fit_mode: ImageFitMode::Exact {
-                    width: cycle_length,
-                    height: cycle_length,
-                },
-                sampling: ImageSampling::Nearest,
-                edge_mode: (Extend::Repeat, Extend::Repeat),
-                transform: Some(pattern_transform),
-                alpha: self.params.alpha.map(|a| a as f32),
-            },
+        let mut baked_image = None;
+
+        // a gradient takes precedence over the pattern
+        let fill_brush = if let Some(gradient) = &self.fill_gradient {
+            Brush::Gradient(
+                gradient.to_renderer_gradient(x_origin, y_origin, windows_size, screen_props, |c| {
+                    frame.resolve_color(c, &window_state)
+                }),
+            )
+        } else {
+            match self.fill_pattern {
+                FillPattern::Uniform => Brush::Solid(frame.resolve_color(self.params.fill_color, &window_state).into()),
+                FillPattern::Sinosoidal => todo!(),
+                FillPattern::Checkerboard | FillPattern::Stripes => {
+                    // patterns with colors that depend on the calibration are baked for every frame
+                    let image = match &self.pattern_image {
+                        Some(image) => image,
+                        None => {
+                            let fg = frame.resolve_color(self.params.fill_color, &window_state);
+                            let bg = frame.resolve_color(self.params.background_color, &window_state);
+                            let bitmap = Self::pattern_bitmap(self.fill_pattern, fg, bg, renderer_factory.as_ref());
+                            &*baked_image.insert(bitmap.unwrap())
+                        }
+                    };
+
+                    Brush::Image {
+                        image,
+                        start: (shift_x, shift_y).into(),
+                        fit_mode: ImageFitMode::Exact {
+                            width: cycle_length,
+                            height: cycle_length,
+                        },
+                        sampling: ImageSampling::Nearest,
+                        edge_mode: (Extend::Repeat, Extend::Repeat),
+                        transform: Some(pattern_transform),
+                        alpha: self.params.alpha.map(|a| a as f32),
+                    }
+                }
+            }
         };
 
-        let stroke_color = self.params.stroke_color;
+        let stroke_color = frame.resolve_color(self.params.stroke_color, &window_state);
 
         let stroke_brush = renderer::brushes::Brush::Solid(stroke_color.into());
 
-        let stroke_width = self.params.stroke_width.eval(windows_size, screen_props) as f64;
-
-        let stroke_options = renderer::styles::StrokeStyle::new(stroke_width);
-
-        match &self.params.shape {
-            Shape::Circle { x, y, radius } => {
-                let x = x.eval(windows_size, screen_props) as f64;
-                let y = y.eval(windows_size, screen_props) as f64;
-                let radius = radius.eval(windows_size, screen_props) as f64;
-
-                // move by x_origin and y_origin
-                let x = x + x_origin;
-                let y = y + y_origin;
-
-                let shape = renderer::shapes::Shape::circle((x, y), radius);
-
-                frame.scene_mut().draw_shape_fill(shape, fill_brush.clone(), None, None);
-
-                frame
-                    .scene_mut()
-                    .draw_shape_stroke(shape, stroke_brush, stroke_options, None, None);
-            }
-            Shape::Rectangle { x, y, width, height } => {
-                let x = x.eval(windows_size, screen_props) as f64;
-                let y = y.eval(windows_size, screen_props) as f64;
-                let width = width.eval(windows_size, screen_props) as f64;
-                let height = height.eval(windows_size, screen_props) as f64;
+        let stroke_width = self
+            .params
+            .stroke_width
+            .eval_extent(x_origin as f32, Axis::X, windows_size, screen_props) as f64;
+
+        let stroke_options = self.params.stroke_style.to_renderer_style(
+            stroke_width,
+            x_origin as f32,
+            self.stroke_cap,
+            self.stroke_join,
+            windows_size,
+            screen_props,
+        );
+
+        let shape = self
+            .params
+            .shape
+            .to_renderer_shape(x_origin, y_origin, windows_size, screen_props);
+        let transform: Affine = self.transform.eval(windows_size, screen_props).into();
+
+        // lines and arcs are open shapes that are only stroked
+        if !matches!(
+            self.params.shape,
+            Shape::Line { .. } | Shape::Polyline { .. } | Shape::Arc { .. }
+        ) {
+            frame
+                .scene_mut()
+                .draw_shape_fill(shape.clone(), fill_brush, self.fill_rule.into(), Some(transform), None);
+        }
 
-                // move by x_origin and y_origin
-                let x = x + x_origin;
-                let y = y + y_origin;
+        if let Some(stroke_options) = stroke_options {
+            frame
+                .scene_mut()
+                .draw_shape_stroke(shape, stroke_brush, stroke_options, Some(transform), None);
+        }
+    }
 
-                let shape = renderer::shapes::Shape::rectangle((x, y), width, height);
+    fn contains(&self, x: Size, y: Size, window: &Window) -> bool {
+        let Some(point) = self.local_point(&x, &y, window) else {
+            return false;
+        };
 
-                frame.scene_mut().draw_shape_fill(shape, fill_brush.clone(), None, None);
+        let (shape, half_stroke) = self.geometry(window);
+        shape.contains(point, self.fill_rule.into(), half_stroke)
+    }
 
-                frame
-                    .scene_mut()
-                    .draw_shape_stroke(shape, stroke_brush, stroke_options, None, None);
-            }
-            Shape::Ellipse {
-                x,
-                y,
-                radius_x,
-                radius_y,
-            } => {
-                todo!("Render ellipse")
-            }
-            Shape::Line { x1, y1, x2, y2 } => {
-                let x1 = x1.eval(windows_size, screen_props) as f64;
-                let y1 = y1.eval(windows_size, screen_props) as f64;
-                let x2 = x2.eval(windows_size, screen_props) as f64;
-                let y2 = y2.eval(windows_size, screen_props) as f64;
-
-                // move by x_origin and y_origin
-                let x1 = x1 + x_origin;
-                let y1 = y1 + y_origin;
-                let x2 = x2 + x_origin;
-                let y2 = y2 + y_origin;
-
-                let shape = renderer::shapes::Shape::line((x1, y1), (x2, y2));
-
-                frame
-                    .scene_mut()
-                    .draw_shape_stroke(shape, stroke_brush, stroke_options, None, None);
-            }
-            Shape::Polygon { points } => {
-                todo!("Render polygon")
-            }
-        };
+    fn local_bounds(&self, window: &Window) -> Rect {
+        let (shape, half_stroke) = self.geometry(window);
+        let (min, max) = shape.bounds();
+        Rect::bounding([(min.x as f32, min.y as f32), (max.x as f32, max.y as f32)]).expanded(half_stroke as f32)
     }
+
     fn set_visible(&mut self, visible: bool) {
         self.visible = visible;
     }
@@ -410,6 +512,14 @@ impl Stimulus for PatternStimulus {
         self.visible
     }
 
+    fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
     fn set_transformation(&mut self, transformation: crate::visual::geometry::Transformation2D) {
         self.transform = transformation;
     }
@@ -423,6 +533,10 @@ impl Stimulus for PatternStimulus {
     }
 
     fn set_param(&mut self, name: &str, value: StimulusParamValue) {
+        // a new fill color replaces the gradient
+        if name == "fill_color" {
+            self.fill_gradient = None;
+        }
         self.params.set_param(name, value)
     }
 }
@@ -7,12 +7,13 @@ use strum::EnumString;
 use uuid::Uuid;
 
 use super::{
-    animations::Animation, impl_pystimulus_for_wrapper, PyStimulus, Stimulus, StimulusParamValue, StimulusParams,
-    StrokeStyle,
+    animations::Animation, impl_pystimulus_for_wrapper, BlendMode, FillRule, PyStimulus, Stimulus, StimulusParamValue,
+    StimulusParams, StrokeCap, StrokeJoin, StrokeStyle,
 };
 use crate::visual::{
     color::{IntoLinRgba, LinRgba},
-    geometry::{Shape, Size, Transformation2D},
+    fill::{Gradient, IntoFill},
+    geometry::{Axis, Rect, Shape, Size, Transformation2D},
     window::Frame,
 };
 
@@ -32,6 +33,11 @@ pub struct ShapeParams {
 pub struct ShapeStimulus {
     id: uuid::Uuid,
     params: ShapeParams,
+    stroke_cap: StrokeCap,
+    stroke_join: StrokeJoin,
+    fill_rule: FillRule,
+    fill_gradient: Option<Gradient>,
+    blend_mode: BlendMode,
 
     transform: Transformation2D,
     animations: Vec<Animation>,
@@ -44,10 +50,15 @@ impl ShapeStimulus {
         x: Size,
         y: Size,
         fill_color: Option<LinRgba>,
+        fill_gradient: Option<Gradient>,
         stroke_style: Option<StrokeStyle>,
         stroke_color: Option<LinRgba>,
         stroke_width: Option<Size>,
+        stroke_cap: StrokeCap,
+        stroke_join: StrokeJoin,
+        fill_rule: FillRule,
         alpha: Option<f64>,
+        blend_mode: BlendMode,
 
         transform: Transformation2D,
     ) -> Self {
@@ -63,6 +74,11 @@ impl ShapeStimulus {
                 stroke_width,
                 alpha,
             },
+            stroke_cap,
+            stroke_join,
+            fill_rule,
+            fill_gradient,
+            blend_mode,
             transform,
             animations: Vec::new(),
             visible: true,
@@ -82,16 +98,25 @@ impl ShapeStimulus {
 ///     The x-coordinate of the center of the shape.
 /// y : Size, optional
 ///     The y-coordinate of the center of the shape.
-/// fill_color : Union[LinRgba, (float, float, float), (float, float, float, float), str], optional
-///    The fill color of the shape.
+/// fill_color : Union[LinRgba, Gradient, (float, float, float), (float, float, float, float), str], optional
+///    The fill color or gradient of the shape.
 /// stroke_style : StrokeStyle, optional
 ///    The stroke style of the shape.
 /// stroke_color : Union[LinRgba, (float, float, float), (float, float, float, float), str], optional
 ///   The stroke color of the shape.
 /// stroke_width : Union[Size, float], optional
 ///  The stroke width of the shape.
+/// stroke_cap : str, optional
+///  The shape of the stroke ends: "butt", "square" or "round".
+/// stroke_join : str, optional
+///  The shape of the stroke corners: "miter", "bevel" or "round".
+/// fill_rule : str, optional
+///  The fill rule, "non_zero" or "even_odd". Use "even_odd" for shapes with holes.
 /// alpha : float, optional
 ///  The alpha channel of the shape.
+/// blend_mode : str, optional
+///  How the shape is blended with what is behind it, e.g., "source_over"
+///  (the default), "lighter" (additive), "multiply" or "xor".
 /// transform : Transformation2D, optional
 /// The transformation of the shape.
 pub struct PyShapeStimulus();
@@ -107,7 +132,11 @@ impl PyShapeStimulus {
         stroke_style = None,
         stroke_color = None,
         stroke_width = None,
+        stroke_cap = StrokeCap::default(),
+        stroke_join = StrokeJoin::default(),
+        fill_rule = FillRule::default(),
         alpha = None,
+        blend_mode = BlendMode::default(),
         transform = Transformation2D::Identity()
     ))]
     /// A stimulus that displays a shape.
@@ -120,48 +149,106 @@ impl PyShapeStimulus {
     ///     The x-coordinate of the center of the shape.
     /// y : Size, optional
     ///     The y-coordinate of the center of the shape.
-    /// fill_color : Union[LinRgba, (float, float, float), (float, float, float, float), str], optional
-    ///    The fill color of the shape.
+    /// fill_color : Union[LinRgba, Gradient, (float, float, float), (float, float, float, float), str], optional
+    ///    The fill color or gradient of the shape.
     /// stroke_style : StrokeStyle, optional
-    ///    The stroke style of the shape.
+    ///    The stroke style of the shape: "solid", "dashed", "dotted", "dash_dot" or a
+    ///    list of dash and gap lengths.
     /// stroke_color : Union[LinRgba, (float, float, float), (float, float, float, float), str], optional
     ///   The stroke color of the shape.
     /// stroke_width : Union[Size, float], optional
     ///    The stroke width of the shape.
+    /// stroke_cap : str, optional
+    ///    The shape of the stroke ends: "butt", "square" or "round".
+    /// stroke_join : str, optional
+    ///    The shape of the stroke corners: "miter", "bevel" or "round".
+    /// fill_rule : str, optional
+    ///    The fill rule, "non_zero" or "even_odd". Use "even_odd" for shapes with holes.
     /// alpha : float, optional
     ///    The alpha channel of the shape.
+    /// blend_mode : str, optional
+    ///    How the shape is blended with what is behind it, e.g., "source_over"
+    ///    (the default), "lighter" (additive), "multiply" or "xor".
     /// transform : Transformation2D, optional
     ///    The transformation of the shape.
     fn __new__(
         shape: Shape,
         x: IntoSize,
         y: IntoSize,
-        fill_color: Option<IntoLinRgba>,
+        fill_color: Option<IntoFill>,
         stroke_style: Option<StrokeStyle>,
         stroke_color: Option<IntoLinRgba>,
         stroke_width: Option<IntoSize>,
+        stroke_cap: StrokeCap,
+        stroke_join: StrokeJoin,
+        fill_rule: FillRule,
         alpha: Option<f64>,
+        blend_mode: BlendMode,
         transform: Transformation2D,
     ) -> (Self, PyStimulus) {
+        let (fill_color, fill_gradient) = fill_color.map(IntoFill::split).unwrap_or((None, None));
         (
             Self(),
             PyStimulus::new(ShapeStimulus::new(
                 shape,
                 x.into(),
                 y.into(),
-                fill_color.map(|f| f.into()),
+                fill_color,
+                fill_gradient,
                 stroke_style,
                 stroke_color.map(|s| s.into()),
                 stroke_width.map(|s| s.into()),
+                stroke_cap,
+                stroke_join,
+                fill_rule,
                 alpha,
+                blend_mode,
                 transform,
             )),
         )
     }
+
+    /// The gradient that fills the shape, or None if it is filled with
+    /// `fill_color`. Setting a gradient replaces the fill color.
+    #[getter]
+    fn get_fill_gradient(slf: PyRef<'_, Self>) -> Option<Gradient> {
+        downcast_stimulus!(slf, ShapeStimulus).fill_gradient.clone()
+    }
+
+    #[setter]
+    fn set_fill_gradient(mut slf: PyRefMut<'_, Self>, gradient: Option<Gradient>) {
+        downcast_py_stimulus_mut!(slf, ShapeStimulus).fill_gradient = gradient;
+    }
 }
 
 impl_pystimulus_for_wrapper!(PyShapeStimulus, ShapeStimulus);
 
+impl ShapeStimulus {
+    /// Returns the shape in pixels and how far its stroke extends beyond the
+    /// outline.
+    fn geometry(&self, window: &Window) -> (renderer::shapes::Shape, f64) {
+        let window_state = window.lock_state();
+        let window_size = window_state.size;
+        let screen_props = window_state.physical_screen;
+
+        let x_origin = self.params.x.eval_position(Axis::X, window_size, screen_props) as f64;
+        let y_origin = self.params.y.eval_position(Axis::Y, window_size, screen_props) as f64;
+
+        let shape = self
+            .params
+            .shape
+            .to_renderer_shape(x_origin, y_origin, window_size, screen_props);
+
+        // the stroke is centred on the outline of the shape
+        let half_stroke = match (&self.params.stroke_style, &self.params.stroke_width) {
+            (Some(StrokeStyle::None), _) | (_, None) => 0.0,
+            (_, Some(width)) => width.eval_extent(x_origin as f32, Axis::X, window_size, screen_props) as f64 / 2.0,
+        };
+
+        (shape, half_stroke)
+    }
+}
+
 impl Stimulus for ShapeStimulus {
     fn uuid(&self) -> Uuid {
         self.id
@@ -185,93 +272,85 @@ impl Stimulus for ShapeStimulus {
         let windows_size = window_state.size;
         let screen_props = window_state.physical_screen;
 
-        let x_origin = self.params.x.eval(windows_size, screen_props) as f64;
-        let y_origin = self.params.y.eval(windows_size, screen_props) as f64;
+        let x_origin = self.params.x.eval_position(Axis::X, windows_size, screen_props) as f64;
+        let y_origin = self.params.y.eval_position(Axis::Y, windows_size, screen_props) as f64;
+
+        let fill_color = self.params.fill_color.map(|c| frame.resolve_color(c, &window_state));
+        let fill_gradient = self.fill_gradient.as_ref().map(|gradient| {
+            gradient.to_renderer_gradient(x_origin, y_origin, windows_size, screen_props, |c| {
+                frame.resolve_color(c, &window_state)
+            })
+        });
 
         let fill_brush = super::helpers::create_fill_brush(
-            &self.params.fill_color,
+            &fill_color,
             &self.params.stroke_style,
             &self.params.stroke_color,
             &self.params.stroke_width,
-            &None,
+            &fill_gradient,
         );
 
         let stroke_color = self.params.stroke_color.unwrap_or(LinRgba::new(0.0, 0.0, 0.0, 0.0));
+        let stroke_color = frame.resolve_color(stroke_color, &window_state);
 
         let stroke_brush = renderer::brushes::Brush::Solid(stroke_color.into());
 
         let stroke_width = self.params.stroke_width.clone().unwrap_or(Size::Pixels(0.0));
-        let stroke_width = stroke_width.eval(windows_size, screen_props) as f64;
-
-        let stroke_options = renderer::styles::StrokeStyle::new(stroke_width);
-
-        match &self.params.shape {
-            Shape::Circle { x, y, radius } => {
-                let x = x.eval(windows_size, screen_props) as f64;
-                let y = y.eval(windows_size, screen_props) as f64;
-                let radius = radius.eval(windows_size, screen_props) as f64;
-
-                // move by x_origin and y_origin
-                let x = x + x_origin;
-                let y = y + y_origin;
-
-                let shape = renderer::shapes::Shape::circle((x, y), radius);
-
-                frame.scene_mut().draw_shape_fill(shape, fill_brush.clone(), None, None);
-
-                frame
-                    .scene_mut()
-                    .draw_shape_stroke(shape, stroke_brush, stroke_options, None, None);
-            }
-            Shape::Rectangle { x, y, width, height } => {
-                let x = x.eval(windows_size, screen_props) as f64;
-                let y = y.eval(windows_size, screen_props) as f64;
-                let width = width.eval(windows_size, screen_props) as f64;
-                let height = height.eval(windows_size, screen_props) as f64;
+        let stroke_width = stroke_width.eval_extent(x_origin as f32, Axis::X, windows_size, screen_props) as f64;
+
+        // without an explicit stroke style, a solid stroke is drawn
+        let stroke_options = self
+            .params
+            .stroke_style
+            .clone()
+            .unwrap_or(StrokeStyle::Solid)
+            .to_renderer_style(
+                stroke_width,
+                x_origin as f32,
+                self.stroke_cap,
+                self.stroke_join,
+                windows_size,
+                screen_props,
+            );
+
+        let shape = self
+            .params
+            .shape
+            .to_renderer_shape(x_origin, y_origin, windows_size, screen_props);
+        let transform: Affine = self.transform.eval(windows_size, screen_props).into();
+
+        // lines and arcs are open shapes that are only stroked
+        if !matches!(
+            self.params.shape,
+            Shape::Line { .. } | Shape::Polyline { .. } | Shape::Arc { .. }
+        ) {
+            frame
+                .scene_mut()
+                .draw_shape_fill(shape.clone(), fill_brush, self.fill_rule.into(), Some(transform), None);
+        }
 
-                // move by x_origin and y_origin
-                let x = x + x_origin;
-                let y = y + y_origin;
+        if let Some(stroke_options) = stroke_options {
+            frame
+                .scene_mut()
+                .draw_shape_stroke(shape, stroke_brush, stroke_options, Some(transform), None);
+        }
+    }
 
-                let shape = renderer::shapes::Shape::rectangle((x, y), width, height);
+    fn contains(&self, x: Size, y: Size, window: &Window) -> bool {
+        let Some(point) = self.local_point(&x, &y, window) else {
+            return false;
+        };
 
-                frame.scene_mut().draw_shape_fill(shape, fill_brush.clone(), None, None);
+        let (shape, half_stroke) = self.geometry(window);
+        shape.contains(point, self.fill_rule.into(), half_stroke)
+    }
 
-                frame
-                    .scene_mut()
-                    .draw_shape_stroke(shape, stroke_brush, stroke_options, None, None);
-            }
-            Shape::Ellipse {
-                x,
-                y,
-                radius_x,
-                radius_y,
-            } => {
-                todo!("Render ellipse")
-            }
-            Shape::Line { x1, y1, x2, y2 } => {
-                let x1 = x1.eval(windows_size, screen_props) as f64;
-                let y1 = y1.eval(windows_size, screen_props) as f64;
-                let x2 = x2.eval(windows_size, screen_props) as f64;
-                let y2 = y2.eval(windows_size, screen_props) as f64;
-
-                // move by x_origin and y_origin
-                let x1 = x1 + x_origin;
-                let y1 = y1 + y_origin;
-                let x2 = x2 + x_origin;
-                let y2 = y2 + y_origin;
-
-                let shape = renderer::shapes::Shape::line((x1, y1), (x2, y2));
-
-                frame
-                    .scene_mut()
-                    .draw_shape_stroke(shape, stroke_brush, stroke_options, None, None);
-            }
-            Shape::Polygon { points } => {
-                todo!("Render polygon")
-            }
-        };
+    fn local_bounds(&self, window: &Window) -> Rect {
+        let (shape, half_stroke) = self.geometry(window);
+        let (min, max) = shape.bounds();
+        Rect::bounding([(min.x as f32, min.y as f32), (max.x as f32, max.y as f32)]).expanded(half_stroke as f32)
     }
+
     fn set_visible(&mut self, visible: bool) {
         self.visible = visible;
     }
@@ -280,6 +359,14 @@ impl Stimulus for ShapeStimulus {
         self.visible
     }
 
+    fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
     fn set_transformation(&mut self, transformation: crate::visual::geometry::Transformation2D) {
         self.transform = transformation;
     }
@@ -293,6 +380,10 @@ impl Stimulus for ShapeStimulus {
     }
 
     fn set_param(&mut self, name: &str, value: StimulusParamValue) {
+        // a new fill color replaces the gradient
+        if name == "fill_color" {
+            self.fill_gradient = None;
+        }
         self.params.set_param(name, value)
     }
 }
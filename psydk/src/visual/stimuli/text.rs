@@ -2,10 +2,11 @@ use std::sync::{Arc, Mutex};
 
 use super::helpers;
 use super::{
-    animations::Animation, impl_pystimulus_for_wrapper, PyStimulus, Stimulus, StimulusParamValue, StimulusParams,
+    animations::Animation, impl_pystimulus_for_wrapper, BlendMode, PyStimulus, Stimulus, StimulusParamValue,
+    StimulusParams,
 };
 use crate::visual::geometry::Transformation2D;
-use crate::visual::geometry::{Anchor, Size};
+use crate::visual::geometry::{Anchor, Axis, Rect, Size};
 use cosmic_text::Attrs as ComsicAttrs;
 use cosmic_text::Buffer as CosmicBuffer;
 use cosmic_text::Family as CosmicFamily;
@@ -21,7 +22,7 @@ use uuid::Uuid;
 
 use crate::visual::color::IntoLinRgba;
 use crate::visual::color::LinRgba;
-use crate::visual::window::Frame;
+use crate::visual::window::{Frame, PhysicalScreen, PixelSize};
 use renderer::affine::Affine;
 use renderer::brushes::Brush;
 use renderer::colors::RGBA;
@@ -77,6 +78,7 @@ pub struct TextStimulus {
     anchor: Anchor,
     font: renderer::font::DynamicFontFace,
     font_manager: Arc<Mutex<CosmicFontSystem>>,
+    blend_mode: BlendMode,
     transform: Transformation2D,
     animations: Vec<Animation>,
     visible: bool,
@@ -94,6 +96,7 @@ impl TextStimulus {
         font_weight: FontWeight,
         fill_color: LinRgba,
         alpha: f64,
+        blend_mode: BlendMode,
         transform: Transformation2D,
         experiment_manager: &crate::experiment::ExperimentManager,
     ) -> Self {
@@ -146,11 +149,54 @@ impl TextStimulus {
             alignment,
             anchor,
             font_manager: font_manager_clone,
+            blend_mode,
             transform,
             animations: Vec::new(),
             visible: true,
         }
     }
+
+    /// Returns a new buffer with the text laid out for the given window.
+    fn laid_out_buffer(&self, window_size: PixelSize, screen_props: PhysicalScreen) -> CosmicBuffer {
+        let pos_y = self.params.y.eval_position(Axis::Y, window_size, screen_props);
+        let font_size = self
+            .params
+            .font_size
+            .eval_extent(pos_y, Axis::Y, window_size, screen_props);
+        let mut font_manager = self.font_manager.lock().unwrap();
+
+        let mut buffer = CosmicBuffer::new(&mut font_manager, CosmicMetrics::new(font_size, font_size));
+        layout(
+            &mut buffer,
+            &mut font_manager,
+            &self.params.text,
+            (&self.attrs).into(),
+            font_size,
+        );
+        buffer
+    }
+
+    /// Returns the point at which the glyphs of the laid out buffer are drawn,
+    /// i.e., the start of the baseline of the first line.
+    fn text_origin(&self, buffer: &CosmicBuffer, pos_x: f32, pos_y: f32) -> (f32, f32) {
+        // get the width and height of the text
+        let (bb_width, bb_height) = measure(buffer);
+
+        // depending on the achoring, we need to adjust the position
+        let (new_x, new_y) = self.anchor.to_top_left(pos_x, pos_y, bb_width, bb_height / 2.0);
+        (new_x, -new_y)
+    }
+
+    /// Returns the bounds of the laid out buffer in pixels.
+    fn layout_bounds(&self, buffer: &CosmicBuffer, pos_x: f32, pos_y: f32) -> Rect {
+        let (width, height) = measure(buffer);
+        let (origin_x, origin_y) = self.text_origin(buffer, pos_x, pos_y);
+
+        // the first line extends above the baseline by its ascent
+        let ascent = buffer.layout_runs().next().map_or(0.0, |run| run.line_y - run.line_top);
+
+        Rect::new(origin_x, origin_y - ascent, width, height)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -171,6 +217,7 @@ impl PyTextStimulus {
         x = IntoSize(Size::Pixels(0.0)),
         y = IntoSize(Size::Pixels(0.0)),
         fill_color = IntoLinRgba::new(0.0, 0.0, 0.0, 1.0),
+        blend_mode = BlendMode::default(),
         transform = Transformation2D::Identity()
     ))]
     fn __new__(
@@ -185,6 +232,7 @@ impl PyTextStimulus {
         x: IntoSize,
         y: IntoSize,
         fill_color: IntoLinRgba,
+        blend_mode: BlendMode,
         transform: Transformation2D,
     ) -> (Self, PyStimulus) {
         let experiment_manager = helpers::get_experiment_manager(py).unwrap();
@@ -201,6 +249,7 @@ impl PyTextStimulus {
                 font_weight,
                 fill_color.into(),
                 alpha,
+                blend_mode,
                 transform,
                 &experiment_manager,
             )),
@@ -227,39 +276,26 @@ impl Stimulus for TextStimulus {
         let mut font_manager = self.font_manager.lock().unwrap();
 
         // convert physical units to pixels
-        let pos_x = self.params.x.eval(window_size, screen_props) as f64;
-        let pos_y = self.params.y.eval(window_size, screen_props) as f64;
-        let font_size = self.params.font_size.eval(window_size, screen_props) as f64;
+        let pos_x = self.params.x.eval_position(Axis::X, window_size, screen_props) as f64;
+        let pos_y = self.params.y.eval_position(Axis::Y, window_size, screen_props) as f64;
+        let font_size = self
+            .params
+            .font_size
+            .eval_extent(pos_y as f32, Axis::Y, window_size, screen_props) as f64;
 
         let trans_mat = self.transform.eval(window_size, screen_props);
 
-        let fill_color: RGBA = self.params.fill_color.into();
-
-        // Set a size for the text buffer, in pixels
-        self.buffer.set_size(&mut font_manager, None, None);
+        let fill_color: RGBA = frame.resolve_color(self.params.fill_color, &window_state).into();
 
-        self.buffer.set_metrics(
+        layout(
+            &mut self.buffer,
             &mut font_manager,
-            CosmicMetrics::new(font_size as f32, font_size as f32),
+            &self.params.text,
+            (&self.attrs).into(),
+            font_size as f32,
         );
 
-        let attrs = (&self.attrs).into();
-
-        // Add some text!
-        self.buffer
-            .set_text(&mut font_manager, &self.params.text, attrs, cosmic_text::Shaping::Basic);
-
-        // Perform shaping
-        self.buffer.shape_until_scroll(&mut font_manager, true);
-
-        // get the width and height of the text
-        let (bb_width, bb_height) = measure(&self.buffer);
-        // let (bb_width, bb_height) = (bb_width as f64, bb_height as f64);
-
-        // depending on the achoring, we need to adjust the position
-        let (new_x, new_y) = self
-            .anchor
-            .to_top_left(pos_x as f32, pos_y as f32, bb_width, bb_height / 2.0);
+        let (origin_x, origin_y) = self.text_origin(&self.buffer, pos_x as f32, pos_y as f32);
 
         let mut glyphs = vec![];
 
@@ -276,17 +312,37 @@ impl Stimulus for TextStimulus {
         let brush = Brush::Solid(fill_color);
 
         frame.scene_mut().draw_glyphs(
-            (new_x, -new_y).into(),
+            (origin_x, origin_y).into(),
             &glyphs,
             &self.font,
             font_size as f32,
             brush,
             Some(self.params.alpha as f32),
-            None,
+            Some(trans_mat.into()),
             None,
         );
     }
 
+    fn contains(&self, x: Size, y: Size, window: &Window) -> bool {
+        let Some((x, y)) = self.local_point(&x, &y, window) else {
+            return false;
+        };
+
+        self.local_bounds(window).contains_point(x, y)
+    }
+
+    fn local_bounds(&self, window: &Window) -> Rect {
+        let window_state = window.lock_state();
+        let window_size = window_state.size;
+        let screen_props = window_state.physical_screen;
+
+        let pos_x = self.params.x.eval_position(Axis::X, window_size, screen_props);
+        let pos_y = self.params.y.eval_position(Axis::Y, window_size, screen_props);
+
+        let buffer = self.laid_out_buffer(window_size, screen_props);
+        self.layout_bounds(&buffer, pos_x, pos_y)
+    }
+
     fn set_visible(&mut self, visible: bool) {
         self.visible = visible;
     }
@@ -295,6 +351,14 @@ impl Stimulus for TextStimulus {
         self.visible
     }
 
+    fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
     fn animations(&mut self) -> &mut Vec<Animation> {
         &mut self.animations
     }
@@ -368,6 +432,25 @@ impl From<ComsicAttrs<'_>> for OwnedCosmicAttrs {
     }
 }
 
+/// Lays out the text in the buffer with the given font size (in pixels).
+fn layout(
+    buffer: &mut CosmicBuffer,
+    font_manager: &mut CosmicFontSystem,
+    text: &str,
+    attrs: ComsicAttrs,
+    font_size: f32,
+) {
+    // Set a size for the text buffer, in pixels
+    buffer.set_size(font_manager, None, None);
+    buffer.set_metrics(font_manager, CosmicMetrics::new(font_size, font_size));
+
+    // Add some text!
+    buffer.set_text(font_manager, text, attrs, cosmic_text::Shaping::Basic);
+
+    // Perform shaping
+    buffer.shape_until_scroll(font_manager, true);
+}
+
 fn measure(buffer: &CosmicBuffer) -> (f32, f32) {
     buffer.layout_runs().fold((0.0f32, 0.0f32), |size, run| {
         (size.0.max(run.line_w), size.1 + run.line_height)
@@ -1,6 +1,7 @@
 use std::{
     collections::HashMap,
     ops::Deref,
+    path::{Path, PathBuf},
     pin::Pin,
     str::FromStr,
     sync::{
@@ -16,46 +17,61 @@ use futures_lite::{future::block_on, Future};
 use nalgebra;
 use palette::IntoColor;
 use pyo3::prelude::*;
-use renderer::{renderer::RendererFactory, wgpu_renderer::WgpuRenderer, DynamicRenderer, DynamicScene};
+use renderer::{
+    renderer::RendererFactory,
+    scenes::VectorFormat,
+    wgpu_renderer::{DitherPattern, Dithering, GammaCorrection, OutputEncoding, WgpuRenderer},
+    DynamicRenderer, DynamicScene,
+};
 use send_wrapper::SendWrapper;
 use uuid::Uuid;
 use wgpu::TextureFormat;
 use winit::{dpi::PhysicalSize, window::WindowId};
 
 use super::{
+    calibration::MonitorCalibration,
     color::LinRgba,
-    geometry::Size,
-    stimuli::{DynamicStimulus, Stimulus},
+    geometry::{AngleMode, Axis, IntoSize, Size},
+    stimuli::{BlendMode, DynamicStimulus, Stimulus},
 };
 use crate::{
     app::GPUState,
+    errors::psydkError,
     input::{Event, EventHandler, EventHandlerId, EventHandlingExt, EventKind, EventReceiver},
     RenderThreadChannelPayload,
 };
 
 #[derive(Debug, Clone, Copy)]
 pub struct PhysicalScreen {
-    /// Pixel/mm of the screen.
+    /// Horizontal pixels/mm of the screen.
     pub pixel_density: f32,
-    /// Viewing distance in meters.
+    /// Viewing distance in millimeters.
     pub viewing_distance: f32,
+    /// The width of a pixel divided by its height.
+    pub pixel_aspect_ratio: f32,
+    /// How degrees of visual angle are converted to pixels.
+    pub angle_mode: AngleMode,
+    /// The projection of the eye onto the screen in pixels, relative to the
+    /// center of the window. Eccentricities are measured from this point.
+    pub fixation: (f32, f32),
 }
 
 impl PhysicalScreen {
-    /// Creates a new physical screen given width in pixels and millimeters.
+    /// Creates a new physical screen with square pixels given width in pixels and millimeters.
     pub fn new(width_px: u32, width_mm: f32, viewing_distance: f32) -> Self {
         let pixel_density = width_px as f32 / width_mm;
         Self {
             pixel_density,
             viewing_distance,
+            pixel_aspect_ratio: 1.0,
+            angle_mode: AngleMode::Chord,
+            fixation: (0.0, 0.0),
         }
     }
 
     /// Returns the size of the screen in millimeters.
     pub fn size(&self, width_px: u32, height_px: u32) -> (f32, f32) {
-        let width_mm = width_px as f32 / self.pixel_density;
-        let height_mm = height_px as f32 / self.pixel_density;
-        (width_mm, height_mm)
+        (self.width(width_px), self.height(height_px))
     }
 
     /// Returns the width of the screen in millimeters.
@@ -65,13 +81,29 @@ impl PhysicalScreen {
 
     /// Returns the height of the screen in millimeters.
     pub fn height(&self, height_px: u32) -> f32 {
-        height_px as f32 / self.pixel_density
+        height_px as f32 / (self.pixel_density * self.pixel_aspect_ratio)
     }
 
     /// Sets the pixel density of the screen based on the width of the screen in pixels and millimeters.
     pub fn set_pixel_density(&mut self, width_px: u32, width_mm: f32) {
         self.pixel_density = width_px as f32 / width_mm;
     }
+
+    /// Sets the pixel aspect ratio based on the height of the screen in pixels and millimeters. The
+    /// width of the screen is kept.
+    pub fn set_height(&mut self, height_px: u32, height_mm: f32) {
+        self.pixel_aspect_ratio = height_px as f32 / (self.pixel_density * height_mm);
+    }
+
+    /// Returns the fixation point along the given axis and the viewing
+    /// distance, both in pixels of that axis.
+    pub fn eccentricity_frame(&self, axis: Axis) -> (f32, f32) {
+        let distance = self.viewing_distance * self.pixel_density;
+        match axis {
+            Axis::X => (self.fixation.0, distance),
+            Axis::Y => (self.fixation.1, distance * self.pixel_aspect_ratio),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -101,6 +133,15 @@ impl From<PixelSize> for (u32, u32) {
     }
 }
 
+/// Physical properties of the screen need to be positive and finite.
+pub(crate) fn check_positive(name: &str, value: f32) -> Result<(), String> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(format!("The {} must be positive, but got {}.", name, value))
+    }
+}
+
 /// Internal window state. This is used to store the winit window, the wgpu
 /// device, the wgpu queue, etc.
 #[derive(Dbg)]
@@ -124,6 +165,10 @@ pub struct WindowState {
     pub size: PixelSize,
     /// Physical properties of the screen.
     pub physical_screen: PhysicalScreen,
+    /// The name of the monitor the window was created on.
+    pub monitor_name: String,
+    /// The calibration of the monitor, if one has been loaded.
+    pub calibration: Option<MonitorCalibration>,
     /// Event handlers for the window.
     #[dbg(placeholder = "...")]
     pub event_handlers: HashMap<EventHandlerId, (EventKind, EventHandler)>,
@@ -260,6 +305,103 @@ impl Window {
         )
     }
 
+    /// Sets the gamma correction that is applied to every frame before it is
+    /// presented. Returns an error if the window has a floating point color
+    /// format, which does not support gamma correction.
+    pub fn set_gamma_correction(&self, gamma_correction: GammaCorrection) -> Result<(), psydkError> {
+        let gpu_state = self.gpu_state.lock().unwrap();
+        let mut win_state = self.state.lock().unwrap();
+        win_state
+            .wgpu_renderer
+            .set_gamma_correction(&gpu_state.queue, gamma_correction)
+            .map_err(psydkError::CustomError)
+    }
+
+    /// Returns the gamma correction of the window.
+    pub fn gamma_correction(&self) -> GammaCorrection {
+        let win_state = self.state.lock().unwrap();
+        win_state.wgpu_renderer.gamma_correction().clone()
+    }
+
+    /// Sets how the output of the window is encoded into the channels of the
+    /// surface. Returns an error if the surface does not have an 8-bit
+    /// format.
+    pub fn set_output_encoding(&self, output_encoding: OutputEncoding) -> Result<(), psydkError> {
+        let gpu_state = self.gpu_state.lock().unwrap();
+        let mut win_state = self.state.lock().unwrap();
+        win_state
+            .wgpu_renderer
+            .set_output_encoding(&gpu_state.queue, output_encoding)
+            .map_err(psydkError::CustomError)
+    }
+
+    /// Returns the output encoding of the window.
+    pub fn output_encoding(&self) -> OutputEncoding {
+        let win_state = self.state.lock().unwrap();
+        win_state.wgpu_renderer.output_encoding()
+    }
+
+    /// Sets how the output of the window is dithered.
+    pub fn set_dithering(&self, dithering: Dithering) {
+        let gpu_state = self.gpu_state.lock().unwrap();
+        let mut win_state = self.state.lock().unwrap();
+        win_state.wgpu_renderer.set_dithering(&gpu_state.queue, dithering);
+    }
+
+    /// Returns the dithering of the window.
+    pub fn dithering(&self) -> Dithering {
+        let win_state = self.state.lock().unwrap();
+        win_state.wgpu_renderer.dithering()
+    }
+
+    /// Applies the given monitor calibration to the window.
+    pub fn set_calibration(&self, calibration: MonitorCalibration) -> Result<(), psydkError> {
+        let gamma_correction = calibration.gamma_correction()?;
+        self.set_gamma_correction(gamma_correction)?;
+        self.state.lock().unwrap().calibration = Some(calibration);
+        Ok(())
+    }
+
+    /// Loads a monitor calibration from the given file, or searches the
+    /// calibration directory for a calibration of the window's monitor if no
+    /// path is given, and applies it to the window.
+    pub fn load_calibration(&self, path: Option<&Path>) -> Result<(), psydkError> {
+        let calibration = match path {
+            Some(path) => MonitorCalibration::load(path)?,
+            None => {
+                let monitor_name = self.state.lock().unwrap().monitor_name.clone();
+                MonitorCalibration::find(&monitor_name).ok_or_else(|| {
+                    psydkError::CalibrationError(format!("No calibration found for monitor {}.", monitor_name))
+                })?
+            }
+        };
+        self.set_calibration(calibration)
+    }
+
+    /// Returns the calibration of the window's monitor, if one has been loaded.
+    pub fn calibration(&self) -> Option<MonitorCalibration> {
+        self.state.lock().unwrap().calibration.clone()
+    }
+
+    /// Returns the physical properties of the window's screen.
+    pub fn physical_screen(&self) -> PhysicalScreen {
+        self.state.lock().unwrap().physical_screen
+    }
+
+    /// Sets the physical properties of the window's screen. Sizes are
+    /// evaluated with the new properties from the next frame on.
+    pub fn set_physical_screen(&self, physical_screen: PhysicalScreen) {
+        self.state.lock().unwrap().physical_screen = physical_screen;
+    }
+
+    /// Changes the physical properties of the window's screen with the given
+    /// function, which also receives the size of the window in pixels.
+    fn update_physical_screen(&self, f: impl FnOnce(&mut PhysicalScreen, PixelSize)) {
+        let mut win_state = self.state.lock().unwrap();
+        let size = win_state.size;
+        f(&mut win_state.physical_screen, size);
+    }
+
     /// Returns the size of the window in pixels.
     pub fn size(&self) -> PixelSize {
         let win_state = &self.state.lock().unwrap();
@@ -276,6 +418,7 @@ impl Window {
             bg_color: LinRgba::new(0.0, 0.0, 0.0, 1.0),
             scene,
             window: self.clone(),
+            draw_errors: Vec::new(),
         }
     }
     fn remove_event_handler(&self, id: EventHandlerId) {
@@ -374,6 +517,309 @@ impl Window {
         self.size().into()
     }
 
+    /// Set the gamma correction of the window. The correction is applied to
+    /// every frame right before it is presented. Windows with a floating point
+    /// color format expect linear values, so only "none" can be set for them.
+    ///
+    /// Parameters
+    /// ----------
+    /// model : str
+    ///   The correction model. One of "none", "gamma", "psychopy", "polylog4",
+    ///   "polylog5", "polylog6" or "lut".
+    /// r : list of float, optional
+    ///   The coefficients of the red channel: [gamma] for "gamma", [a, k, gamma]
+    ///   for "psychopy", 5, 6 or 7 polynomial coefficients for the polylog models
+    ///   and a lookup table of up to 4096 entries for "lut". The entries of a
+    ///   lookup table are the output values for evenly spaced linear values
+    ///   from 0 to 1.
+    /// g : list of float, optional
+    ///   The coefficients of the green channel.
+    /// b : list of float, optional
+    ///   The coefficients of the blue channel.
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the model or the coefficients are invalid, or if the window has a
+    ///   floating point color format.
+    #[pyo3(name = "set_gamma_correction", signature = (model, r = None, g = None, b = None))]
+    fn py_set_gamma_correction(
+        &self,
+        model: &str,
+        r: Option<Vec<f32>>,
+        g: Option<Vec<f32>>,
+        b: Option<Vec<f32>>,
+        py: Python,
+    ) -> PyResult<()> {
+        let gamma_correction = GammaCorrection::from_coefficients(
+            &model.to_lowercase(),
+            &r.unwrap_or_default(),
+            &g.unwrap_or_default(),
+            &b.unwrap_or_default(),
+        )
+        .map_err(pyo3::exceptions::PyValueError::new_err)?;
+
+        let self_wrapper = SendWrapper::new(self.clone());
+        py.allow_threads(move || self_wrapper.set_gamma_correction(gamma_correction))
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Set how the output of the window is encoded. The encodings allow more
+    /// than 256 grey levels on 8-bit displays and are applied after the gamma
+    /// correction. They are only available for windows with an 8-bit color
+    /// format.
+    ///
+    /// Parameters
+    /// ----------
+    /// encoding : str
+    ///   One of "none", "pseudo_gray" (bit-stealing), "mono++" (the red channel
+    ///   with 16 bits in red and green) or "color++" (16 bits per channel at
+    ///   half the horizontal resolution).
+    ///
+    /// Raises
+    /// ------
+    /// ValueError
+    ///   If the encoding is unknown, or if the window does not have an 8-bit
+    ///   color format.
+    #[pyo3(name = "set_output_encoding")]
+    fn py_set_output_encoding(&self, encoding: &str, py: Python) -> PyResult<()> {
+        let output_encoding =
+            OutputEncoding::from_name(&encoding.to_lowercase()).map_err(pyo3::exceptions::PyValueError::new_err)?;
+
+        let self_wrapper = SendWrapper::new(self.clone());
+        py.allow_threads(move || self_wrapper.set_output_encoding(output_encoding))
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Returns the current output encoding of the window.
+    ///
+    /// Returns
+    /// -------
+    /// str
+    ///   The name of the encoding.
+    #[pyo3(name = "get_output_encoding")]
+    fn py_get_output_encoding(&self) -> String {
+        self.output_encoding().name().to_string()
+    }
+
+    /// Dither the output of the window. Instead of rounding each pixel to the
+    /// closest level of the display, pixels are rounded up or down depending
+    /// on a threshold pattern, which reproduces intermediate levels on average
+    /// and avoids banding of low contrast stimuli. Dithering is applied after
+    /// the gamma correction and has no effect with an output encoding or on
+    /// windows with a floating point color format.
+    ///
+    /// Parameters
+    /// ----------
+    /// pattern : str, optional
+    ///   One of "none", "bayer" (an 8x8 ordered pattern) or "blue_noise" (a
+    ///   pattern without visible structure). Default is "bayer".
+    /// temporal : bool, optional
+    ///   If True, the thresholds change with every frame, so that intermediate
+    ///   levels are also reproduced over time. Can be combined with pattern
+    ///   "none" for purely temporal dithering. Default is False.
+    /// seed : int, optional
+    ///   Shifts the pattern. The same seed always gives the same pattern.
+    ///   Default is 0.
+    #[pyo3(name = "set_dithering", signature = (pattern = "bayer", temporal = false, seed = 0))]
+    fn py_set_dithering(&self, pattern: &str, temporal: bool, seed: u32, py: Python) -> PyResult<()> {
+        let pattern =
+            DitherPattern::from_name(&pattern.to_lowercase()).map_err(pyo3::exceptions::PyValueError::new_err)?;
+        let dithering = Dithering {
+            pattern,
+            temporal,
+            seed,
+        };
+
+        let self_wrapper = SendWrapper::new(self.clone());
+        py.allow_threads(move || self_wrapper.set_dithering(dithering));
+        Ok(())
+    }
+
+    /// Returns the current dithering of the window.
+    ///
+    /// Returns
+    /// -------
+    /// tuple[str, bool, int]
+    ///   The name of the pattern, whether temporal dithering is enabled and
+    ///   the seed.
+    #[pyo3(name = "get_dithering")]
+    fn py_get_dithering(&self) -> (String, bool, u32) {
+        let dithering = self.dithering();
+        (dithering.pattern.name().to_string(), dithering.temporal, dithering.seed)
+    }
+
+    /// Load a monitor calibration and apply it to the window. Calibration files
+    /// can be created with `psydk.visual.fit_calibration()`.
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str, optional
+    ///   The path of the calibration file (JSON or TOML). If not provided, the
+    ///   calibration directory (`~/.psydk/calibrations` or
+    ///   `$PSYDK_CALIBRATION_DIR`) is searched for a calibration of the
+    ///   window's monitor.
+    #[pyo3(name = "load_calibration", signature = (path = None))]
+    fn py_load_calibration(&self, path: Option<PathBuf>, py: Python) -> PyResult<()> {
+        let self_wrapper = SendWrapper::new(self.clone());
+        py.allow_threads(move || self_wrapper.load_calibration(path.as_deref()))?;
+        Ok(())
+    }
+
+    /// Returns the physical width of the window in millimeters.
+    #[pyo3(name = "get_physical_width")]
+    fn py_get_physical_width(&self) -> f32 {
+        let win_state = self.state.lock().unwrap();
+        win_state.physical_screen.width(win_state.size.width)
+    }
+
+    /// Set the physical width of the window. This is needed to convert
+    /// physical units (e.g., "cm" or "deg") to pixels. The pixel aspect ratio
+    /// is kept, so the physical height changes proportionally.
+    ///
+    /// Parameters
+    /// ----------
+    /// width : float
+    ///   The width of the visible area of the window in millimeters.
+    #[pyo3(name = "set_physical_width")]
+    fn py_set_physical_width(&self, width: f32) -> PyResult<()> {
+        check_positive("width", width).map_err(pyo3::exceptions::PyValueError::new_err)?;
+        self.update_physical_screen(|screen, size| screen.set_pixel_density(size.width, width));
+        Ok(())
+    }
+
+    /// Returns the physical height of the window in millimeters.
+    #[pyo3(name = "get_physical_height")]
+    fn py_get_physical_height(&self) -> f32 {
+        let win_state = self.state.lock().unwrap();
+        win_state.physical_screen.height(win_state.size.height)
+    }
+
+    /// Set the physical height of the window. The physical width is kept, so
+    /// this changes the pixel aspect ratio.
+    ///
+    /// Parameters
+    /// ----------
+    /// height : float
+    ///   The height of the visible area of the window in millimeters.
+    #[pyo3(name = "set_physical_height")]
+    fn py_set_physical_height(&self, height: f32) -> PyResult<()> {
+        check_positive("height", height).map_err(pyo3::exceptions::PyValueError::new_err)?;
+        self.update_physical_screen(|screen, size| screen.set_height(size.height, height));
+        Ok(())
+    }
+
+    /// Returns the viewing distance in millimeters.
+    #[pyo3(name = "get_viewing_distance")]
+    fn py_get_viewing_distance(&self) -> f32 {
+        self.physical_screen().viewing_distance
+    }
+
+    /// Set the distance between the observer's eyes and the screen, which is
+    /// needed to convert degrees of visual angle to pixels.
+    ///
+    /// Parameters
+    /// ----------
+    /// distance : float
+    ///   The viewing distance in millimeters.
+    #[pyo3(name = "set_viewing_distance")]
+    fn py_set_viewing_distance(&self, distance: f32) -> PyResult<()> {
+        check_positive("viewing distance", distance).map_err(pyo3::exceptions::PyValueError::new_err)?;
+        self.update_physical_screen(|screen, _| screen.viewing_distance = distance);
+        Ok(())
+    }
+
+    /// Returns the pixel aspect ratio, i.e., the width of a pixel divided by
+    /// its height.
+    #[pyo3(name = "get_pixel_aspect_ratio")]
+    fn py_get_pixel_aspect_ratio(&self) -> f32 {
+        self.physical_screen().pixel_aspect_ratio
+    }
+
+    /// Set the pixel aspect ratio, i.e., the width of a pixel divided by its
+    /// height. The physical width is kept, so this changes the physical
+    /// height.
+    ///
+    /// Parameters
+    /// ----------
+    /// ratio : float
+    ///   The pixel aspect ratio (1.0 for square pixels).
+    #[pyo3(name = "set_pixel_aspect_ratio")]
+    fn py_set_pixel_aspect_ratio(&self, ratio: f32) -> PyResult<()> {
+        check_positive("pixel aspect ratio", ratio).map_err(pyo3::exceptions::PyValueError::new_err)?;
+        self.update_physical_screen(|screen, _| screen.pixel_aspect_ratio = ratio);
+        Ok(())
+    }
+
+    /// Returns how degrees of visual angle are converted to pixels, either
+    /// "chord" or "eccentricity".
+    #[pyo3(name = "get_angle_mode")]
+    fn py_get_angle_mode(&self) -> &'static str {
+        self.physical_screen().angle_mode.name()
+    }
+
+    /// Set how degrees of visual angle are converted to pixels. In "chord"
+    /// mode (the default), every angle is converted as if it was centred on
+    /// the line of sight. In "eccentricity" mode, positions in degrees are
+    /// eccentricities relative to the fixation point (see `set_fixation`) and
+    /// extents in degrees are measured between the eccentricities of their
+    /// edges, which is exact on flat screens.
+    ///
+    /// Parameters
+    /// ----------
+    /// mode : str
+    ///   Either "chord" or "eccentricity".
+    #[pyo3(name = "set_angle_mode")]
+    fn py_set_angle_mode(&self, mode: AngleMode) {
+        self.update_physical_screen(|screen, _| screen.angle_mode = mode);
+    }
+
+    /// Returns the fixation point in pixels, relative to the center of the
+    /// window.
+    #[pyo3(name = "get_fixation")]
+    fn py_get_fixation(&self) -> (f32, f32) {
+        self.physical_screen().fixation
+    }
+
+    /// Set the fixation point, i.e., the projection of the observer's eye
+    /// onto the screen. In "eccentricity" mode, positions in degrees are
+    /// measured from this point.
+    ///
+    /// Parameters
+    /// ----------
+    /// x : Size
+    ///   The horizontal position of the fixation point.
+    /// y : Size
+    ///   The vertical position of the fixation point. Positions in degrees
+    ///   are measured from the center of the window.
+    #[pyo3(name = "set_fixation")]
+    fn py_set_fixation(&self, x: IntoSize, y: IntoSize) {
+        self.update_physical_screen(|screen, size| {
+            // the new fixation point does not depend on the current one
+            let centered = PhysicalScreen {
+                fixation: (0.0, 0.0),
+                ..*screen
+            };
+            screen.fixation = (
+                x.0.eval_position(Axis::X, size, centered),
+                y.0.eval_position(Axis::Y, size, centered),
+            );
+        });
+    }
+
+    /// Returns the current gamma correction of the window.
+    ///
+    /// Returns
+    /// -------
+    /// tuple of (str, list of float, list of float, list of float)
+    ///   The model and the coefficients of the red, green and blue channel.
+    #[pyo3(name = "get_gamma_correction")]
+    fn py_get_gamma_correction(&self) -> (String, Vec<f32>, Vec<f32>, Vec<f32>) {
+        let gamma_correction = self.gamma_correction();
+        let [r, g, b] = gamma_correction.coefficients();
+        (gamma_correction.model().to_string(), r, g, b)
+    }
+
     /// Add an event handler to the window. The event handler will be called
     /// whenever an event of the specified kind occurs.
     ///
@@ -438,6 +884,8 @@ pub struct Frame {
     scene: DynamicScene,
     /// The window that the frame is associated with.
     window: Window,
+    /// Errors that occurred while drawing the current stimulus.
+    draw_errors: Vec<psydkError>,
 }
 
 impl Frame {
@@ -446,8 +894,9 @@ impl Frame {
         self.bg_color = bg_color;
     }
 
-    /// Draw onto the frame.
-    pub fn draw(&mut self, stimulus: &DynamicStimulus) {
+    /// Draw onto the frame. Returns the first error that occurred while
+    /// drawing the stimulus, e.g., a color that could not be resolved.
+    pub fn draw(&mut self, stimulus: &DynamicStimulus) -> Result<(), psydkError> {
         let mut stimulus = stimulus.lock();
 
         let now = Instant::now();
@@ -458,7 +907,45 @@ impl Frame {
             stimulus.update_animations(now, &window_state);
         }
 
+        self.draw_stimulus(&mut *stimulus);
+
+        match self.draw_errors.drain(..).next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Draws a stimulus without updating its animations. A stimulus with a
+    /// blend mode other than the default is drawn into a separate layer that
+    /// is then blended as a whole, so that, e.g., the stroke of a shape is not
+    /// blended with its own fill.
+    pub fn draw_stimulus(&mut self, stimulus: &mut dyn Stimulus) {
+        let blend_mode = stimulus.blend_mode();
+        if blend_mode == BlendMode::SourceOver {
+            stimulus.draw(self);
+            return;
+        }
+
+        let size = self.window.size();
+        let (width, height) = (size.width as f64, size.height as f64);
+        let bounds = renderer::shapes::Shape::rectangle((-width / 2.0, -height / 2.0), width, height);
+
+        self.scene.start_layer(blend_mode.into(), bounds, None, None, 1.0);
         stimulus.draw(self);
+        self.scene.end_layer();
+    }
+
+    /// Resolves a color for the window of this frame (see `LinRgba::resolve`).
+    /// If the color can not be resolved, the error is returned by
+    /// `Frame::draw` and the color is drawn transparent.
+    pub fn resolve_color(&mut self, color: LinRgba, window_state: &WindowState) -> LinRgba {
+        match color.resolve(window_state.calibration.as_ref()) {
+            Ok(color) => color,
+            Err(error) => {
+                self.draw_errors.push(error);
+                LinRgba::new(0.0, 0.0, 0.0, 0.0)
+            }
+        }
     }
 
     pub fn window(&self) -> Window {
@@ -472,15 +959,50 @@ impl Frame {
     pub fn scene_mut(&mut self) -> &mut DynamicScene {
         &mut self.scene
     }
+
+    /// Export the frame as a vector graphic. The document has the same pixel
+    /// geometry as the window and the physical size of the screen. If no
+    /// format is given, it is inferred from the file extension.
+    pub fn export(&mut self, path: &Path, format: Option<VectorFormat>) -> Result<(), psydkError> {
+        let format = match format {
+            Some(format) => format,
+            None => match path.extension().and_then(|ext| ext.to_str()) {
+                Some(ext) if ext.eq_ignore_ascii_case("svg") => VectorFormat::Svg,
+                Some(ext) if ext.eq_ignore_ascii_case("pdf") => VectorFormat::Pdf,
+                _ => {
+                    return Err(psydkError::CustomError(format!(
+                        "Cannot infer the export format from {:?}. Use a .svg or .pdf extension or pass the format explicitly.",
+                        path
+                    )))
+                }
+            },
+        };
+
+        let physical_size = {
+            let window_state = self.window.state.lock().unwrap();
+            window_state
+                .physical_screen
+                .size(window_state.size.width, window_state.size.height)
+        };
+
+        let data = self
+            .scene
+            .export_vector(format, Some(physical_size))
+            .map_err(|e| psydkError::CustomError(e.to_string()))?;
+        std::fs::write(path, data)?;
+
+        Ok(())
+    }
 }
 
 #[pymethods]
 impl Frame {
     #[pyo3(name = "draw")]
-    fn py_draw(&mut self, stimulus: crate::visual::stimuli::PyStimulus, py: Python) {
+    fn py_draw(&mut self, stimulus: crate::visual::stimuli::PyStimulus, py: Python) -> PyResult<()> {
         let mut self_wrapper = SendWrapper::new(self);
         let stimulus_wrapper = SendWrapper::new(stimulus);
-        py.allow_threads(move || self_wrapper.draw(stimulus_wrapper.as_super()));
+        py.allow_threads(move || self_wrapper.draw(stimulus_wrapper.as_super()))
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
     }
 
     #[getter(bg_color)]
@@ -492,4 +1014,33 @@ impl Frame {
     fn py_set_bg_color(&mut self, bg_color: super::color::LinRgba) {
         self.bg_color = bg_color;
     }
+
+    /// Export everything drawn onto the frame so far as a vector graphic (SVG or
+    /// PDF). The exported document uses the same pixel coordinates as the window
+    /// and has the physical size of the screen, so figures match what was shown.
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str
+    ///   The file to write to.
+    /// format : str, optional
+    ///   Either "svg" or "pdf". If omitted, the format is inferred from the file extension.
+    #[pyo3(name = "export", signature = (path, format = None))]
+    fn py_export(&mut self, path: PathBuf, format: Option<String>, py: Python) -> PyResult<()> {
+        let format = match format.as_deref().map(str::to_lowercase).as_deref() {
+            Some("svg") => Some(VectorFormat::Svg),
+            Some("pdf") => Some(VectorFormat::Pdf),
+            Some(other) => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown export format: {}. Expected \"svg\" or \"pdf\".",
+                    other
+                )))
+            }
+            None => None,
+        };
+
+        let mut self_wrapper = SendWrapper::new(self);
+        py.allow_threads(move || self_wrapper.export(&path, format))?;
+        Ok(())
+    }
 }
@@ -2,7 +2,15 @@ struct Params {
     r: P,
     g: P,
     b: P,
-    correction: u32, // 0: none, 1: psychopy, 2: polylog4, 3: polylog5, 4: polylog6
+    correction: u32, // 0: none, 1: psychopy, 2: polylog4, 3: polylog5, 4: polylog6, 5: gamma, 6: lut
+    lut_size: u32,
+    encoding: u32, // 0: none, 1: pseudo-gray, 2: mono++, 3: color++
+    dither: u32, // 0: none, 1: bayer, 2: blue noise
+    seed: u32,
+    frame: u32,
+    levels: f32, // the highest value of the surface's integer format
+    temporal: u32,
+    color_space: u32, // 0: sRGB, 1: Display P3
 };
 
 struct P {
@@ -20,12 +28,14 @@ fn npow(x: f32, n: f32) -> f32 {
     return sign(x) * pow(abs(x), n);
 }
 
+// a: gamma
 fn pure_gamma_inv_eotf(value: f32, params: P) -> f32 {
-    return npow(value, params.c);
+    return npow(value, 1.0 / params.a);
 }
 
+// a: a, b: k, c: gamma
 fn psychopy_scaled_inv_eotf(value: f32, params: P) -> f32 {
-    return (npow(( (1.0 - value) * npow(params.a, params.c) + value * npow((params.a + params[2]), params.c)), (1/params.c)) - params.a) / params[2];
+    return (npow(( (1.0 - value) * npow(params.a, params.c) + value * npow((params.a + params.b), params.c)), (1.0 / params.c)) - params.a) / params.b;
 }
 
 fn polylog4(x: f32, params: P) -> f32 {
@@ -56,6 +66,17 @@ fn polylog6(x: f32, params: P) -> f32 {
     return params.a + params.b * logx + params.c * npow(logx, 2.0) + params.d * npow(logx, 3.0) + params.e * npow(logx, 4.0) + params.f * npow(logx, 5.0) + params.g * npow(logx, 6.0);
 }
 
+// linearly interpolates between the two closest entries of the lookup table
+fn lut_inv_eotf(value: f32, channel: u32) -> f32 {
+    let last = params.lut_size - 1u;
+    let position = clamp(value, 0.0, 1.0) * f32(last);
+    let i = min(u32(floor(position)), last);
+    let j = min(i + 1u, last);
+    let lower = textureLoad(lut, i, 0)[channel];
+    let upper = textureLoad(lut, j, 0)[channel];
+    return mix(lower, upper, fract(position));
+}
+
 @vertex
 fn vs_main(@builtin(vertex_index) ix: u32) -> @builtin(position) vec4<f32> {
     // Generate a full screen quad in normalized device coordinates
@@ -83,19 +104,32 @@ var fine_output: texture_2d<f32>;
 @group(0) @binding(1)
 var<uniform> params: Params;
 
+// bind the lookup table to the shader
+@group(0) @binding(2)
+var lut: texture_1d<f32>;
 
-@fragment
-fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
-    let rgba_sep = textureLoad(fine_output, vec2<i32>(pos.xy), 0);
-    let rgb_pm = vec3(rgba_sep.rgb * rgba_sep.a);
+// bind the blue noise threshold map to the shader
+@group(0) @binding(3)
+var blue_noise: texture_2d<f32>;
 
+
+// converts linear sRGB to linear Display P3 (both have a D65 white point),
+// the columns of the matrix are the sRGB primaries in Display P3
+const SRGB_TO_DISPLAY_P3 = mat3x3<f32>(
+    vec3(0.8224621, 0.0331941, 0.0170827),
+    vec3(0.1775380, 0.9668058, 0.0723974),
+    vec3(0.0, 0.0, 0.9105199),
+);
+
+// applies the gamma correction selected in params.correction
+fn gamma_correct(rgb_pm: vec3<f32>) -> vec3<f32> {
     if params.correction == 0 {
         let rgb = vec3(
             rgb_pm.r,
             rgb_pm.g,
             rgb_pm.b
         );
-        return vec4(rgb, rgba_sep.a);
+        return rgb;
     }
     else if params.correction == 1 {
         let rgb = vec3(
@@ -103,33 +137,169 @@ fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
             psychopy_scaled_inv_eotf(rgb_pm.g, params.g),
             psychopy_scaled_inv_eotf(rgb_pm.b, params.b)
         );
-        return vec4(rgb, rgba_sep.a);
+        return rgb;
     }
     else if params.correction == 2 {
         let rgb = vec3(
-            polylog4(rgb_pm.r, params.b),
+            polylog4(rgb_pm.r, params.r),
             polylog4(rgb_pm.g, params.g),
             polylog4(rgb_pm.b, params.b)
         );
-        return vec4(rgb, rgba_sep.a);
+        return rgb;
     }
     else if params.correction == 3 {
         let rgb = vec3(
-            polylog5_horner(rgb_pm.r, params.b),
+            polylog5_horner(rgb_pm.r, params.r),
             polylog5_horner(rgb_pm.g, params.g),
             polylog5_horner(rgb_pm.b, params.b)
         );
-        return vec4(rgb, rgba_sep.a);
+        return rgb;
     }
     else if params.correction == 4 {
         let rgb = vec3(
-            polylog6(rgb_pm.r, params.b),
+            polylog6(rgb_pm.r, params.r),
             polylog6(rgb_pm.g, params.g),
             polylog6(rgb_pm.b, params.b)
         );
-        return vec4(rgb, rgba_sep.a);
+        return rgb;
+    }
+    else if params.correction == 5 {
+        let rgb = vec3(
+            pure_gamma_inv_eotf(rgb_pm.r, params.r),
+            pure_gamma_inv_eotf(rgb_pm.g, params.g),
+            pure_gamma_inv_eotf(rgb_pm.b, params.b)
+        );
+        return rgb;
+    }
+    else if params.correction == 6 {
+        let rgb = vec3(
+            lut_inv_eotf(rgb_pm.r, 0u),
+            lut_inv_eotf(rgb_pm.g, 1u),
+            lut_inv_eotf(rgb_pm.b, 2u)
+        );
+        return rgb;
+    }
+
+
+    return rgb_pm;
+}
+
+// the luminance contribution of the red, green and blue channel
+const LUMINANCE_WEIGHTS = vec3(0.2126, 0.7152, 0.0722);
+
+// bit-stealing (Tyler, 1997): the fractional part of each channel is
+// approximated by incrementing a combination of channels by one level of the
+// surface
+fn encode_pseudo_gray(rgb: vec3<f32>) -> vec3<f32> {
+    let scaled = clamp(rgb, vec3(0.0), vec3(1.0)) * params.levels;
+    let base = floor(scaled);
+    let wanted = dot(scaled - base, LUMINANCE_WEIGHTS);
+
+    var best = vec3(0.0);
+    var best_error = wanted;
+    for (var i = 1u; i < 8u; i++) {
+        let increment = vec3(f32(i & 1u), f32((i >> 1u) & 1u), f32((i >> 2u) & 1u));
+        let error = abs(dot(increment, LUMINANCE_WEIGHTS) - wanted);
+        if error < best_error {
+            best = increment;
+            best_error = error;
+        }
+    }
+
+    return min(base + best, vec3(params.levels)) / params.levels;
+}
+
+// mono++: the red channel is encoded with 16 bits, the high byte in red and
+// the low byte in green
+fn encode_mono_plus_plus(rgb: vec3<f32>) -> vec3<f32> {
+    let value = u32(round(clamp(rgb.r, 0.0, 1.0) * 65535.0));
+    return vec3(f32(value >> 8u), f32(value & 255u), 0.0) / 255.0;
+}
+
+// color++: each pair of horizontally adjacent pixels encodes one pixel with 16
+// bits per channel, the high bytes in the left and the low bytes in the right
+fn encode_color_plus_plus(rgb: vec3<f32>, x: i32) -> vec3<f32> {
+    let value = vec3<u32>(round(clamp(rgb, vec3(0.0), vec3(1.0)) * 65535.0));
+    if x % 2 == 0 {
+        return vec3<f32>(value >> vec3(8u)) / 255.0;
     }
+    return vec3<f32>(value & vec3(255u)) / 255.0;
+}
+
+// integer hash (lowbias32 by Chris Wellons)
+fn hash(value: u32) -> u32 {
+    var h = value;
+    h ^= h >> 16u;
+    h *= 0x7feb352du;
+    h ^= h >> 15u;
+    h *= 0x846ca68bu;
+    h ^= h >> 16u;
+    return h;
+}
+
+// the threshold of the 8x8 Bayer matrix, computed by interleaving the bits of
+// x ^ y and x in reverse order
+fn bayer_threshold(pixel: vec2<u32>) -> f32 {
+    let x = pixel.x & 7u;
+    let xy = x ^ (pixel.y & 7u);
+    let rank = ((xy & 1u) << 5u) | ((x & 1u) << 4u) | ((xy & 2u) << 2u) | ((x & 2u) << 1u) | ((xy & 4u) >> 1u) | ((x & 4u) >> 2u);
+    return (f32(rank) + 0.5) / 64.0;
+}
+
+// rounds each channel up or down to a level of the surface, depending on the
+// threshold of the pattern at the pixel
+fn dither(rgb: vec3<f32>, pixel: vec2<u32>) -> vec3<f32> {
+    // the seed shifts the pattern
+    let shifted = pixel + vec2(hash(params.seed), hash(params.seed ^ 0x9e3779b9u));
+
+    var threshold = 0.5;
+    if params.dither == 1u {
+        threshold = bayer_threshold(shifted);
+    }
+    else if params.dither == 2u {
+        let size = textureDimensions(blue_noise);
+        threshold = textureLoad(blue_noise, shifted % size, 0).r;
+    }
+
+    // advance the thresholds by the golden ratio with every frame, which
+    // spreads them evenly over time (computed in 32-bit fixed point)
+    if params.temporal != 0u {
+        threshold = fract(threshold + f32(params.frame * 0x9e3779b9u) / 4294967296.0);
+    }
+
+    return floor(clamp(rgb, vec3(0.0), vec3(1.0)) * params.levels + threshold) / params.levels;
+}
 
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    let x = i32(pos.x);
+    var coords = vec2<i32>(pos.xy);
+
+    // with color++, the left pixel of each pair is shown at half resolution
+    if params.encoding == 3u {
+        coords.x = x - x % 2;
+    }
 
-    return vec4(rgb_pm, rgba_sep.a);
-}
\ No newline at end of file
+    let rgba_sep = textureLoad(fine_output, coords, 0);
+    var rgb_linear = rgba_sep.rgb * rgba_sep.a;
+    if params.color_space == 1u {
+        rgb_linear = SRGB_TO_DISPLAY_P3 * rgb_linear;
+    }
+    var rgb = gamma_correct(rgb_linear);
+
+    if params.encoding == 1u {
+        return vec4(encode_pseudo_gray(rgb), rgba_sep.a);
+    }
+    else if params.encoding == 2u {
+        return vec4(encode_mono_plus_plus(rgb), 1.0);
+    }
+    else if params.encoding == 3u {
+        return vec4(encode_color_plus_plus(rgb, x), 1.0);
+    }
+
+    if params.dither != 0u || params.temporal != 0u {
+        rgb = dither(rgb, vec2<u32>(pos.xy));
+    }
+
+    return vec4(rgb, rgba_sep.a);
+}
@@ -0,0 +1,138 @@
+// Generates a tileable blue noise threshold map with the void-and-cluster
+// algorithm (Ulichney, 1993).
+
+/// The standard deviation of the Gaussian filter that is used to find clusters
+/// and voids (in pixels).
+const SIGMA: f32 = 1.5;
+
+/// The fraction of pixels that are set in the initial binary pattern.
+const INITIAL_DENSITY: f32 = 0.1;
+
+/// The energy of each pixel, i.e., the sum of the (toroidally wrapped) Gaussian
+/// filter around every set pixel.
+struct Energy {
+    size: usize,
+    kernel: Vec<f32>,
+    values: Vec<f32>,
+}
+
+impl Energy {
+    fn new(size: usize) -> Self {
+        let kernel = (0..size * size)
+            .map(|i| {
+                // the distance to the closest copy of the origin
+                let wrap = |d: usize| d.min(size - d) as f32;
+                let (dx, dy) = (wrap(i % size), wrap(i / size));
+                (-(dx * dx + dy * dy) / (2.0 * SIGMA * SIGMA)).exp()
+            })
+            .collect();
+
+        Self {
+            size,
+            kernel,
+            values: vec![0.0; size * size],
+        }
+    }
+
+    /// Adds (sign = 1) or removes (sign = -1) the filter around the pixel.
+    fn update(&mut self, pixel: usize, sign: f32) {
+        let (px, py) = (pixel % self.size, pixel / self.size);
+        for (i, value) in self.values.iter_mut().enumerate() {
+            let dx = (i % self.size + self.size - px) % self.size;
+            let dy = (i / self.size + self.size - py) % self.size;
+            *value += sign * self.kernel[dy * self.size + dx];
+        }
+    }
+
+    /// The set pixel with the highest energy.
+    fn tightest_cluster(&self, pattern: &[bool]) -> usize {
+        self.extreme(pattern, true, |a, b| a > b)
+    }
+
+    /// The unset pixel with the lowest energy.
+    fn largest_void(&self, pattern: &[bool]) -> usize {
+        self.extreme(pattern, false, |a, b| a < b)
+    }
+
+    fn extreme(&self, pattern: &[bool], state: bool, better: impl Fn(f32, f32) -> bool) -> usize {
+        let mut best = None;
+        for (i, &value) in self.values.iter().enumerate() {
+            if pattern[i] == state && best.map_or(true, |b: usize| better(value, self.values[b])) {
+                best = Some(i);
+            }
+        }
+        best.expect("the pattern is neither empty nor full")
+    }
+}
+
+/// A xorshift generator, which is good enough to seed the initial pattern.
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// Returns a `size` x `size` threshold map (row-major) with values evenly
+/// distributed between 0 and 1. The map tiles seamlessly.
+pub fn blue_noise(size: usize, seed: u64) -> Vec<f32> {
+    let n = size * size;
+    let mut state = seed.max(1);
+
+    // start with a random binary pattern
+    let mut prototype = vec![false; n];
+    let mut energy = Energy::new(size);
+    let ones = ((n as f32 * INITIAL_DENSITY) as usize).max(1);
+    let mut placed = 0;
+    while placed < ones {
+        let pixel = (xorshift(&mut state) % n as u64) as usize;
+        if !prototype[pixel] {
+            prototype[pixel] = true;
+            energy.update(pixel, 1.0);
+            placed += 1;
+        }
+    }
+
+    // move pixels from the tightest cluster to the largest void until the
+    // pattern is evenly distributed
+    loop {
+        let cluster = energy.tightest_cluster(&prototype);
+        prototype[cluster] = false;
+        energy.update(cluster, -1.0);
+
+        let void = energy.largest_void(&prototype);
+        prototype[void] = true;
+        energy.update(void, 1.0);
+
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0; n];
+
+    // rank the pixels of the prototype by removing the tightest clusters
+    let mut pattern = prototype.clone();
+    let mut cluster_energy = Energy {
+        size,
+        kernel: energy.kernel.clone(),
+        values: energy.values.clone(),
+    };
+    for rank in (0..ones).rev() {
+        let cluster = cluster_energy.tightest_cluster(&pattern);
+        pattern[cluster] = false;
+        cluster_energy.update(cluster, -1.0);
+        ranks[cluster] = rank;
+    }
+
+    // rank the remaining pixels by filling the largest voids
+    let mut pattern = prototype;
+    for rank in ones..n {
+        let void = energy.largest_void(&pattern);
+        pattern[void] = true;
+        energy.update(void, 1.0);
+        ranks[void] = rank;
+    }
+
+    ranks.into_iter().map(|rank| (rank as f32 + 0.5) / n as f32).collect()
+}
@@ -41,6 +41,11 @@ pub enum ImageSampling {
     Nearest,
     /// Linear sampling.
     Linear,
+    /// Bicubic sampling, which keeps upscaled images sharper than linear sampling.
+    Cubic,
+    /// Linear sampling between mipmap levels, which avoids aliasing when
+    /// images are downscaled.
+    Mipmap,
 }
 
 pub trait ImageData {}
@@ -106,9 +111,9 @@ pub enum GradientKind {
     Sweep {
         /// Center point.
         center: Point,
-        /// Start angle of the sweep, counter-clockwise of the x-axis.
+        /// Start angle of the sweep in degrees, clockwise of the x-axis.
         start_angle: f32,
-        /// End angle of the sweep, counter-clockwise of the x-axis.
+        /// End angle of the sweep in degrees, clockwise of the x-axis.
         end_angle: f32,
     },
 }
@@ -1,5 +1,6 @@
 pub mod affine;
 pub mod bitmaps;
+mod blue_noise;
 pub mod brushes;
 pub mod colors;
 pub mod effects;
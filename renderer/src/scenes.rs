@@ -5,7 +5,7 @@ use std::{
 
 use super::{
     affine::Affine,
-    styles::{BlendMode, ImageFitMode, StrokeStyle},
+    styles::{BlendMode, FillStyle, ImageFitMode, StrokeStyle},
 };
 use crate::{
     bitmaps::DynamicBitmap,
@@ -15,6 +15,32 @@ use crate::{
     shapes::{Point, Shape},
 };
 
+/// Vector graphics formats that a scene can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorFormat {
+    Svg,
+    Pdf,
+}
+
+/// Error returned when a scene cannot be exported as a vector graphic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorExportError {
+    /// The rendering backend has no vector output for the requested format.
+    Unsupported(VectorFormat),
+}
+
+impl std::fmt::Display for VectorExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VectorExportError::Unsupported(format) => {
+                write!(f, "{:?} export is not supported by this rendering backend", format)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VectorExportError {}
+
 pub struct DynamicScene(pub Arc<Mutex<Box<dyn Scene>>>);
 
 impl DynamicScene {
@@ -70,10 +96,12 @@ impl DynamicScene {
         &mut self,
         shape: Shape,
         brush: Brush,
+        fill_style: FillStyle,
         transform: Option<Affine>,
         blend_mode: Option<BlendMode>,
     ) {
-        self.inner().draw_shape_fill(shape, brush, transform, blend_mode);
+        self.inner()
+            .draw_shape_fill(shape, brush, fill_style, transform, blend_mode);
     }
 
     pub fn draw_shape_stroke(
@@ -103,6 +131,14 @@ impl DynamicScene {
             position, glyphs, font_face, font_size, brush, alpha, transform, blend_mode,
         );
     }
+
+    pub fn export_vector(
+        &mut self,
+        format: VectorFormat,
+        physical_size: Option<(f32, f32)>,
+    ) -> Result<Vec<u8>, VectorExportError> {
+        self.inner().export_vector(format, physical_size)
+    }
 }
 
 pub trait Scene: Any {
@@ -123,7 +159,14 @@ pub trait Scene: Any {
         alpha: f32,
     );
     fn end_layer(&mut self);
-    fn draw_shape_fill(&mut self, shape: Shape, brush: Brush, transform: Option<Affine>, blend_mode: Option<BlendMode>);
+    fn draw_shape_fill(
+        &mut self,
+        shape: Shape,
+        brush: Brush,
+        fill_style: FillStyle,
+        transform: Option<Affine>,
+        blend_mode: Option<BlendMode>,
+    );
     fn draw_shape_stroke(
         &mut self,
         shape: Shape,
@@ -155,6 +198,7 @@ pub trait Scene: Any {
         self.draw_shape_fill(
             Shape::rectangle(position, width as f64, height as f64),
             brush,
+            FillStyle::NonZero,
             None,
             blend_mode,
         );
@@ -170,4 +214,16 @@ pub trait Scene: Any {
         transform: Option<Affine>,
         blend_mode: Option<BlendMode>,
     );
+    /// Exports everything drawn into the scene so far as a vector graphic. The
+    /// origin is placed at the center of the page, exactly as on screen.
+    /// `physical_size` is the (width, height) of the scene in millimeters; if
+    /// given, the document is scaled so that it prints at that size.
+    /// Backends without vector output return `VectorExportError::Unsupported`.
+    fn export_vector(
+        &mut self,
+        format: VectorFormat,
+        _physical_size: Option<(f32, f32)>,
+    ) -> Result<Vec<u8>, VectorExportError> {
+        Err(VectorExportError::Unsupported(format))
+    }
 }
@@ -1,4 +1,5 @@
 pub use super::scenes::Scene;
+use super::styles::FillStyle;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Point {
@@ -6,6 +7,13 @@ pub struct Point {
     pub y: f64,
 }
 
+impl Point {
+    /// The Euclidean distance to another point.
+    pub fn distance(&self, other: Point) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
 impl Into<Point> for (f64, f64) {
     fn into(self) -> Point {
         Point { x: self.0, y: self.1 }
@@ -21,7 +29,22 @@ impl Into<Point> for (f32, f32) {
     }
 }
 
+/// A single segment of a path.
 #[derive(Debug, Clone, Copy)]
+pub enum PathElement {
+    /// Start a new subpath at the given point.
+    MoveTo(Point),
+    /// Draw a straight line to the given point.
+    LineTo(Point),
+    /// Draw a quadratic Bézier curve with the given control point and end point.
+    QuadTo(Point, Point),
+    /// Draw a cubic Bézier curve with the given control points and end point.
+    CubicTo(Point, Point, Point),
+    /// Close the current subpath.
+    Close,
+}
+
+#[derive(Debug, Clone)]
 pub enum Shape {
     Circle {
         center: Point,
@@ -47,6 +70,38 @@ pub enum Shape {
         radius_y: f64,
         rotation: f64,
     },
+    /// A general path made of lines and Bézier curves.
+    Path {
+        elements: Vec<PathElement>,
+    },
+    /// A closed polygon.
+    Polygon {
+        points: Vec<Point>,
+    },
+    /// An open sequence of connected line segments.
+    Polyline {
+        points: Vec<Point>,
+    },
+    /// A circular arc. Angles are in degrees, measured clockwise from the positive x-axis.
+    Arc {
+        center: Point,
+        radius: f64,
+        start_angle: f64,
+        sweep_angle: f64,
+    },
+    /// A pie slice of a circle. Angles are in degrees, measured clockwise from the positive x-axis.
+    Wedge {
+        center: Point,
+        radius: f64,
+        start_angle: f64,
+        sweep_angle: f64,
+    },
+    /// A ring between two concentric circles.
+    Annulus {
+        center: Point,
+        inner_radius: f64,
+        outer_radius: f64,
+    },
 }
 
 impl Shape {
@@ -90,4 +145,398 @@ impl Shape {
             rotation,
         }
     }
+
+    pub fn path(elements: impl IntoIterator<Item = PathElement>) -> Self {
+        Self::Path {
+            elements: elements.into_iter().collect(),
+        }
+    }
+
+    pub fn polygon<P: Into<Point>>(points: impl IntoIterator<Item = P>) -> Self {
+        Self::Polygon {
+            points: points.into_iter().map(|p| p.into()).collect(),
+        }
+    }
+
+    pub fn polyline<P: Into<Point>>(points: impl IntoIterator<Item = P>) -> Self {
+        Self::Polyline {
+            points: points.into_iter().map(|p| p.into()).collect(),
+        }
+    }
+
+    pub fn arc(center: impl Into<Point>, radius: f64, start_angle: f64, sweep_angle: f64) -> Self {
+        Self::Arc {
+            center: center.into(),
+            radius,
+            start_angle,
+            sweep_angle,
+        }
+    }
+
+    pub fn wedge(center: impl Into<Point>, radius: f64, start_angle: f64, sweep_angle: f64) -> Self {
+        Self::Wedge {
+            center: center.into(),
+            radius,
+            start_angle,
+            sweep_angle,
+        }
+    }
+
+    pub fn annulus(center: impl Into<Point>, inner_radius: f64, outer_radius: f64) -> Self {
+        Self::Annulus {
+            center: center.into(),
+            inner_radius,
+            outer_radius,
+        }
+    }
+}
+
+/// A flattened part of the outline of a shape.
+struct Contour {
+    points: Vec<Point>,
+    closed: bool,
+}
+
+impl Contour {
+    /// The segments of the contour, including the closing segment.
+    fn segments(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        let closing = match (self.closed, self.points.first(), self.points.last()) {
+            (true, Some(first), Some(last)) => Some((*last, *first)),
+            _ => None,
+        };
+        self.points.windows(2).map(|w| (w[0], w[1])).chain(closing)
+    }
+}
+
+/// The number of line segments used to approximate a circle with the given
+/// radius, which keeps the error below half a pixel.
+fn circle_segments(radius: f64) -> usize {
+    ((radius.abs().sqrt() * 4.0).ceil() as usize).max(16)
+}
+
+/// Returns `count` + 1 points on the ellipse around `center`, starting at
+/// `start` and ending at `start + sweep` (in degrees, clockwise).
+fn ellipse_points(center: Point, radius_x: f64, radius_y: f64, rotation: f64, start: f64, sweep: f64) -> Vec<Point> {
+    let count = ((circle_segments(radius_x.max(radius_y)) as f64 * (sweep.abs() / 360.0)).ceil() as usize).max(1);
+    let (sin_r, cos_r) = rotation.to_radians().sin_cos();
+    (0..=count)
+        .map(|i| {
+            let angle = (start + sweep * i as f64 / count as f64).to_radians();
+            let (x, y) = (radius_x * angle.cos(), radius_y * angle.sin());
+            Point {
+                x: center.x + x * cos_r - y * sin_r,
+                y: center.y + x * sin_r + y * cos_r,
+            }
+        })
+        .collect()
+}
+
+/// Returns true if the angle of the vector (dx, dy) lies within the sweep.
+fn in_sweep(dx: f64, dy: f64, start_angle: f64, sweep_angle: f64) -> bool {
+    if sweep_angle.abs() >= 360.0 {
+        return true;
+    }
+    let (start, sweep) = if sweep_angle < 0.0 {
+        (start_angle + sweep_angle, -sweep_angle)
+    } else {
+        (start_angle, sweep_angle)
+    };
+    (dy.atan2(dx).to_degrees() - start).rem_euclid(360.0) <= sweep
+}
+
+/// The distance between the point and the segment from `a` to `b`.
+fn segment_distance(p: Point, a: Point, b: Point) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq > 0.0 {
+        (((p.x - a.x) * dx + (p.y - a.y) * dy) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (p.x - (a.x + t * dx)).hypot(p.y - (a.y + t * dy))
+}
+
+/// The winding number of the (implicitly closed) contours around the point.
+fn winding_number(p: Point, outline: &[Contour]) -> i32 {
+    let mut winding = 0;
+    for contour in outline {
+        let n = contour.points.len();
+        for i in 0..n {
+            let (a, b) = (contour.points[i], contour.points[(i + 1) % n]);
+            // the sign of the cross product tells on which side of the edge the point is
+            let side = (b.x - a.x) * (p.y - a.y) - (p.x - a.x) * (b.y - a.y);
+            if a.y <= p.y && b.y > p.y && side > 0.0 {
+                winding += 1;
+            } else if a.y > p.y && b.y <= p.y && side < 0.0 {
+                winding -= 1;
+            }
+        }
+    }
+    winding
+}
+
+impl Shape {
+    /// Returns true if the point lies inside the shape (according to the fill
+    /// style) or within `tolerance` of its outline. Lines, polylines and arcs
+    /// are open shapes that only contain points close to their outline.
+    pub fn contains(&self, point: impl Into<Point>, fill_style: FillStyle, tolerance: f64) -> bool {
+        let p = point.into();
+        if self.fill_contains(p, fill_style) {
+            return true;
+        }
+
+        tolerance > 0.0
+            && self
+                .outline()
+                .iter()
+                .flat_map(|contour| contour.segments())
+                .any(|(a, b)| segment_distance(p, a, b) <= tolerance)
+    }
+
+    /// Returns the top-left and the bottom-right corner of the axis-aligned
+    /// bounding box of the shape (without the stroke).
+    pub fn bounds(&self) -> (Point, Point) {
+        let around = |c: &Point, rx: f64, ry: f64| -> (Point, Point) {
+            ((c.x - rx, c.y - ry).into(), (c.x + rx, c.y + ry).into())
+        };
+
+        match self {
+            Shape::Circle { center, radius } => around(center, radius.abs(), radius.abs()),
+            Shape::Annulus {
+                center, outer_radius, ..
+            } => around(center, outer_radius.abs(), outer_radius.abs()),
+            Shape::Ellipse {
+                center,
+                radius_x,
+                radius_y,
+                rotation,
+            } => {
+                // the half extents of the rotated ellipse
+                let (sin_r, cos_r) = rotation.to_radians().sin_cos();
+                let rx = (radius_x * cos_r).hypot(radius_y * sin_r);
+                let ry = (radius_x * sin_r).hypot(radius_y * cos_r);
+                around(center, rx, ry)
+            }
+            _ => {
+                let (x0, y0, x1, y1) = self.outline().iter().flat_map(|contour| &contour.points).fold(
+                    (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+                    |(x0, y0, x1, y1), p| (x0.min(p.x), y0.min(p.y), x1.max(p.x), y1.max(p.y)),
+                );
+
+                // shapes without points have empty bounds
+                if x0 > x1 {
+                    let origin = Point { x: 0.0, y: 0.0 };
+                    (origin, origin)
+                } else {
+                    ((x0, y0).into(), (x1, y1).into())
+                }
+            }
+        }
+    }
+
+    /// Returns true if the point lies inside the filled area of the shape.
+    fn fill_contains(&self, p: Point, fill_style: FillStyle) -> bool {
+        match self {
+            Shape::Circle { center, radius } => p.distance(*center) <= *radius,
+            Shape::Rectangle { a, w, h } => {
+                let (x0, x1) = (a.x.min(a.x + w), a.x.max(a.x + w));
+                let (y0, y1) = (a.y.min(a.y + h), a.y.max(a.y + h));
+                p.x >= x0 && p.x <= x1 && p.y >= y0 && p.y <= y1
+            }
+            Shape::RoundedRectangle { a, b, radius } => {
+                let (x0, x1) = (a.x.min(b.x), a.x.max(b.x));
+                let (y0, y1) = (a.y.min(b.y), a.y.max(b.y));
+                let radius = radius.min((x1 - x0) / 2.0).min((y1 - y0) / 2.0).max(0.0);
+                // the distance to the rectangle that is rounded by the radius
+                let dx = (x0 + radius - p.x).max(p.x - (x1 - radius)).max(0.0);
+                let dy = (y0 + radius - p.y).max(p.y - (y1 - radius)).max(0.0);
+                p.x >= x0 && p.x <= x1 && p.y >= y0 && p.y <= y1 && dx.hypot(dy) <= radius
+            }
+            Shape::Ellipse {
+                center,
+                radius_x,
+                radius_y,
+                rotation,
+            } => {
+                if *radius_x <= 0.0 || *radius_y <= 0.0 {
+                    return false;
+                }
+                // rotate the point into the frame of the ellipse
+                let (sin_r, cos_r) = rotation.to_radians().sin_cos();
+                let (dx, dy) = (p.x - center.x, p.y - center.y);
+                let (x, y) = (dx * cos_r + dy * sin_r, -dx * sin_r + dy * cos_r);
+                (x / radius_x).powi(2) + (y / radius_y).powi(2) <= 1.0
+            }
+            Shape::Polygon { .. } | Shape::Path { .. } => {
+                let winding = winding_number(p, &self.outline());
+                match fill_style {
+                    FillStyle::NonZero => winding != 0,
+                    FillStyle::EvenOdd => winding % 2 != 0,
+                }
+            }
+            Shape::Wedge {
+                center,
+                radius,
+                start_angle,
+                sweep_angle,
+            } => {
+                let (dx, dy) = (p.x - center.x, p.y - center.y);
+                dx.hypot(dy) <= *radius && in_sweep(dx, dy, *start_angle, *sweep_angle)
+            }
+            Shape::Annulus {
+                center,
+                inner_radius,
+                outer_radius,
+            } => {
+                let distance = p.distance(*center);
+                distance >= *inner_radius && distance <= *outer_radius
+            }
+            Shape::Line { .. } | Shape::Polyline { .. } | Shape::Arc { .. } => false,
+        }
+    }
+
+    /// Approximates the outline of the shape by contours.
+    fn outline(&self) -> Vec<Contour> {
+        let closed = |points: Vec<Point>| Contour { points, closed: true };
+        let open = |points: Vec<Point>| Contour { points, closed: false };
+
+        match self {
+            Shape::Circle { center, radius } => {
+                vec![closed(ellipse_points(*center, *radius, *radius, 0.0, 0.0, 360.0))]
+            }
+            Shape::Rectangle { a, w, h } => vec![closed(vec![
+                *a,
+                (a.x + w, a.y).into(),
+                (a.x + w, a.y + h).into(),
+                (a.x, a.y + h).into(),
+            ])],
+            Shape::RoundedRectangle { a, b, radius } => {
+                let (x0, x1) = (a.x.min(b.x), a.x.max(b.x));
+                let (y0, y1) = (a.y.min(b.y), a.y.max(b.y));
+                let r = radius.min((x1 - x0) / 2.0).min((y1 - y0) / 2.0).max(0.0);
+                // the corners, clockwise from the top left
+                let corners = [
+                    (x0 + r, y0 + r, 180.0),
+                    (x1 - r, y0 + r, 270.0),
+                    (x1 - r, y1 - r, 0.0),
+                    (x0 + r, y1 - r, 90.0),
+                ];
+                vec![closed(
+                    corners
+                        .into_iter()
+                        .flat_map(|(x, y, start)| ellipse_points((x, y).into(), r, r, 0.0, start, 90.0))
+                        .collect(),
+                )]
+            }
+            Shape::Line { start, end } => vec![open(vec![*start, *end])],
+            Shape::Ellipse {
+                center,
+                radius_x,
+                radius_y,
+                rotation,
+            } => vec![closed(ellipse_points(
+                *center, *radius_x, *radius_y, *rotation, 0.0, 360.0,
+            ))],
+            Shape::Path { elements } => {
+                let mut outline = Vec::new();
+                let mut current: Vec<Point> = Vec::new();
+                // the current point and the start of the current subpath
+                let mut pen = Point { x: 0.0, y: 0.0 };
+                let mut start = pen;
+
+                for element in elements {
+                    if current.is_empty() && !matches!(element, PathElement::MoveTo(_) | PathElement::Close) {
+                        current.push(pen);
+                    }
+                    match *element {
+                        PathElement::MoveTo(p) => {
+                            outline.push(open(std::mem::take(&mut current)));
+                            current.push(p);
+                            start = p;
+                        }
+                        PathElement::LineTo(p) => current.push(p),
+                        PathElement::QuadTo(c, p) => {
+                            let count = circle_segments(pen.distance(c) + c.distance(p));
+                            current.extend((1..=count).map(|i| {
+                                let t = i as f64 / count as f64;
+                                let (u, v, w) = ((1.0 - t).powi(2), 2.0 * (1.0 - t) * t, t * t);
+                                Point {
+                                    x: u * pen.x + v * c.x + w * p.x,
+                                    y: u * pen.y + v * c.y + w * p.y,
+                                }
+                            }));
+                        }
+                        PathElement::CubicTo(c1, c2, p) => {
+                            let count = circle_segments(pen.distance(c1) + c1.distance(c2) + c2.distance(p));
+                            current.extend((1..=count).map(|i| {
+                                let t = i as f64 / count as f64;
+                                let (u, v, w, z) = (
+                                    (1.0 - t).powi(3),
+                                    3.0 * (1.0 - t).powi(2) * t,
+                                    3.0 * (1.0 - t) * t * t,
+                                    t.powi(3),
+                                );
+                                Point {
+                                    x: u * pen.x + v * c1.x + w * c2.x + z * p.x,
+                                    y: u * pen.y + v * c1.y + w * c2.y + z * p.y,
+                                }
+                            }));
+                        }
+                        PathElement::Close => {
+                            outline.push(closed(std::mem::take(&mut current)));
+                            // the next subpath starts where the closed one started
+                            pen = start;
+                            continue;
+                        }
+                    }
+                    pen = *current.last().unwrap();
+                }
+                outline.push(open(current));
+
+                // single points have no outline
+                outline.retain(|contour| contour.points.len() > 1);
+                outline
+            }
+            Shape::Polygon { points } => vec![closed(points.clone())],
+            Shape::Polyline { points } => vec![open(points.clone())],
+            Shape::Arc {
+                center,
+                radius,
+                start_angle,
+                sweep_angle,
+            } => vec![open(ellipse_points(
+                *center,
+                *radius,
+                *radius,
+                0.0,
+                *start_angle,
+                *sweep_angle,
+            ))],
+            Shape::Wedge {
+                center,
+                radius,
+                start_angle,
+                sweep_angle,
+            } => {
+                let mut points = vec![*center];
+                points.extend(ellipse_points(
+                    *center,
+                    *radius,
+                    *radius,
+                    0.0,
+                    *start_angle,
+                    *sweep_angle,
+                ));
+                vec![closed(points)]
+            }
+            Shape::Annulus {
+                center,
+                inner_radius,
+                outer_radius,
+            } => vec![
+                closed(ellipse_points(*center, *inner_radius, *inner_radius, 0.0, 0.0, 360.0)),
+                closed(ellipse_points(*center, *outer_radius, *outer_radius, 0.0, 0.0, 360.0)),
+            ],
+        }
+    }
 }
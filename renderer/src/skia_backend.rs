@@ -19,8 +19,8 @@ use skia_safe::{
     },
     image::Image as SkImage,
     images::raster_from_data as sk_raster_from_data,
-    scalar, AlphaType as SkAlphaType, ColorSpace, ColorType, Font as SkFont, Matrix, PictureRecorder, SamplingOptions,
-    Typeface as SkTypeface,
+    pdf, scalar, svg, AlphaType as SkAlphaType, ColorSpace, ColorType, Font as SkFont, Matrix, Picture,
+    PictureRecorder, SamplingOptions, Typeface as SkTypeface,
 };
 use wgpu::{Adapter, Device, Queue, Texture};
 
@@ -31,9 +31,9 @@ use crate::{
     colors::RGBA,
     font::{DynamicFontFace, Glyph, Typeface},
     renderer::{Renderer, RendererFactory},
-    scenes::Scene,
-    shapes::{Point, Shape},
-    styles::{BlendMode, ImageFitMode, StrokeStyle},
+    scenes::{Scene, VectorExportError, VectorFormat},
+    shapes::{PathElement, Point, Shape},
+    styles::{BlendMode, Cap, FillStyle, ImageFitMode, Join, StrokeStyle},
 };
 
 #[derive(Debug)]
@@ -87,6 +87,73 @@ impl SkiaScene {
         }
     }
 
+    /// Returns everything recorded so far as a picture. Recording continues on a
+    /// fresh canvas that starts with the picture, so the scene can still be drawn
+    /// to, rendered or exported afterwards.
+    fn snapshot(&mut self) -> Picture {
+        let picture = self.picture_recorder.finish_recording_as_picture(None).unwrap();
+        let bounds = skia_safe::Rect::from_wh(self.width as f32, self.height as f32);
+        let canvas = self.picture_recorder.begin_recording(bounds, None);
+        canvas.draw_picture(&picture, None, None);
+        picture
+    }
+
+    /// Renders the scene to an SVG document.
+    fn export_svg(&mut self, physical_size: Option<(f32, f32)>) -> Vec<u8> {
+        let picture = self.snapshot();
+        let (width, height) = (self.width as scalar, self.height as scalar);
+
+        // convert text to paths so that the document does not depend on installed fonts
+        let canvas = svg::Canvas::new(
+            skia_safe::Rect::from_wh(width, height),
+            Some(svg::canvas::Flags::CONVERT_TEXT_TO_PATHS),
+        );
+
+        // move origin to the center, same as when rendering to the screen
+        canvas.translate((width / 2.0, height / 2.0));
+        canvas.draw_picture(&picture, None, None);
+
+        let data = canvas.end();
+        let document = String::from_utf8_lossy(data.as_bytes());
+
+        match physical_size {
+            Some((width_mm, height_mm)) => {
+                svg_with_physical_size(&document, self.width, self.height, width_mm, height_mm).into_bytes()
+            }
+            None => document.into_owned().into_bytes(),
+        }
+    }
+
+    /// Renders the scene to a single page PDF document.
+    fn export_pdf(&mut self, physical_size: Option<(f32, f32)>) -> Vec<u8> {
+        let picture = self.snapshot();
+        let (width, height) = (self.width as scalar, self.height as scalar);
+
+        // PDF pages are measured in points (1/72 inch). Without a physical size,
+        // one pixel maps to one point.
+        let (page_width, page_height) = match physical_size {
+            Some((width_mm, height_mm)) => (width_mm / 25.4 * 72.0, height_mm / 25.4 * 72.0),
+            None => (width, height),
+        };
+
+        let mut metadata = pdf::Metadata::default();
+        metadata.creator = "psydk".to_owned();
+        // rasterize effects that can't be represented as vectors at the native pixel density
+        metadata.raster_dpi = Some(width / page_width * 72.0);
+
+        let mut buffer = Vec::new();
+        let document = pdf::new_document(&mut buffer, Some(&metadata));
+        let mut page = document.begin_page((page_width, page_height), None);
+
+        let canvas = page.canvas();
+        canvas.scale((page_width / width, page_height / height));
+        canvas.translate((width / 2.0, height / 2.0));
+        canvas.draw_picture(&picture, None, None);
+
+        page.end_page().close();
+        buffer
+    }
+
     fn draw_shape(skia_canvas: &skia_safe::Canvas, skia_paint: skia_safe::Paint, shape: Shape, affine: Option<Affine>) {
         // apply the affine transformation
         if let Some(affine) = affine {
@@ -129,9 +196,12 @@ impl SkiaScene {
                 skia_canvas.restore();
             }
             Shape::RoundedRectangle { a, b, radius } => {
-                let rect = skia_safe::Rect::from_xywh(a.x as f32, a.y as f32, b.x as f32, b.y as f32);
+                let rect = skia_safe::Rect::new(a.x as f32, a.y as f32, b.x as f32, b.y as f32);
                 skia_canvas.draw_round_rect(rect, radius as f32, radius as f32, &skia_paint);
             }
+            shape => {
+                skia_canvas.draw_path(&Self::shape_to_path(&shape), &skia_paint);
+            }
         }
         // restore the canvas
         if let Some(_) = affine {
@@ -139,31 +209,114 @@ impl SkiaScene {
         }
     }
 
-    fn clip_shape(skia_canvas: &skia_safe::Canvas, skia_paint: skia_safe::Paint, shape: Shape, affine: Option<Affine>) {
-        // apply the affine transformation
-        if let Some(affine) = affine {
-            skia_canvas.save();
-            skia_canvas.concat(&affine.into());
-        }
+    /// Converts any shape into a skia path.
+    fn shape_to_path(shape: &Shape) -> skia_safe::Path {
+        use skia_safe::{Path, PathDirection, Rect};
+
+        // bounds of a circle, used for arcs and wedges
+        let oval = |center: &Point, radius: f64| {
+            Rect::from_xywh(
+                (center.x - radius) as f32,
+                (center.y - radius) as f32,
+                (radius * 2.0) as f32,
+                (radius * 2.0) as f32,
+            )
+        };
 
         match shape {
+            Shape::Circle { center, radius } => Path::circle(*center, *radius as f32, None),
             Shape::Rectangle { a, w, h } => {
-                let rect = skia_safe::Rect::from_xywh(a.x as f32, a.y as f32, w as f32, h as f32);
-                skia_canvas.clip_rect(rect, skia_safe::ClipOp::Intersect, true);
+                Path::rect(Rect::from_xywh(a.x as f32, a.y as f32, *w as f32, *h as f32), None)
             }
-            Shape::Circle { center, radius } => {
-                let circle = skia_safe::path::Path::circle(center, radius as f32, skia_safe::path::Direction::CCW);
-                skia_canvas.clip_path(&circle, skia_safe::ClipOp::Intersect, true);
+            Shape::RoundedRectangle { a, b, radius } => {
+                let rect = Rect::new(a.x as f32, a.y as f32, b.x as f32, b.y as f32);
+                Path::rrect(
+                    skia_safe::RRect::new_rect_xy(rect, *radius as f32, *radius as f32),
+                    None,
+                )
+            }
+            Shape::Line { start, end } => Path::line(*start, *end),
+            Shape::Ellipse {
+                center,
+                radius_x,
+                radius_y,
+                rotation,
+            } => {
+                let bounds = Rect::from_xywh(
+                    (center.x - radius_x) as f32,
+                    (center.y - radius_y) as f32,
+                    (radius_x * 2.0) as f32,
+                    (radius_y * 2.0) as f32,
+                );
+                let mut path = Path::oval(bounds, None);
+                path.transform(&Matrix::rotate_deg_pivot(*rotation as f32, *center));
+                path
+            }
+            Shape::Path { elements } => {
+                let mut path = Path::new();
+                for element in elements {
+                    match *element {
+                        PathElement::MoveTo(p) => path.move_to(p),
+                        PathElement::LineTo(p) => path.line_to(p),
+                        PathElement::QuadTo(p1, p2) => path.quad_to(p1, p2),
+                        PathElement::CubicTo(p1, p2, p3) => path.cubic_to(p1, p2, p3),
+                        PathElement::Close => path.close(),
+                    };
+                }
+                path
+            }
+            Shape::Polygon { points } => {
+                let points: Vec<skia_safe::Point> = points.iter().map(|p| (*p).into()).collect();
+                Path::polygon(&points, true, None, None)
+            }
+            Shape::Polyline { points } => {
+                let points: Vec<skia_safe::Point> = points.iter().map(|p| (*p).into()).collect();
+                Path::polygon(&points, false, None, None)
+            }
+            Shape::Arc {
+                center,
+                radius,
+                start_angle,
+                sweep_angle,
+            } => {
+                let mut path = Path::new();
+                path.add_arc(oval(center, *radius), *start_angle as f32, *sweep_angle as f32);
+                path
+            }
+            Shape::Wedge {
+                center,
+                radius,
+                start_angle,
+                sweep_angle,
+            } => {
+                let mut path = Path::new();
+                path.move_to(*center);
+                path.arc_to(oval(center, *radius), *start_angle as f32, *sweep_angle as f32, false);
+                path.close();
+                path
             }
-            _ => {
-                todo!()
+            Shape::Annulus {
+                center,
+                inner_radius,
+                outer_radius,
+            } => {
+                // the inner circle winds the other way so that it cuts a hole with the non-zero fill rule
+                let mut path = Path::circle(*center, *outer_radius as f32, PathDirection::CW);
+                path.add_circle(*center, *inner_radius as f32, PathDirection::CCW);
+                path
             }
         }
+    }
 
-        // restore the canvas
-        if let Some(_) = affine {
-            skia_canvas.restore();
+    /// Intersects the clip of the canvas with the shape. The transformation is
+    /// applied to the path rather than the canvas, as restoring the canvas
+    /// would also remove the clip.
+    fn clip_shape(skia_canvas: &skia_safe::Canvas, shape: Shape, affine: Option<Affine>) {
+        let mut path = Self::shape_to_path(&shape);
+        if let Some(affine) = affine {
+            path.transform(&affine.into());
         }
+        skia_canvas.clip_path(&path, skia_safe::ClipOp::Intersect, true);
     }
 }
 
@@ -209,17 +362,15 @@ impl Scene for SkiaScene {
         alpha: f32,
     ) {
         let mut canvas = self.picture_recorder.recording_canvas().unwrap();
-        // let mut layer_paint = skia_safe::Paint::default();
-        // layer_paint.set_alpha_f(alpha);
-        // // layer_paint.set_blend_mode(composite_mode.into());
-        // let save_layer_rec = skia_safe::canvas::SaveLayerRec::default();
-        // let save_layer_rec = save_layer_rec.paint(&layer_paint);
 
-        canvas.save_layer_alpha_f(None, alpha);
-        // Self::clip_shape(&mut canvas, skia_safe::Paint::default(), clip, clip_transform);
+        // the layer is composited onto the canvas with its alpha and blend mode
+        let mut layer_paint = skia_safe::Paint::default();
+        layer_paint.set_alpha_f(alpha);
+        layer_paint.set_blend_mode(composite_mode.into());
+        canvas.save_layer(&skia_safe::canvas::SaveLayerRec::default().paint(&layer_paint));
 
-        // update the current blend mode
-        // self.current_blend_mode = composite_mode.into();
+        // the clip is part of the saved layer and is removed by `end_layer`
+        Self::clip_shape(&mut canvas, clip, clip_transform);
     }
 
     fn end_layer(&mut self) {
@@ -230,6 +381,7 @@ impl Scene for SkiaScene {
         &mut self,
         shape: Shape,
         brush: Brush,
+        fill_style: FillStyle,
         transform: Option<Affine>,
         blend_mode: Option<BlendMode>,
     ) {
@@ -242,7 +394,18 @@ impl Scene for SkiaScene {
             paint.set_blend_mode(blend_mode.into());
         }
 
-        Self::draw_shape(&mut canvas, paint, shape, transform);
+        match fill_style {
+            FillStyle::NonZero => Self::draw_shape(&mut canvas, paint, shape, transform),
+            FillStyle::EvenOdd => {
+                // the fill rule is a property of the path in skia
+                let mut path = Self::shape_to_path(&shape);
+                path.set_fill_type(skia_safe::PathFillType::EvenOdd);
+                if let Some(affine) = transform {
+                    path.transform(&affine.into());
+                }
+                canvas.draw_path(&path, &paint);
+            }
+        }
     }
 
     fn draw_shape_stroke(
@@ -264,10 +427,35 @@ impl Scene for SkiaScene {
 
         // set the stroke width
         paint.set_stroke_width(style.width as scalar);
+        paint.set_stroke_join(style.join.into());
+        paint.set_stroke_miter(style.miter_limit as scalar);
+        // skia uses the same cap for both ends of a stroke
+        paint.set_stroke_cap(style.start_cap.into());
+
+        // set the dash pattern
+        if !style.dash_pattern.is_empty() {
+            let mut intervals: Vec<scalar> = style.dash_pattern.iter().map(|d| *d as scalar).collect();
+            // skia needs an even number of intervals, so repeat odd patterns (as SVG does)
+            if intervals.len() % 2 == 1 {
+                intervals.extend_from_within(..);
+            }
+            paint.set_path_effect(skia_safe::PathEffect::dash(&intervals, style.dash_offset as scalar));
+        }
 
         Self::draw_shape(&mut canvas, paint, shape, transform);
     }
 
+    fn export_vector(
+        &mut self,
+        format: VectorFormat,
+        physical_size: Option<(f32, f32)>,
+    ) -> Result<Vec<u8>, VectorExportError> {
+        Ok(match format {
+            VectorFormat::Svg => self.export_svg(physical_size),
+            VectorFormat::Pdf => self.export_pdf(physical_size),
+        })
+    }
+
     fn draw_glyphs(
         &mut self,
         position: Point,
@@ -293,17 +481,33 @@ impl Scene for SkiaScene {
             paint.set_alpha_f(alpha);
         }
 
+        if let Some(blend_mode) = blend_mode {
+            paint.set_blend_mode(blend_mode.into());
+        }
+
         // the origin of the text
         let origin: skia_safe::Point = position.into();
 
         // draw the glyphs
         let canvas = self.picture_recorder.recording_canvas().unwrap();
+
+        // apply the affine transformation
+        if let Some(affine) = transform {
+            canvas.save();
+            canvas.concat(&affine.into());
+        }
+
         let glyph_ids = glyphs.iter().map(|glyph| glyph.id).collect::<Vec<u16>>();
         let glyph_positions: Vec<skia_safe::Point> = glyphs.into_iter().map(|glyph| glyph.position.into()).collect();
         let glyph_positions = skia_safe::canvas::GlyphPositions::Points(&glyph_positions);
         // let glyph_cluster_size: Vec<u32> = glyphs.into_iter().map(|glyph| glyph.end - glyph.start).collect();
         // canvas.draw_glyphs_at(&glyph_ids, glyph_positions, origin, &skia_font, &paint);
         canvas.draw_glyphs_at(&glyph_ids, glyph_positions, origin, &skia_font, &paint);
+
+        // restore the canvas
+        if transform.is_some() {
+            canvas.restore();
+        }
     }
 }
 
@@ -531,6 +735,29 @@ impl Bitmap for SkiaBitmap {
     }
 }
 
+/// Wraps an SVG document in an outer `svg` element with the physical size of
+/// the screen in millimeters. The `viewBox` of the outer element spans the
+/// pixel size of the document, so the drawing keeps its pixel coordinates.
+fn svg_with_physical_size(document: &str, width_px: u32, height_px: u32, width_mm: f32, height_mm: f32) -> String {
+    // the XML declaration is only allowed at the very start of the document
+    let body = match document.trim_start().strip_prefix("<?xml") {
+        Some(rest) => rest.split_once("?>").map_or(rest, |(_, body)| body),
+        None => document,
+    };
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\" ?>\n\
+         <svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}mm\" height=\"{}mm\" viewBox=\"0 0 {} {}\">\n\
+         {}\n\
+         </svg>\n",
+        width_mm,
+        height_mm,
+        width_px,
+        height_px,
+        body.trim()
+    )
+}
+
 // convert a color to a skia color
 impl From<RGBA> for skia_safe::Color4f {
     fn from(color: RGBA) -> Self {
@@ -640,6 +867,10 @@ impl From<&Brush<'_>> for skia_safe::Paint {
                     ImageSampling::Linear => {
                         SamplingOptions::new(skia_safe::FilterMode::Linear, skia_safe::MipmapMode::None)
                     }
+                    ImageSampling::Cubic => SamplingOptions::from(skia_safe::CubicResampler::mitchell()),
+                    ImageSampling::Mipmap => {
+                        SamplingOptions::new(skia_safe::FilterMode::Linear, skia_safe::MipmapMode::Linear)
+                    }
                 };
 
                 // create a shader from the image
@@ -700,6 +931,28 @@ impl From<Extend> for skia_safe::TileMode {
     }
 }
 
+// convert Join to skia stroke join
+impl From<Join> for skia_safe::PaintJoin {
+    fn from(join: Join) -> Self {
+        match join {
+            Join::Bevel => skia_safe::PaintJoin::Bevel,
+            Join::Miter => skia_safe::PaintJoin::Miter,
+            Join::Round => skia_safe::PaintJoin::Round,
+        }
+    }
+}
+
+// convert Cap to skia stroke cap
+impl From<Cap> for skia_safe::PaintCap {
+    fn from(cap: Cap) -> Self {
+        match cap {
+            Cap::Butt => skia_safe::PaintCap::Butt,
+            Cap::Square => skia_safe::PaintCap::Square,
+            Cap::Round => skia_safe::PaintCap::Round,
+        }
+    }
+}
+
 // convert CompositeMode to skia blend mode
 impl From<BlendMode> for skia_safe::BlendMode {
     fn from(composite_mode: BlendMode) -> Self {
@@ -712,7 +965,8 @@ impl From<BlendMode> for skia_safe::BlendMode {
             BlendMode::DestinationIn => skia_safe::BlendMode::DstIn,
             BlendMode::DestinationOut => skia_safe::BlendMode::DstOut,
             BlendMode::DestinationOver => skia_safe::BlendMode::DstOver,
-            BlendMode::Lighter => skia_safe::BlendMode::Lighten,
+            // "lighter" adds the colors, like the canvas composite operation
+            BlendMode::Lighter => skia_safe::BlendMode::Plus,
             BlendMode::Copy => skia_safe::BlendMode::Src,
             BlendMode::Xor => skia_safe::BlendMode::Xor,
             BlendMode::Multiply => skia_safe::BlendMode::Multiply,
@@ -724,46 +978,7 @@ impl From<BlendMode> for skia_safe::BlendMode {
 // convert Shape to Path
 impl From<&Shape> for skia_safe::Path {
     fn from(shape: &Shape) -> Self {
-        let mut path = skia_safe::Path::new();
-        match shape {
-            Shape::Rectangle { a, w, h } => {
-                path.add_rect(
-                    skia_safe::Rect::from_xywh(a.x as scalar, a.y as scalar, *w as scalar, *h as scalar),
-                    None,
-                );
-            }
-            Shape::Circle { center, radius } => {
-                path.add_circle(*center, *radius as scalar, None);
-            }
-            Shape::Line { start, end } => {
-                path.move_to(*start);
-                path.line_to(*end);
-            }
-            Shape::Ellipse {
-                center,
-                radius_x,
-                radius_y,
-                rotation,
-            } => {
-                path.add_oval(
-                    skia_safe::Rect::from_xywh(
-                        center.x as scalar - *radius_x as scalar,
-                        center.y as scalar - *radius_y as scalar,
-                        *radius_x as scalar * 2.0,
-                        *radius_y as scalar * 2.0,
-                    ),
-                    None,
-                );
-            }
-            Shape::RoundedRectangle { a, b, radius } => {
-                path.add_round_rect(
-                    skia_safe::Rect::from_xywh(a.x as scalar, a.y as scalar, b.x as scalar, b.y as scalar),
-                    (*radius as scalar, *radius as scalar),
-                    None,
-                );
-            }
-        }
-        path
+        SkiaScene::shape_to_path(shape)
     }
 }
 
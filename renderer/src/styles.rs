@@ -1,5 +1,6 @@
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub enum FillStyle {
+    #[default]
     NonZero,
     EvenOdd,
 }
@@ -27,6 +28,32 @@ impl StrokeStyle {
             dash_offset: 0.0,
         }
     }
+
+    /// Sets the join style.
+    pub fn with_join(mut self, join: Join) -> Self {
+        self.join = join;
+        self
+    }
+
+    /// Sets the miter limit.
+    pub fn with_miter_limit(mut self, miter_limit: f64) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    /// Sets the cap style of both the start and the end of the stroke.
+    pub fn with_caps(mut self, cap: Cap) -> Self {
+        self.start_cap = cap;
+        self.end_cap = cap;
+        self
+    }
+
+    /// Sets the dash pattern (alternating lengths of dashes and gaps) and its offset.
+    pub fn with_dashes(mut self, dash_offset: f64, dash_pattern: impl IntoIterator<Item = f64>) -> Self {
+        self.dash_offset = dash_offset;
+        self.dash_pattern = dash_pattern.into_iter().collect();
+        self
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -43,7 +70,8 @@ pub enum Cap {
     Round,
 }
 
-pub type Dashes = Vec<[f64; 4]>;
+/// Alternating lengths of dashes and gaps.
+pub type Dashes = Vec<f64>;
 
 #[derive(Debug, Clone, Copy)]
 pub enum ImageFitMode {
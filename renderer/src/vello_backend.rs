@@ -1,10 +1,11 @@
-use crate::brushes::{Extend, ImageColor};
+use crate::brushes::{Extend, ImageColor, ImageSampling};
 use cosmic_text::fontdb::FaceInfo;
 use image::{DynamicImage, GenericImageView};
 use skrifa::raw::FileRef;
 use std::any::Any;
 use std::sync::Arc;
 use vello::kurbo::PathEl;
+use vello::kurbo::Shape as KurboShape;
 use vello::peniko::color::{AlphaColor, ColorSpaceTag};
 use vello::peniko::BlendMode as VelloBlendMode;
 use vello::peniko::Compose as VelloCompose;
@@ -18,11 +19,11 @@ use super::{
     brushes::{Brush, ColorStop},
     colors::RGBA,
 };
-use crate::bitmaps::DynamicBitmap;
+use crate::bitmaps::{Bitmap, DynamicBitmap};
 use crate::prelude::DynamicFontFace;
 use crate::renderer::Renderer;
-use crate::shapes::{Point, Shape};
-use crate::styles::{BlendMode, FillStyle, StrokeStyle};
+use crate::shapes::{PathElement, Point, Shape};
+use crate::styles::{BlendMode, Cap, FillStyle, ImageFitMode, Join, StrokeStyle};
 use crate::{affine::Affine, scenes::Scene};
 
 pub struct VelloScene {
@@ -38,6 +39,22 @@ pub struct VelloScene {
     pub height: u32,
 }
 
+/// An image that can be used as a brush by the Vello backend.
+#[derive(Debug)]
+pub struct VelloBitmap {
+    image: vello::peniko::Image,
+}
+
+impl Bitmap for VelloBitmap {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
 pub struct VelloRenderer {
     /// The vello renderer struct
     pub renderer: vello::Renderer,
@@ -153,7 +170,10 @@ impl Renderer for vello::Renderer {
     }
 
     fn create_bitmap(&self, data: DynamicImage) -> DynamicBitmap {
-        todo!()
+        let (width, height) = data.dimensions();
+        let blob = vello::peniko::Blob::new(Arc::new(data.into_rgba8().into_raw()));
+        let image = vello::peniko::Image::new(blob, vello::peniko::ImageFormat::Rgba8, width, height);
+        DynamicBitmap(Box::new(VelloBitmap { image }))
     }
 
     fn load_font_face(
@@ -237,6 +257,21 @@ impl VelloScene {
             height,
         }
     }
+
+    /// Pushes a layer that covers the whole scene if the blend mode is not
+    /// the default, as Vello only applies blend modes to layers. Returns true
+    /// if a layer was pushed and needs to be popped after drawing.
+    fn push_blend_layer(&mut self, blend_mode: Option<BlendMode>) -> bool {
+        match blend_mode {
+            None | Some(BlendMode::SourceOver) => false,
+            Some(blend_mode) => {
+                let bounds = vello::kurbo::Rect::new(0.0, 0.0, self.width as f64, self.height as f64);
+                self.vello_scene
+                    .push_layer(blend_mode, 1.0, vello::kurbo::Affine::IDENTITY, &bounds);
+                true
+            }
+        }
+    }
 }
 
 macro_rules! dispatch_to_fun {
@@ -313,7 +348,8 @@ impl Scene for VelloScene {
                 RoundedRect,
                 Circle,
                 Ellipse,
-                Line
+                Line,
+                Path
             }
         );
     }
@@ -326,18 +362,21 @@ impl Scene for VelloScene {
         &mut self,
         shape: Shape,
         brush: Brush,
+        fill_style: FillStyle,
         transform: Option<Affine>,
         blend_mode: Option<BlendMode>,
     ) {
+        let blend_layer = self.push_blend_layer(blend_mode);
+
         let vello_shape = shape.into();
         dispatch_to_fun!(
             vello_shape,
             |shape| {
                 self.vello_scene.fill(
-                    FillStyle::NonZero.into(),
+                    fill_style.into(),
                     transform.unwrap_or(Affine::identity()).into(),
                     &brush.as_brush_or_brushref(),
-                    None,
+                    brush.brush_transform(),
                     &shape,
                 );
             },
@@ -346,9 +385,14 @@ impl Scene for VelloScene {
                 RoundedRect,
                 Circle,
                 Ellipse,
-                Line
+                Line,
+                Path
             }
         );
+
+        if blend_layer {
+            self.vello_scene.pop_layer();
+        }
     }
 
     fn draw_shape_stroke(
@@ -359,7 +403,7 @@ impl Scene for VelloScene {
         transform: Option<Affine>,
         blend_mode: Option<BlendMode>,
     ) {
-        // TODO: if a blend mode is provided, we need to push a new layer
+        let blend_layer = self.push_blend_layer(blend_mode);
 
         let vello_shape = shape.into();
         dispatch_to_fun!(
@@ -369,7 +413,7 @@ impl Scene for VelloScene {
                     &style.into(),
                     transform.unwrap_or(Affine::identity()).into(),
                     &brush.as_brush_or_brushref(),
-                    None,
+                    brush.brush_transform(),
                     &shape,
                 );
             },
@@ -378,9 +422,14 @@ impl Scene for VelloScene {
                 RoundedRect,
                 Circle,
                 Ellipse,
-                Line
+                Line,
+                Path
             }
         );
+
+        if blend_layer {
+            self.vello_scene.pop_layer();
+        }
     }
 
     fn draw_glyphs(
@@ -471,8 +520,12 @@ pub enum VelloShape {
     Circle(vello::kurbo::Circle),
     Ellipse(vello::kurbo::Ellipse),
     Line(vello::kurbo::Line),
+    Path(vello::kurbo::BezPath),
 }
 
+/// Tolerance used when flattening arcs into Bézier paths.
+const PATH_TOLERANCE: f64 = 0.1;
+
 // allow converting Shape enum to VelloShape
 impl Into<VelloShape> for Shape {
     fn into(self) -> VelloShape {
@@ -493,8 +546,85 @@ impl Into<VelloShape> for Shape {
                 (radius_x, radius_y),
                 rotation.to_radians(),
             )),
+            Shape::Path { elements } => {
+                let mut path = vello::kurbo::BezPath::new();
+                for element in elements {
+                    match element {
+                        PathElement::MoveTo(p) => path.move_to(p),
+                        PathElement::LineTo(p) => path.line_to(p),
+                        PathElement::QuadTo(p1, p2) => path.quad_to(p1, p2),
+                        PathElement::CubicTo(p1, p2, p3) => path.curve_to(p1, p2, p3),
+                        PathElement::Close => path.close_path(),
+                    }
+                }
+                VelloShape::Path(path)
+            }
+            Shape::Polygon { points } => VelloShape::Path(polyline_to_bez_path(&points, true)),
+            Shape::Polyline { points } => VelloShape::Path(polyline_to_bez_path(&points, false)),
+            Shape::Arc {
+                center,
+                radius,
+                start_angle,
+                sweep_angle,
+            } => VelloShape::Path(
+                vello::kurbo::Arc::new(
+                    center,
+                    (radius, radius),
+                    start_angle.to_radians(),
+                    sweep_angle.to_radians(),
+                    0.0,
+                )
+                .into_path(PATH_TOLERANCE),
+            ),
+            Shape::Wedge {
+                center,
+                radius,
+                start_angle,
+                sweep_angle,
+            } => VelloShape::Path(
+                vello::kurbo::CircleSegment::new(
+                    center,
+                    radius,
+                    0.0,
+                    start_angle.to_radians(),
+                    sweep_angle.to_radians(),
+                )
+                .into_path(PATH_TOLERANCE),
+            ),
+            Shape::Annulus {
+                center,
+                inner_radius,
+                outer_radius,
+            } => {
+                // two closed circles of opposite winding, so the hole is cut with the non-zero
+                // fill rule and there is no seam where a segment would meet itself
+                let circle = |radius: f64, sweep: f64| {
+                    vello::kurbo::Arc::new(center, (radius, radius), 0.0, sweep, 0.0).path_elements(PATH_TOLERANCE)
+                };
+                let mut path = vello::kurbo::BezPath::new();
+                path.extend(circle(outer_radius, std::f64::consts::TAU));
+                path.close_path();
+                path.extend(circle(inner_radius, -std::f64::consts::TAU));
+                path.close_path();
+                VelloShape::Path(path)
+            }
+        }
+    }
+}
+
+fn polyline_to_bez_path(points: &[Point], closed: bool) -> vello::kurbo::BezPath {
+    let mut path = vello::kurbo::BezPath::new();
+    for (i, point) in points.iter().enumerate() {
+        if i == 0 {
+            path.move_to(*point);
+        } else {
+            path.line_to(*point);
         }
     }
+    if closed {
+        path.close_path();
+    }
+    path
 }
 
 // Point2D
@@ -527,7 +657,34 @@ impl From<StrokeStyle> for vello::kurbo::Stroke {
     fn from(style: StrokeStyle) -> Self {
         vello::kurbo::Stroke {
             width: style.width,
-            ..Default::default()
+            join: style.join.into(),
+            miter_limit: style.miter_limit,
+            start_cap: style.start_cap.into(),
+            end_cap: style.end_cap.into(),
+            dash_pattern: style.dash_pattern.iter().copied().collect(),
+            dash_offset: style.dash_offset,
+        }
+    }
+}
+
+// Join
+impl From<Join> for vello::kurbo::Join {
+    fn from(join: Join) -> Self {
+        match join {
+            Join::Bevel => vello::kurbo::Join::Bevel,
+            Join::Miter => vello::kurbo::Join::Miter,
+            Join::Round => vello::kurbo::Join::Round,
+        }
+    }
+}
+
+// Cap
+impl From<Cap> for vello::kurbo::Cap {
+    fn from(cap: Cap) -> Self {
+        match cap {
+            Cap::Butt => vello::kurbo::Cap::Butt,
+            Cap::Square => vello::kurbo::Cap::Square,
+            Cap::Round => vello::kurbo::Cap::Round,
         }
     }
 }
@@ -536,8 +693,23 @@ impl From<StrokeStyle> for vello::kurbo::Stroke {
 impl<'a> Brush<'_> {
     fn as_brush_or_brushref(&'a self) -> VelloBrushOrBrushRef<'a> {
         match self {
-            Brush::Image { .. } => {
-                todo!()
+            Brush::Image {
+                image,
+                sampling,
+                edge_mode,
+                alpha,
+                ..
+            } => {
+                let image = image
+                    .try_as::<VelloBitmap>()
+                    .expect("You're trying to use a non-vello image with a vello renderer")
+                    .image
+                    .clone()
+                    .with_x_extend(edge_mode.0.into())
+                    .with_y_extend(edge_mode.1.into())
+                    .with_quality((*sampling).into())
+                    .with_alpha(alpha.unwrap_or(1.0));
+                VelloBrushOrBrushRef::Brush(vello::peniko::Brush::Image(image))
             }
             Brush::Solid(rgba) => VelloBrushOrBrushRef::Brush(vello::peniko::Brush::Solid(rgba.clone().into())),
             Brush::Gradient(gradient) => {
@@ -548,6 +720,44 @@ impl<'a> Brush<'_> {
     }
 }
 
+impl Brush<'_> {
+    /// Returns the transform that maps the image of an image brush to the
+    /// shape, i.e., the fit mode and start point followed by the transform
+    /// of the brush. Other brushes are not transformed.
+    fn brush_transform(&self) -> Option<vello::kurbo::Affine> {
+        match self {
+            Brush::Image {
+                image,
+                start,
+                fit_mode,
+                transform,
+                ..
+            } => {
+                let image = &image
+                    .try_as::<VelloBitmap>()
+                    .expect("You're trying to use a non-vello image with a vello renderer")
+                    .image;
+
+                let local = match fit_mode {
+                    ImageFitMode::Original => vello::kurbo::Affine::IDENTITY,
+                    ImageFitMode::Exact { width, height } => {
+                        let scale_x = *width as f64 / image.width as f64;
+                        let scale_y = *height as f64 / image.height as f64;
+                        vello::kurbo::Affine::translate((start.x, start.y))
+                            * vello::kurbo::Affine::scale_non_uniform(scale_x, scale_y)
+                    }
+                };
+
+                match transform {
+                    Some(transform) => Some(vello::kurbo::Affine::from(*transform) * local),
+                    None => Some(local),
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
 /// A brush or a brush reference
 pub enum VelloBrushOrBrushRef<'a> {
     /// A brush brush
@@ -655,6 +865,18 @@ impl From<&Extend> for vello::peniko::Extend {
     }
 }
 
+// ImageSampling
+impl From<ImageSampling> for vello::peniko::ImageQuality {
+    fn from(sampling: ImageSampling) -> Self {
+        match sampling {
+            ImageSampling::Nearest => vello::peniko::ImageQuality::Low,
+            ImageSampling::Linear => vello::peniko::ImageQuality::Medium,
+            // vello selects bicubic filtering and mipmaps for the highest quality
+            ImageSampling::Cubic | ImageSampling::Mipmap => vello::peniko::ImageQuality::High,
+        }
+    }
+}
+
 // GradientKind
 impl From<GradientKind> for vello::peniko::GradientKind {
     fn from(kind: GradientKind) -> Self {
@@ -674,9 +896,10 @@ impl From<GradientKind> for vello::peniko::GradientKind {
                 start_angle,
                 end_angle,
             } => vello::peniko::GradientKind::Sweep {
+                // vello expects the angles in radians
                 center: center.into(),
-                start_angle,
-                end_angle,
+                start_angle: start_angle.to_radians(),
+                end_angle: end_angle.to_radians(),
             },
         }
     }
@@ -1,8 +1,6 @@
 use std::sync::Arc;
 
-use wgpu::{
-    util::DeviceExt, BindGroup, Buffer, Device, Instance, Queue, RenderPipeline, Surface, Texture, TextureFormat,
-};
+use wgpu::{BindGroup, Buffer, Device, Instance, Queue, RenderPipeline, Surface, Texture, TextureFormat};
 use winit::{dpi::PhysicalSize, window::Window};
 
 #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
@@ -12,6 +10,369 @@ pub struct GammaParams {
     g: [f32; 8],
     b: [f32; 8],
     correction: u32,
+    lut_size: u32,
+    encoding: u32,
+    dither: u32,
+    seed: u32,
+    frame: u32,
+    levels: f32,
+    temporal: u32,
+    color_space: u32,
+}
+
+/// The maximum number of entries of a lookup table.
+pub const MAX_LUT_SIZE: usize = 4096;
+
+/// The width and height of the (tiled) blue noise threshold map.
+const BLUE_NOISE_SIZE: u32 = 64;
+
+/// Gamma correction applied to every pixel before it is written to the
+/// surface. Each model stores one set of coefficients per color channel.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GammaCorrection {
+    /// No correction.
+    None,
+    /// A simple power law with the given exponent.
+    Gamma { r: f32, g: f32, b: f32 },
+    /// PsychoPy's scaled model, with coefficients `[a, k, gamma]`.
+    PsychoPy { r: [f32; 3], g: [f32; 3], b: [f32; 3] },
+    /// A 4th order polynomial in log space.
+    Polylog4 { r: [f32; 5], g: [f32; 5], b: [f32; 5] },
+    /// A 5th order polynomial in log space.
+    Polylog5 { r: [f32; 6], g: [f32; 6], b: [f32; 6] },
+    /// A 6th order polynomial in log space.
+    Polylog6 { r: [f32; 7], g: [f32; 7], b: [f32; 7] },
+    /// A lookup table per channel that maps evenly spaced linear values from 0
+    /// to 1 to output values. Values in between entries are interpolated
+    /// linearly.
+    Lut { r: Vec<f32>, g: Vec<f32>, b: Vec<f32> },
+}
+
+impl Default for GammaCorrection {
+    fn default() -> Self {
+        GammaCorrection::None
+    }
+}
+
+impl GammaCorrection {
+    /// Creates a correction from the name of the model and the coefficients for
+    /// each channel. Returns an error if the model is unknown or the number of
+    /// coefficients does not match the model.
+    pub fn from_coefficients(model: &str, r: &[f32], g: &[f32], b: &[f32]) -> Result<Self, String> {
+        fn channels<const N: usize>(model: &str, r: &[f32], g: &[f32], b: &[f32]) -> Result<[[f32; N]; 3], String> {
+            let convert = |channel: &str, c: &[f32]| {
+                <[f32; N]>::try_from(c).map_err(|_| {
+                    format!(
+                        "The {} model needs {} coefficients per channel, but {} were given for the {} channel.",
+                        model,
+                        N,
+                        c.len(),
+                        channel
+                    )
+                })
+            };
+            Ok([convert("red", r)?, convert("green", g)?, convert("blue", b)?])
+        }
+
+        match model {
+            "none" => Ok(GammaCorrection::None),
+            "gamma" => {
+                let [r, g, b] = channels::<1>(model, r, g, b)?;
+                Ok(GammaCorrection::Gamma {
+                    r: r[0],
+                    g: g[0],
+                    b: b[0],
+                })
+            }
+            "psychopy" => {
+                let [r, g, b] = channels(model, r, g, b)?;
+                Ok(GammaCorrection::PsychoPy { r, g, b })
+            }
+            "polylog4" => {
+                let [r, g, b] = channels(model, r, g, b)?;
+                Ok(GammaCorrection::Polylog4 { r, g, b })
+            }
+            "polylog5" => {
+                let [r, g, b] = channels(model, r, g, b)?;
+                Ok(GammaCorrection::Polylog5 { r, g, b })
+            }
+            "polylog6" => {
+                let [r, g, b] = channels(model, r, g, b)?;
+                Ok(GammaCorrection::Polylog6 { r, g, b })
+            }
+            "lut" => Self::lut(r.to_vec(), g.to_vec(), b.to_vec()),
+            _ => Err(format!(
+                "Unknown gamma correction model: {}. Expected one of none, gamma, psychopy, polylog4, polylog5, polylog6 or lut.",
+                model
+            )),
+        }
+    }
+
+    /// Creates a lookup table correction. All tables need to have the same
+    /// length, between 2 and `MAX_LUT_SIZE` entries.
+    pub fn lut(r: Vec<f32>, g: Vec<f32>, b: Vec<f32>) -> Result<Self, String> {
+        if r.len() != g.len() || r.len() != b.len() {
+            return Err(format!(
+                "The lookup tables of all channels need to have the same length, but got {}, {} and {} entries.",
+                r.len(),
+                g.len(),
+                b.len()
+            ));
+        }
+
+        if r.len() < 2 || r.len() > MAX_LUT_SIZE {
+            return Err(format!(
+                "A lookup table needs between 2 and {} entries, but got {}.",
+                MAX_LUT_SIZE,
+                r.len()
+            ));
+        }
+
+        Ok(GammaCorrection::Lut { r, g, b })
+    }
+
+    /// Creates a lookup table correction with `size` entries per channel by
+    /// evaluating the given function for each channel (0: red, 1: green,
+    /// 2: blue) at evenly spaced values from 0 to 1.
+    pub fn lut_from_fn(size: usize, f: impl Fn(usize, f32) -> f32) -> Result<Self, String> {
+        let table = |channel: usize| {
+            (0..size)
+                .map(|i| f(channel, i as f32 / (size.max(2) - 1) as f32))
+                .collect::<Vec<f32>>()
+        };
+        Self::lut(table(0), table(1), table(2))
+    }
+
+    /// The name of the model.
+    pub fn model(&self) -> &'static str {
+        match self {
+            GammaCorrection::None => "none",
+            GammaCorrection::Gamma { .. } => "gamma",
+            GammaCorrection::PsychoPy { .. } => "psychopy",
+            GammaCorrection::Polylog4 { .. } => "polylog4",
+            GammaCorrection::Polylog5 { .. } => "polylog5",
+            GammaCorrection::Polylog6 { .. } => "polylog6",
+            GammaCorrection::Lut { .. } => "lut",
+        }
+    }
+
+    /// The coefficients of the red, green and blue channel.
+    pub fn coefficients(&self) -> [Vec<f32>; 3] {
+        match self {
+            GammaCorrection::None => [vec![], vec![], vec![]],
+            GammaCorrection::Gamma { r, g, b } => [vec![*r], vec![*g], vec![*b]],
+            GammaCorrection::PsychoPy { r, g, b } => [r.to_vec(), g.to_vec(), b.to_vec()],
+            GammaCorrection::Polylog4 { r, g, b } => [r.to_vec(), g.to_vec(), b.to_vec()],
+            GammaCorrection::Polylog5 { r, g, b } => [r.to_vec(), g.to_vec(), b.to_vec()],
+            GammaCorrection::Polylog6 { r, g, b } => [r.to_vec(), g.to_vec(), b.to_vec()],
+            GammaCorrection::Lut { r, g, b } => [r.clone(), g.clone(), b.clone()],
+        }
+    }
+
+    /// The mode of the correction in `render.wgsl`.
+    fn shader_mode(&self) -> u32 {
+        match self {
+            GammaCorrection::None => 0,
+            GammaCorrection::PsychoPy { .. } => 1,
+            GammaCorrection::Polylog4 { .. } => 2,
+            GammaCorrection::Polylog5 { .. } => 3,
+            GammaCorrection::Polylog6 { .. } => 4,
+            GammaCorrection::Gamma { .. } => 5,
+            GammaCorrection::Lut { .. } => 6,
+        }
+    }
+
+    /// The texels of the lookup table texture, or `None` if this is not a
+    /// lookup table correction.
+    fn lut_texels(&self) -> Option<Vec<[f32; 4]>> {
+        match self {
+            GammaCorrection::Lut { r, g, b } => {
+                Some(r.iter().zip(g).zip(b).map(|((&r, &g), &b)| [r, g, b, 1.0]).collect())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// How the (gamma corrected) output is encoded into the 8-bit channels of the
+/// surface. The encodings allow more than 256 grey levels on 8-bit displays or
+/// external video attenuators.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OutputEncoding {
+    /// The output is written as is.
+    #[default]
+    None,
+    /// Bit-stealing (Tyler, 1997): small offsets between the channels add
+    /// intermediate luminance steps for grey values.
+    PseudoGray,
+    /// The red channel is encoded with 16 bits into the red (high byte) and
+    /// green (low byte) channels, as used by the Mono++ mode of Bits# devices.
+    MonoPlusPlus,
+    /// Each pair of horizontally adjacent pixels encodes one pixel with 16 bits
+    /// per channel, as used by the Color++ mode of Bits# devices. This halves
+    /// the horizontal resolution.
+    ColorPlusPlus,
+}
+
+impl OutputEncoding {
+    /// Creates an encoding from its name. Returns an error if the name is
+    /// unknown.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "none" => Ok(OutputEncoding::None),
+            "pseudo_gray" => Ok(OutputEncoding::PseudoGray),
+            "mono++" => Ok(OutputEncoding::MonoPlusPlus),
+            "color++" => Ok(OutputEncoding::ColorPlusPlus),
+            _ => Err(format!(
+                "Unknown output encoding: {}. Expected one of none, pseudo_gray, mono++ or color++.",
+                name
+            )),
+        }
+    }
+
+    /// The name of the encoding.
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputEncoding::None => "none",
+            OutputEncoding::PseudoGray => "pseudo_gray",
+            OutputEncoding::MonoPlusPlus => "mono++",
+            OutputEncoding::ColorPlusPlus => "color++",
+        }
+    }
+
+    /// The mode of the encoding in `render.wgsl`.
+    fn shader_mode(&self) -> u32 {
+        match self {
+            OutputEncoding::None => 0,
+            OutputEncoding::PseudoGray => 1,
+            OutputEncoding::MonoPlusPlus => 2,
+            OutputEncoding::ColorPlusPlus => 3,
+        }
+    }
+}
+
+/// The spatial pattern that is used to dither the output before it is quantized
+/// to the bit depth of the surface.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DitherPattern {
+    /// No spatial dithering.
+    #[default]
+    None,
+    /// An 8x8 ordered (Bayer) threshold matrix.
+    Bayer,
+    /// A 64x64 blue noise threshold map, which has no visible structure.
+    BlueNoise,
+}
+
+impl DitherPattern {
+    /// Creates a pattern from its name. Returns an error if the name is
+    /// unknown.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "none" => Ok(DitherPattern::None),
+            "bayer" => Ok(DitherPattern::Bayer),
+            "blue_noise" => Ok(DitherPattern::BlueNoise),
+            _ => Err(format!(
+                "Unknown dither pattern: {}. Expected one of none, bayer or blue_noise.",
+                name
+            )),
+        }
+    }
+
+    /// The name of the pattern.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DitherPattern::None => "none",
+            DitherPattern::Bayer => "bayer",
+            DitherPattern::BlueNoise => "blue_noise",
+        }
+    }
+
+    /// The mode of the pattern in `render.wgsl`.
+    fn shader_mode(&self) -> u32 {
+        match self {
+            DitherPattern::None => 0,
+            DitherPattern::Bayer => 1,
+            DitherPattern::BlueNoise => 2,
+        }
+    }
+}
+
+/// Dithering of the (gamma corrected) output. The rendered frame has more
+/// precision than the surface, so instead of rounding each pixel to the
+/// closest level, pixels are rounded up or down depending on a threshold
+/// pattern. On average, this reproduces the intermediate levels.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Dithering {
+    /// The spatial threshold pattern.
+    pub pattern: DitherPattern,
+    /// If true, the thresholds change with every rendered frame, so that
+    /// intermediate levels are also reproduced over time.
+    pub temporal: bool,
+    /// Shifts the pattern, so that different seeds give different (but
+    /// reproducible) patterns.
+    pub seed: u32,
+}
+
+impl Dithering {
+    /// Returns true if the output is dithered at all.
+    pub fn is_enabled(&self) -> bool {
+        self.pattern != DitherPattern::None || self.temporal
+    }
+}
+
+/// The color space of the surface. Frames are rendered in linear sRGB, so
+/// for other color spaces the output pass converts the colors before the
+/// gamma correction is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OutputColorSpace {
+    /// The surface expects sRGB primaries.
+    #[default]
+    Srgb,
+    /// The surface expects Display P3 primaries (with a D65 white point).
+    DisplayP3,
+}
+
+impl OutputColorSpace {
+    /// The mode of the color space in `render.wgsl`.
+    fn shader_mode(&self) -> u32 {
+        match self {
+            OutputColorSpace::Srgb => 0,
+            OutputColorSpace::DisplayP3 => 1,
+        }
+    }
+}
+
+impl From<&GammaCorrection> for GammaParams {
+    fn from(correction: &GammaCorrection) -> Self {
+        // the shader reads the coefficients of each channel as a, b, c, ...
+        let pad = |c: &[f32]| {
+            let mut padded = [0.0; 8];
+            padded[..c.len()].copy_from_slice(c);
+            padded
+        };
+
+        // lookup tables are uploaded to a texture instead
+        let (lut_size, [r, g, b]) = match correction {
+            GammaCorrection::Lut { r, .. } => (r.len() as u32, Default::default()),
+            _ => (0, correction.coefficients()),
+        };
+
+        GammaParams {
+            r: pad(&r),
+            g: pad(&g),
+            b: pad(&b),
+            correction: correction.shader_mode(),
+            lut_size,
+            encoding: OutputEncoding::None.shader_mode(),
+            dither: DitherPattern::None.shader_mode(),
+            seed: 0,
+            frame: 0,
+            levels: 255.0,
+            temporal: 0,
+            color_space: OutputColorSpace::Srgb.shader_mode(),
+        }
+    }
 }
 
 pub struct WgpuRenderer {
@@ -19,6 +380,14 @@ pub struct WgpuRenderer {
     render_pipeline: RenderPipeline,
     texture: Texture,
     gamma_buffer: Buffer,
+    lut_texture: Texture,
+    gamma_correction: GammaCorrection,
+    output_encoding: OutputEncoding,
+    output_color_space: OutputColorSpace,
+    blue_noise_texture: Texture,
+    has_blue_noise: bool,
+    dithering: Dithering,
+    frame: u32,
     bind_group: BindGroup,
     size: PhysicalSize<u32>,
 }
@@ -28,8 +397,9 @@ impl WgpuRenderer {
         window: Arc<Window>,
         _instance: &Instance,
         device: &Device,
-        _queue: &Queue,
+        queue: &Queue,
         surface_format: TextureFormat,
+        output_color_space: OutputColorSpace,
     ) -> Self {
         let size = window.inner_size();
         let (width, height) = (size.width, size.height);
@@ -38,16 +408,159 @@ impl WgpuRenderer {
         let render_pipeline = Self::create_render_pipelie(&device, surface_format);
         let texture = Self::create_texture(&device, width, height);
         let gamma_buffer = Self::create_uniform_buffer(&device);
-        let bind_group = Self::create_bind_group(&device, &texture);
+        let lut_texture = Self::create_lut_texture(&device);
+        let blue_noise_texture = Self::create_blue_noise_texture(&device);
+        let bind_group = Self::create_bind_group(&device, &texture, &gamma_buffer, &lut_texture, &blue_noise_texture);
 
-        Self {
+        let mut renderer = Self {
             surface_format,
             render_pipeline,
             texture,
             gamma_buffer,
+            lut_texture,
+            gamma_correction: GammaCorrection::default(),
+            output_encoding: OutputEncoding::default(),
+            output_color_space,
+            blue_noise_texture,
+            has_blue_noise: false,
+            dithering: Dithering::default(),
+            frame: 0,
             bind_group,
             size,
+        };
+
+        renderer.write_params(queue);
+        renderer
+    }
+
+    /// Returns the current gamma correction.
+    pub fn gamma_correction(&self) -> &GammaCorrection {
+        &self.gamma_correction
+    }
+
+    /// Sets the gamma correction. Takes effect with the next rendered frame.
+    /// Floating point surfaces expect linear values, so the correction is
+    /// only available for surfaces with normalized integer formats. Returns an
+    /// error if a correction is set for a floating point surface.
+    pub fn set_gamma_correction(&mut self, queue: &Queue, gamma_correction: GammaCorrection) -> Result<(), String> {
+        if self.has_linear_output() && gamma_correction != GammaCorrection::None {
+            return Err(format!(
+                "Gamma correction is not available for surfaces with the floating point format {:?}.",
+                self.surface_format
+            ));
         }
+
+        if let Some(texels) = gamma_correction.lut_texels() {
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &self.lut_texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                bytemuck::cast_slice(&texels),
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: None,
+                    rows_per_image: None,
+                },
+                wgpu::Extent3d {
+                    width: texels.len() as u32,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+
+        self.gamma_correction = gamma_correction;
+        self.write_params(queue);
+        Ok(())
+    }
+
+    /// Returns the current output encoding.
+    pub fn output_encoding(&self) -> OutputEncoding {
+        self.output_encoding
+    }
+
+    /// Sets the output encoding. Takes effect with the next rendered frame.
+    /// The encodings pack their values into 8-bit channels, so they are only
+    /// available for surfaces with 8-bit normalized integer formats. Returns
+    /// an error if an encoding is set for any other surface.
+    pub fn set_output_encoding(&mut self, queue: &Queue, output_encoding: OutputEncoding) -> Result<(), String> {
+        if !self.has_8bit_output() && output_encoding != OutputEncoding::None {
+            return Err(format!(
+                "The output encoding {} is only available for surfaces with 8-bit formats, not {:?}.",
+                output_encoding.name(),
+                self.surface_format
+            ));
+        }
+
+        self.output_encoding = output_encoding;
+        self.write_params(queue);
+        Ok(())
+    }
+
+    /// Returns the current dithering.
+    pub fn dithering(&self) -> Dithering {
+        self.dithering
+    }
+
+    /// Sets the dithering. Takes effect with the next rendered frame. The
+    /// output is only dithered for surfaces with normalized integer formats
+    /// and without an output encoding (which has its own way of reproducing
+    /// intermediate levels).
+    pub fn set_dithering(&mut self, queue: &Queue, dithering: Dithering) {
+        // the blue noise map is expensive to generate, so only do it once it is used
+        if dithering.pattern == DitherPattern::BlueNoise && !self.has_blue_noise {
+            let size = BLUE_NOISE_SIZE as usize;
+            let thresholds = crate::blue_noise::blue_noise(size, 0x5eed);
+
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &self.blue_noise_texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                bytemuck::cast_slice(&thresholds),
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(BLUE_NOISE_SIZE * 4),
+                    rows_per_image: None,
+                },
+                wgpu::Extent3d {
+                    width: BLUE_NOISE_SIZE,
+                    height: BLUE_NOISE_SIZE,
+                    depth_or_array_layers: 1,
+                },
+            );
+            self.has_blue_noise = true;
+        }
+
+        self.dithering = dithering;
+        self.write_params(queue);
+    }
+
+    fn write_params(&self, queue: &Queue) {
+        let mut params = GammaParams::from(&self.gamma_correction);
+        params.color_space = self.output_color_space.shader_mode();
+
+        if !self.has_linear_output() {
+            params.encoding = self.output_encoding.shader_mode();
+            params.levels = match self.surface_format {
+                TextureFormat::Rgb10a2Unorm => 1023.0,
+                _ => 255.0,
+            };
+        }
+
+        if !self.has_linear_output() && self.output_encoding == OutputEncoding::None {
+            params.dither = self.dithering.pattern.shader_mode();
+            params.temporal = self.dithering.temporal as u32;
+            params.seed = self.dithering.seed;
+            params.frame = self.frame;
+        }
+
+        queue.write_buffer(&self.gamma_buffer, 0, bytemuck::cast_slice(&[params]));
     }
 
     pub fn width(&self) -> u32 {
@@ -66,6 +579,31 @@ impl WgpuRenderer {
         self.surface_format
     }
 
+    /// Returns the color space of the surface.
+    pub fn output_color_space(&self) -> OutputColorSpace {
+        self.output_color_space
+    }
+
+    /// Returns true if the surface expects linear (and possibly extended range)
+    /// values, i.e., if it has a floating point format.
+    pub fn has_linear_output(&self) -> bool {
+        matches!(
+            self.surface_format,
+            TextureFormat::Rgba16Float | TextureFormat::Rgba32Float
+        )
+    }
+
+    /// Returns true if the surface has an 8-bit normalized integer format.
+    pub fn has_8bit_output(&self) -> bool {
+        matches!(
+            self.surface_format,
+            TextureFormat::Rgba8Unorm
+                | TextureFormat::Rgba8UnormSrgb
+                | TextureFormat::Bgra8Unorm
+                | TextureFormat::Bgra8UnormSrgb
+        )
+    }
+
     pub fn configure_surface(&self, surface: &Surface, device: &Device) {
         let surface_config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
@@ -85,7 +623,13 @@ impl WgpuRenderer {
     pub fn resize(&mut self, width: u32, height: u32, surface: &Surface, device: &Device) {
         self.size = winit::dpi::PhysicalSize::new(width, height);
         self.texture = Self::create_texture(device, width, height);
-        self.bind_group = Self::create_bind_group(device, &self.texture);
+        self.bind_group = Self::create_bind_group(
+            device,
+            &self.texture,
+            &self.gamma_buffer,
+            &self.lut_texture,
+            &self.blue_noise_texture,
+        );
         self.configure_surface(surface, device);
     }
 
@@ -106,6 +650,40 @@ impl WgpuRenderer {
         })
     }
 
+    fn create_lut_texture(device: &wgpu::Device) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width: MAX_LUT_SIZE as u32,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D1,
+            format: wgpu::TextureFormat::Rgba32Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            label: Some("Gamma LUT Texture"),
+            view_formats: &[wgpu::TextureFormat::Rgba32Float],
+        })
+    }
+
+    fn create_blue_noise_texture(device: &wgpu::Device) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width: BLUE_NOISE_SIZE,
+                height: BLUE_NOISE_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R32Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            label: Some("Blue Noise Texture"),
+            view_formats: &[wgpu::TextureFormat::R32Float],
+        })
+    }
+
     fn create_uniform_buffer(device: &wgpu::Device) -> wgpu::Buffer {
         device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Gamma Buffer"),
@@ -115,7 +693,13 @@ impl WgpuRenderer {
         })
     }
 
-    fn create_bind_group(device: &wgpu::Device, texture: &wgpu::Texture) -> wgpu::BindGroup {
+    fn create_bind_group(
+        device: &wgpu::Device,
+        texture: &wgpu::Texture,
+        gamma_buffer: &wgpu::Buffer,
+        lut_texture: &wgpu::Texture,
+        blue_noise_texture: &wgpu::Texture,
+    ) -> wgpu::BindGroup {
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("Render Bind Group Layout"),
             entries: &[
@@ -139,6 +723,26 @@ impl WgpuRenderer {
                     },
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D1,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -155,47 +759,23 @@ impl WgpuRenderer {
                 wgpu::BindGroupEntry {
                     binding: 1,
                     resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                        buffer: &device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                            label: Some("Gamma Buffer"),
-                            contents: bytemuck::cast_slice(&[GammaParams {
-                                correction: 0, // 0: none, 1: psychopy, 2: polylog4, 3: polylog5, 4: polylog6
-                                r: [
-                                    0.9972361456765942,
-                                    0.5718201120693766,
-                                    0.1494526003308258,
-                                    0.021348959590415988,
-                                    0.0016066519145011171,
-                                    4.956890077371443e-05,
-                                    0.0,
-                                    0.0,
-                                ],
-                                g: [
-                                    1.0058002029776596,
-                                    0.5695706025327177,
-                                    0.14551632725612368,
-                                    0.020115266744271217,
-                                    0.0014548822571441762,
-                                    4.3086307473990124e-05,
-                                    0.0,
-                                    0.0,
-                                ],
-                                b: [
-                                    1.0116733520722856,
-                                    0.5329488652553003,
-                                    0.11728724922990535,
-                                    0.012259928984426039,
-                                    0.000528402626505164,
-                                    4.086604661837748e-06,
-                                    0.0,
-                                    0.0,
-                                ],
-                            }]),
-                            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-                        }),
+                        buffer: gamma_buffer,
                         offset: 0,
                         size: None,
                     }),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(
+                        &lut_texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                    ),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(
+                        &blue_noise_texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                    ),
+                },
             ],
         })
     }
@@ -230,6 +810,26 @@ impl WgpuRenderer {
                     },
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D1,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -283,6 +883,12 @@ impl WgpuRenderer {
     }
 
     pub fn render_to_texture(&mut self, device: &Device, queue: &Queue, texture_view: &wgpu::TextureView) {
+        // temporal dithering changes the thresholds with every frame
+        self.frame = self.frame.wrapping_add(1);
+        if self.dithering.temporal {
+            self.write_params(queue);
+        }
+
         // create a new render pass
         let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Render Encoder"),